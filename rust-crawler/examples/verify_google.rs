@@ -11,7 +11,7 @@ async fn main() -> anyhow::Result<()> {
     
     // 1. Run Search
     println!("🔎 Searching for: {}", keyword);
-    let result = crawler::search_google(keyword).await;
+    let result = crawler::search_google(keyword, &crawler::SearchOptions::default()).await;
     
     match result {
         Ok(data) => {
@@ -24,7 +24,7 @@ async fn main() -> anyhow::Result<()> {
             
             if let Some(first_result) = data.results.first() {
                 println!("🌐 Visiting first result: {}", first_result.link);
-                match crawler::extract_website_data(&first_result.link).await {
+                match crawler::extract_website_data(&first_result.link, &crawler::ExtractOptions::default()).await {
                     Ok(site_data) => {
                         println!("✅ Extraction SUCCESS!");
                         println!("Title: {}", site_data.title);
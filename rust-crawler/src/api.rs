@@ -1,11 +1,13 @@
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, State},
     Json,
     http::StatusCode,
+    response::{IntoResponse, Response, sse::{Event as SseEvent, KeepAlive, Sse}},
 };
 use serde::{Deserialize, Serialize};
 use sqlx::PgPool;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
 use uuid::Uuid;
 use crate::crawler;
 use utoipa::{ToSchema, OpenApi};
@@ -17,18 +19,226 @@ use crate::queue::QueueManager;
 #[derive(Clone)]
 pub struct AppState {
     pub pool: PgPool,
-    pub storage: StorageManager,
+    /// None when MinIO is unavailable and STORAGE_REQUIRED=false
+    pub storage: Option<StorageManager>,
     pub queue: QueueManager,
+    /// Set via /admin/worker/pause; the worker stops popping jobs while true
+    pub worker_paused: Arc<AtomicBool>,
 }
 
-#[derive(Deserialize, ToSchema)]
+#[derive(Deserialize, ToSchema, Default)]
 pub struct CrawlRequest {
     #[schema(example = "rust programming")]
     pub keyword: String,
     #[schema(example = "bing", default = "bing")]
     pub engine: Option<String>,
-    #[schema(example = "{\"title\": \"h1\", \"content\": \".post-body\"}")]
+    /// Generic engine: name -> CSS selector to extract. The reserved `__wait_for`
+    /// key names a selector to wait for before extracting.
+    #[schema(example = "{\"title\": \"h1\", \"content\": \".post-body\", \"__wait_for\": \".post-body\"}")]
     pub selectors: Option<std::collections::HashMap<String, String>>, 
+    /// Engine-specific URL parameters, whitelisted and value-checked per engine
+    /// (google: tbs, tbm, lr, cr, gl, hl, safe, num, filter; bing and any other engine
+    /// crawled via Bing: freshness, qft, filters, cc, setlang, mkt, safesearch, count)
+    #[schema(example = "{\"tbs\": \"qdr:w\"}")]
+    pub engine_options: Option<std::collections::HashMap<String, String>>,
+    /// Keep only results whose URL matches one of these patterns (glob on host/URL, or `re:<regex>`)
+    #[schema(example = json!(["*.gov", "*.edu"]))]
+    pub result_include_patterns: Option<Vec<String>>,
+    /// Drop results whose URL matches any of these patterns
+    #[schema(example = json!(["*pinterest.*", "*quora.com"]))]
+    pub result_exclude_patterns: Option<Vec<String>>,
+    /// Capture a full-page screenshot of the deep-extracted page and store it as the task thumbnail
+    #[schema(example = false, default = false)]
+    pub capture_thumbnail: Option<bool>,
+    /// Store a full-page PNG of every deep-extracted page in MinIO
+    /// (`pages[].screenshot_key` in `results_json`)
+    #[schema(example = false, default = false)]
+    pub screenshot: Option<bool>,
+    /// Visit a neutral page before searching to look less like a cold bot session
+    /// (defaults to SEARCH_WARMUP, false if unset)
+    #[schema(example = true)]
+    pub warmup: Option<bool>,
+    /// After this crawl completes, queue a child crawl for each related search
+    /// (up to RELATED_CRAWL_MAX, default 5). Children are not expanded further.
+    #[schema(example = false, default = false)]
+    pub crawl_related: Option<bool>,
+    /// `Referer` header for the deep-extraction page load
+    #[schema(example = "https://www.google.com/")]
+    pub referer: Option<String>,
+    /// Arbitrary client metadata (client_id, campaign, ...) stored with the task and echoed back.
+    /// Does not affect crawling. Max METADATA_MAX_BYTES (default 16KB) serialized.
+    #[schema(example = json!({"client_id": "acme", "campaign": "q3-launch"}))]
+    pub metadata: Option<serde_json::Value>,
+    /// Generic engine only: CSS selectors clicked in order (waiting for the DOM to
+    /// settle after each) to reach an SPA view before extracting
+    #[schema(example = json!(["nav a[href='/pricing']", "button.show-all"]))]
+    pub click_sequence: Option<Vec<String>>,
+    /// Fairness group (campaign/batch name). With FAIR_QUEUEING=true the worker
+    /// round-robins across tags; untagged jobs are grouped per user.
+    #[schema(example = "campaign-q3")]
+    pub tag: Option<String>,
+    /// Search retry budget (1-10); defaults to GOOGLE_MAX_ATTEMPTS / BING_MAX_ATTEMPTS
+    #[schema(example = 3)]
+    pub max_attempts: Option<u32>,
+    /// Deep-extract the first result's page; false stores only the SERP (faster, fewer proxy hits)
+    #[schema(example = true, default = true)]
+    pub deep_crawl: Option<bool>,
+    /// Groups jobs submitted together (e.g. a keyword set) so their results can be
+    /// collapsed via GET /batch/{batch_id}/unique-results. Chosen by the client.
+    #[schema(example = "competitors-2024-06")]
+    pub batch_id: Option<String>,
+    /// Queue tier (low | normal | high). Waiting jobs are aged up every PRIORITY_AGING_SECS.
+    /// Ignored under FAIR_QUEUEING.
+    pub priority: Option<crate::queue::JobPriority>,
+    /// Object-storage prefix for this crawl's HTML/thumbnail (tenant isolation).
+    /// Must be within STORAGE_PREFIX_ALLOWLIST; rejected when the allowlist is empty.
+    #[schema(example = "tenants/acme")]
+    pub storage_prefix: Option<String>,
+    /// Extract organization/location/date mentions from the deep-crawled page
+    /// (defaults to EXTRACT_ENTITIES, false if unset; adds CPU time per page)
+    #[schema(example = false)]
+    pub extract_entities: Option<bool>,
+    /// Browser fingerprint override (e.g. WebGL vendor/renderer matching a known User-Agent).
+    /// When omitted, values come from the profile of each launch's rotated User-Agent.
+    pub stealth: Option<crate::stealth::StealthConfig>,
+    /// Deep-extract the top N results (1-10, default 1), at most DEEP_EXTRACT_CONCURRENCY at a time
+    #[schema(example = 3, default = 1)]
+    pub max_deep_extract: Option<u32>,
+    /// SERP pages to scrape (1-10, default 1) by following the engine's "Next" link.
+    /// Results are deduplicated by link; a challenge on a later page keeps what was gathered.
+    #[schema(example = 2, default = 1)]
+    pub pages: Option<u32>,
+    /// Keep the search and every deep-extracted page on one proxy, so session
+    /// cookies survive across pages (default false = rotate per browser)
+    #[schema(default = false)]
+    pub sticky: Option<bool>,
+    /// Fetch each result site's robots.txt before deep extraction and skip disallowed
+    /// URLs (listed in `results_json.robots_disallowed`). Default false.
+    #[schema(default = false)]
+    pub respect_robots: Option<bool>,
+    /// POST a JSON summary here when the task completes or finally fails, signed with
+    /// WEBHOOK_SECRET in `X-Signature: sha256=<hex HMAC>` when that is set. Loopback,
+    /// private and link-local hosts are rejected unless WEBHOOK_ALLOW_PRIVATE=true
+    #[schema(example = "https://example.com/hooks/crawl")]
+    pub callback_url: Option<String>,
+}
+
+/// A single rejected request field
+#[derive(Serialize, ToSchema, Debug, PartialEq)]
+pub struct FieldError {
+    #[schema(example = "keyword")]
+    pub field: String,
+    #[schema(example = "must not be empty")]
+    pub message: String,
+}
+
+/// 422 body listing every invalid field
+#[derive(Serialize, ToSchema)]
+pub struct ValidationErrorResponse {
+    pub errors: Vec<FieldError>,
+}
+
+impl CrawlRequest {
+    /// Reject requests that would only queue garbage jobs.
+    /// Limits: MAX_KEYWORD_LENGTH (default 500 chars), MAX_SELECTORS (default 50).
+    pub fn validate(&self) -> Result<(), Vec<FieldError>> {
+        let max_keyword_len = crate::config::env_parse("MAX_KEYWORD_LENGTH", 500usize);
+        let max_selectors = crate::config::env_parse("MAX_SELECTORS", 50usize);
+        let mut errors = Vec::new();
+        let mut reject = |field: &str, message: String| errors.push(FieldError { field: field.to_string(), message });
+
+        if self.keyword.trim().is_empty() {
+            reject("keyword", "must not be empty".to_string());
+        } else if self.keyword.chars().count() > max_keyword_len {
+            reject("keyword", format!("must be at most {} characters", max_keyword_len));
+        }
+
+        if let Some(ref selectors) = self.selectors {
+            if selectors.len() > max_selectors {
+                reject("selectors", format!("must contain at most {} entries", max_selectors));
+            }
+            if selectors.iter().any(|(k, v)| k.trim().is_empty() || v.trim().is_empty()) {
+                reject("selectors", "names and selectors must not be empty".to_string());
+            }
+        }
+
+        if let Some(ref referer) = self.referer {
+            let valid = reqwest::Url::parse(referer)
+                .map(|u| u.scheme() == "http" || u.scheme() == "https")
+                .unwrap_or(false);
+            if !valid {
+                reject("referer", "must be an absolute http(s) URL".to_string());
+            }
+        }
+
+        if let Some(ref callback_url) = self.callback_url {
+            match reqwest::Url::parse(callback_url) {
+                Ok(u) if u.scheme() == "http" || u.scheme() == "https" => {
+                    if let Err(e) = crate::webhook::check_callback_host(&u) {
+                        reject("callback_url", e);
+                    }
+                }
+                _ => reject("callback_url", "must be an absolute http(s) URL".to_string()),
+            }
+        }
+
+        if let Some(ref steps) = self.click_sequence {
+            if steps.len() > max_selectors {
+                reject("click_sequence", format!("must contain at most {} steps", max_selectors));
+            }
+            if steps.iter().any(|s| scraper::Selector::parse(s).is_err()) {
+                reject("click_sequence", "every step must be a valid CSS selector".to_string());
+            }
+        }
+
+        if let Some(attempts) = self.max_attempts {
+            if !(1..=crawler::MAX_SEARCH_ATTEMPTS).contains(&attempts) {
+                reject("max_attempts", format!("must be between 1 and {}", crawler::MAX_SEARCH_ATTEMPTS));
+            }
+        }
+
+        if let Some(pages) = self.max_deep_extract {
+            if !(1..=crawler::MAX_DEEP_EXTRACT).contains(&pages) {
+                reject("max_deep_extract", format!("must be between 1 and {}", crawler::MAX_DEEP_EXTRACT));
+            }
+        }
+
+        if let Some(pages) = self.pages {
+            if !(1..=crawler::MAX_SERP_PAGES).contains(&pages) {
+                reject("pages", format!("must be between 1 and {}", crawler::MAX_SERP_PAGES));
+            }
+        }
+
+        if let Some(ref batch_id) = self.batch_id {
+            if batch_id.trim().is_empty() || batch_id.len() > 128 {
+                reject("batch_id", "must be 1-128 characters".to_string());
+            }
+        }
+
+        if let Some(ref prefix) = self.storage_prefix {
+            if let Err(e) = crate::storage::validate_storage_prefix(prefix, &crate::storage::storage_prefix_allowlist()) {
+                reject("storage_prefix", e);
+            }
+        }
+
+        if let Some(ref stealth) = self.stealth {
+            if stealth.hardware_concurrency.is_some_and(|c| !(1..=crate::stealth::MAX_HARDWARE_CONCURRENCY).contains(&c)) {
+                reject("stealth", format!("hardware_concurrency must be between 1 and {}", crate::stealth::MAX_HARDWARE_CONCURRENCY));
+            }
+            if stealth.webgl_vendor.trim().is_empty() || stealth.webgl_renderer.trim().is_empty() {
+                reject("stealth", "webgl_vendor and webgl_renderer must not be empty".to_string());
+            }
+        }
+
+        if let Some(ref metadata) = self.metadata {
+            let max_metadata = crate::config::env_parse("METADATA_MAX_BYTES", 16 * 1024usize);
+            if metadata.to_string().len() > max_metadata {
+                reject("metadata", format!("must be at most {} bytes when serialized", max_metadata));
+            }
+        }
+
+        if errors.is_empty() { Ok(()) } else { Err(errors) }
+    }
 }
 
 #[derive(Serialize, ToSchema)]
@@ -47,16 +257,51 @@ pub struct TaskResult {
     pub keyword: String,
     #[schema(example = "bing")]
     pub engine: String,
+    /// pending, running, completed or failed
     #[schema(example = "completed")]
     pub status: String,
     pub results_json: Option<String>,
     pub extracted_text: Option<String>,
+    /// The deep-extracted article as Markdown (headings, lists and links preserved)
+    pub extracted_markdown: Option<String>,
     pub first_page_html: Option<String>,
     pub meta_description: Option<String>,
     pub meta_author: Option<String>,
     pub meta_date: Option<String>,
     pub entities: Option<serde_json::Value>,
     pub category: Option<String>,
+    /// MinIO key of the page thumbnail (when `capture_thumbnail` was requested)
+    pub thumbnail_key: Option<String>,
+    /// MinIO key of the full results JSON (`results_json` before RESULT_MAX_BYTES trimming)
+    pub results_key: Option<String>,
+    /// Task whose related searches spawned this one (`crawl_related`)
+    pub parent_task_id: Option<String>,
+    /// Client metadata passed through from the crawl request
+    pub metadata: Option<serde_json::Value>,
+    /// Deep-extracted page's redirect hops: [{url, status}, ...], requested URL first
+    pub redirect_chain: Option<serde_json::Value>,
+    /// Why the crawl (or its deep extraction) failed
+    pub error_message: Option<String>,
+    /// challenge_detected, page_too_small, no_results_found, extract_failed or robots_disallowed.
+    /// extract_failed tasks are `failed` but keep their SERP in `results_json`.
+    #[schema(example = "challenge_detected")]
+    pub failure_reason: Option<String>,
+    /// When the task was stored (UTC)
+    pub crawled_at: Option<NaiveDateTime>,
+    /// Seconds since `crawled_at`
+    #[sqlx(skip)]
+    pub age_seconds: Option<i64>,
+    /// Older than RESULT_FRESHNESS_SECS (default 86400)
+    #[sqlx(skip)]
+    pub stale: bool,
+}
+
+impl TaskResult {
+    /// Fill `age_seconds` / `stale` relative to `now`
+    pub fn apply_freshness(&mut self, now: NaiveDateTime, freshness_secs: i64) {
+        self.age_seconds = self.crawled_at.map(|t| (now - t).num_seconds().max(0));
+        self.stale = self.age_seconds.map(|age| age > freshness_secs).unwrap_or(false);
+    }
 }
 
 #[derive(Serialize, sqlx::FromRow, utoipa::ToSchema)]
@@ -68,6 +313,8 @@ pub struct TaskSummary {
     pub created_at: Option<chrono::NaiveDateTime>,
     pub results_json: Option<String>,
     pub extracted_text: Option<String>,
+    pub error_message: Option<String>,
+    pub failure_reason: Option<String>,
 }
 
 
@@ -76,41 +323,370 @@ pub struct TaskSummary {
     path = "/crawl",
     request_body = CrawlRequest,
     responses(
-        (status = 200, description = "Crawl started successfully", body = CrawlResponse)
+        (status = 200, description = "Crawl started successfully", body = CrawlResponse),
+        (status = 400, description = "Invalid engine_options or URL patterns"),
+        (status = 422, description = "Invalid request fields", body = ValidationErrorResponse),
+        (status = 429, description = "DAILY_CRAWL_LIMIT reached"),
+        (status = 503, description = "Job could not be queued (task marked failed)", body = CrawlResponse)
     )
 )]
 pub async fn trigger_crawl(
     State(state): State<Arc<AppState>>,
     user: crate::auth::AuthUser, // Require Auth
     Json(payload): Json<CrawlRequest>,
-) -> Json<CrawlResponse> {
+) -> Result<Json<CrawlResponse>, Response> {
+    enqueue_crawl(&state, &user, payload).await
+}
+
+/// Query-string form of a crawl request for GET-only integrations
+#[derive(Deserialize, utoipa::IntoParams)]
+pub struct CrawlQuery {
+    pub keyword: String,
+    pub engine: Option<String>,
+    pub tag: Option<String>,
+    pub batch_id: Option<String>,
+    pub deep_crawl: Option<bool>,
+    pub max_attempts: Option<u32>,
+    pub priority: Option<crate::queue::JobPriority>,
+}
+
+impl From<CrawlQuery> for CrawlRequest {
+    fn from(q: CrawlQuery) -> Self {
+        CrawlRequest {
+            keyword: q.keyword,
+            engine: q.engine,
+            tag: q.tag,
+            max_attempts: q.max_attempts,
+            deep_crawl: q.deep_crawl,
+            batch_id: q.batch_id,
+            priority: q.priority,
+            ..Default::default()
+        }
+    }
+}
+
+/// Convenience alias of POST /crawl for tools that can only send GET
+/// (no-code tools, simple webhooks). Disabled unless CRAWL_GET_ENABLED=true,
+/// since a GET with side effects can be triggered by prefetchers and crawlers.
+#[utoipa::path(
+    get,
+    path = "/crawl",
+    params(CrawlQuery),
+    responses(
+        (status = 200, description = "Crawl started successfully", body = CrawlResponse),
+        (status = 405, description = "GET submission disabled (CRAWL_GET_ENABLED=false)"),
+        (status = 422, description = "Invalid request fields", body = ValidationErrorResponse),
+        (status = 429, description = "DAILY_CRAWL_LIMIT reached"),
+        (status = 503, description = "Job could not be queued (task marked failed)", body = CrawlResponse)
+    )
+)]
+pub async fn trigger_crawl_get(
+    State(state): State<Arc<AppState>>,
+    user: crate::auth::AuthUser, // Require Auth
+    Query(query): Query<CrawlQuery>,
+) -> Result<Json<CrawlResponse>, Response> {
+    if !crate::config::env_flag("CRAWL_GET_ENABLED", false) {
+        return Err((StatusCode::METHOD_NOT_ALLOWED, "GET /crawl is disabled; use POST or set CRAWL_GET_ENABLED=true").into_response());
+    }
+    enqueue_crawl(&state, &user, query.into()).await
+}
+
+/// Multipart body for POST /crawl/upload
+#[derive(ToSchema)]
+#[allow(dead_code)]
+pub struct CrawlUploadForm {
+    /// CSV with a header row: keyword (required), engine, selectors.
+    /// `selectors` is a JSON object or `name=css;name2=css2` pairs.
+    #[schema(value_type = String, format = Binary)]
+    file: Vec<u8>,
+    /// Batch to file the jobs under; generated when omitted
+    batch_id: Option<String>,
+}
+
+/// Outcome of one CSV row (line numbers count the header as line 1)
+#[derive(Serialize, ToSchema, Debug, PartialEq)]
+pub struct UploadRowStatus {
+    pub line: u64,
+    pub keyword: String,
+    /// "queued" or "rejected"
+    #[schema(example = "queued")]
+    pub status: String,
+    pub task_id: Option<String>,
+    pub error: Option<String>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct CrawlUploadResponse {
+    pub batch_id: String,
+    pub queued: usize,
+    pub rejected: usize,
+    pub rows: Vec<UploadRowStatus>,
+}
+
+/// One parsed CSV row, before validation
+#[derive(Debug, PartialEq)]
+pub struct UploadRow {
+    pub line: u64,
+    pub keyword: String,
+    pub engine: Option<String>,
+    pub selectors: Result<Option<std::collections::HashMap<String, String>>, String>,
+}
+
+/// `selectors` cell: a JSON object, or `name=css` pairs separated by `;`
+fn parse_selector_cell(cell: &str) -> Result<Option<std::collections::HashMap<String, String>>, String> {
+    let cell = cell.trim();
+    if cell.is_empty() {
+        return Ok(None);
+    }
+    if cell.starts_with('{') {
+        return serde_json::from_str(cell).map(Some).map_err(|e| format!("invalid selectors JSON: {}", e));
+    }
+    cell.split(';')
+        .filter(|pair| !pair.trim().is_empty())
+        .map(|pair| match pair.split_once('=') {
+            Some((name, css)) => Ok((name.trim().to_string(), css.trim().to_string())),
+            None => Err(format!("selector '{}' is not name=css", pair.trim())),
+        })
+        .collect::<Result<_, _>>()
+        .map(Some)
+}
+
+/// Parse an upload CSV (header row required, column order free, extra columns ignored)
+pub fn parse_upload_csv(data: &[u8], max_rows: usize) -> Result<Vec<UploadRow>, String> {
+    let mut reader = csv::ReaderBuilder::new().trim(csv::Trim::All).flexible(true).from_reader(data);
+    let headers = reader.headers().map_err(|e| format!("invalid CSV header: {}", e))?.clone();
+    let column = |name: &str| headers.iter().position(|h| h.trim_start_matches('\u{feff}').eq_ignore_ascii_case(name));
+    let keyword_col = column("keyword").ok_or("CSV must have a 'keyword' column")?;
+    let (engine_col, selectors_col) = (column("engine"), column("selectors"));
+
+    let mut rows = Vec::new();
+    for record in reader.records() {
+        let record = record.map_err(|e| format!("invalid CSV: {}", e))?;
+        let cell = |col: Option<usize>| col.and_then(|c| record.get(c)).filter(|v| !v.is_empty());
+        if record.iter().all(|v| v.is_empty()) {
+            continue;
+        }
+        if rows.len() >= max_rows {
+            return Err(format!("CSV has more than {} rows", max_rows));
+        }
+        rows.push(UploadRow {
+            line: record.position().map(|p| p.line()).unwrap_or(0),
+            keyword: cell(Some(keyword_col)).unwrap_or_default().to_string(),
+            engine: cell(engine_col).map(|e| e.to_lowercase()),
+            selectors: cell(selectors_col).map(parse_selector_cell).unwrap_or(Ok(None)),
+        });
+    }
+    Ok(rows)
+}
+
+/// Bulk submission from a spreadsheet export: every CSV row becomes a crawl job
+/// in one batch (see GET /batch/{batch_id}/unique-results). Rows are validated
+/// and queued independently, so one bad row doesn't sink the upload.
+/// At most CRAWL_UPLOAD_MAX_ROWS (default 1000) rows.
+#[utoipa::path(
+    post,
+    path = "/crawl/upload",
+    request_body(content = CrawlUploadForm, content_type = "multipart/form-data"),
+    responses(
+        (status = 200, description = "Per-row queue status", body = CrawlUploadResponse),
+        (status = 400, description = "Missing file or unreadable CSV")
+    )
+)]
+pub async fn upload_crawl_jobs(
+    State(state): State<Arc<AppState>>,
+    user: crate::auth::AuthUser, // Require Auth
+    mut multipart: axum::extract::Multipart,
+) -> Result<Json<CrawlUploadResponse>, (StatusCode, String)> {
+    let bad_request = |msg: String| (StatusCode::BAD_REQUEST, msg);
+    let mut file: Option<Vec<u8>> = None;
+    let mut batch_id: Option<String> = None;
+    while let Some(field) = multipart.next_field().await.map_err(|e| bad_request(e.to_string()))? {
+        let name = field.name().map(str::to_string);
+        match name.as_deref() {
+            Some("batch_id") => batch_id = Some(field.text().await.map_err(|e| bad_request(e.to_string()))?.trim().to_string()),
+            Some("file") => file = Some(field.bytes().await.map_err(|e| bad_request(e.to_string()))?.to_vec()),
+            _ => {}
+        }
+    }
+    let file = file.ok_or_else(|| bad_request("multipart field 'file' is required".to_string()))?;
+    let max_rows = crate::config::env_parse("CRAWL_UPLOAD_MAX_ROWS", 1000usize);
+    let rows = parse_upload_csv(&file, max_rows).map_err(bad_request)?;
+    let batch_id = batch_id.filter(|b| !b.is_empty()).unwrap_or_else(|| Uuid::new_v4().to_string());
+    println!("📄 [API] CSV upload: {} rows into batch {}", rows.len(), batch_id);
+
+    let mut statuses = Vec::with_capacity(rows.len());
+    for row in rows {
+        let reject = |error: String| UploadRowStatus {
+            line: row.line,
+            keyword: row.keyword.clone(),
+            status: "rejected".to_string(),
+            task_id: None,
+            error: Some(error),
+        };
+        let selectors = match row.selectors {
+            Ok(selectors) => selectors,
+            Err(ref e) => {
+                statuses.push(reject(e.clone()));
+                continue;
+            }
+        };
+        let request = CrawlRequest {
+            keyword: row.keyword.clone(),
+            engine: row.engine.clone(),
+            selectors,
+            batch_id: Some(batch_id.clone()),
+            ..Default::default()
+        };
+        let status = match enqueue_crawl(&state, &user, request).await {
+            Ok(Json(resp)) => UploadRowStatus {
+                line: row.line,
+                keyword: row.keyword.clone(),
+                status: "queued".to_string(),
+                task_id: Some(resp.task_id),
+                error: None,
+            },
+            Err(resp) => {
+                let code = resp.status();
+                let body = axum::body::to_bytes(resp.into_body(), 64 * 1024).await.unwrap_or_default();
+                reject(format!("{}: {}", code.as_u16(), String::from_utf8_lossy(&body)))
+            }
+        };
+        statuses.push(status);
+    }
+
+    let queued = statuses.iter().filter(|s| s.status == "queued").count();
+    Ok(Json(CrawlUploadResponse {
+        batch_id,
+        queued,
+        rejected: statuses.len() - queued,
+        rows: statuses,
+    }))
+}
+
+/// Trim and collapse internal whitespace so " Rust  Programming " and "Rust Programming"
+/// share tasks and caches; lowercasing is optional since case matters to some engines.
+pub fn normalize_keyword(keyword: &str, lowercase: bool) -> String {
+    let collapsed = keyword.split_whitespace().collect::<Vec<_>>().join(" ");
+    if lowercase { collapsed.to_lowercase() } else { collapsed }
+}
+
+/// Shared by POST and GET /crawl: validate, check the quota, queue the job
+async fn enqueue_crawl(
+    state: &AppState,
+    user: &crate::auth::AuthUser,
+    payload: CrawlRequest,
+) -> Result<Json<CrawlResponse>, Response> {
+    if let Err(errors) = payload.validate() {
+        return Err((StatusCode::UNPROCESSABLE_ENTITY, Json(ValidationErrorResponse { errors })).into_response());
+    }
+
     let task_id = Uuid::new_v4().to_string();
-    let keyword = payload.keyword.clone();
     let engine = payload.engine.unwrap_or_else(|| "bing".to_string());
 
+    // KEYWORD_NORMALIZE (trim + collapse whitespace, default on) and KEYWORD_LOWERCASE (default off)
+    let mut metadata = payload.metadata;
+    let keyword = if crate::config::env_flag("KEYWORD_NORMALIZE", true) {
+        normalize_keyword(&payload.keyword, crate::config::env_flag("KEYWORD_LOWERCASE", false))
+    } else {
+        payload.keyword.clone()
+    };
+    if keyword != payload.keyword {
+        match metadata.get_or_insert_with(|| serde_json::json!({})) {
+            serde_json::Value::Object(map) => {
+                map.insert("original_keyword".to_string(), serde_json::Value::String(payload.keyword.clone()));
+            }
+            _ => eprintln!("⚠️ [API] Non-object metadata, original keyword '{}' not recorded", payload.keyword),
+        }
+    }
+
+    if let Some(ref opts) = payload.engine_options {
+        crawler::validate_engine_options(&engine, opts)
+            .map_err(|e| (StatusCode::BAD_REQUEST, e).into_response())?;
+    }
+
+    let result_include_patterns = payload.result_include_patterns.unwrap_or_default();
+    let result_exclude_patterns = payload.result_exclude_patterns.unwrap_or_default();
+    crawler::ResultFilter::new(&result_include_patterns, &result_exclude_patterns)
+        .map_err(|e| (StatusCode::BAD_REQUEST, e).into_response())?;
+
+    // Cluster-wide daily cap (DAILY_CRAWL_LIMIT, 0 = unlimited)
+    let daily_limit = crate::config::env_parse("DAILY_CRAWL_LIMIT", 0u64);
+    let mut quota = None;
+    if daily_limit > 0 {
+        match state.queue.reserve_daily_crawl(daily_limit).await {
+            Ok(Some(reservation)) => quota = Some(reservation),
+            Ok(None) => {
+                println!("🚫 [API] Daily crawl limit of {} reached, rejecting", daily_limit);
+                return Err((StatusCode::TOO_MANY_REQUESTS, format!("Daily crawl limit of {} reached", daily_limit)).into_response());
+            }
+            Err(e) => eprintln!("⚠️ [API] Quota check failed, admitting job: {}", e),
+        }
+    }
+
     let job = crate::queue::CrawlJob {
         id: task_id.clone(),
         user_id: user.id.clone(), // Pass user ID to worker
         keyword,
         engine,
         selectors: payload.selectors,
+        engine_options: payload.engine_options,
+        result_include_patterns,
+        result_exclude_patterns,
+        capture_thumbnail: payload.capture_thumbnail.unwrap_or(false),
+        screenshot: payload.screenshot.unwrap_or(false),
+        warmup: payload.warmup.unwrap_or_else(|| crate::config::env_flag("SEARCH_WARMUP", false)),
+        crawl_related: payload.crawl_related.unwrap_or(false),
+        parent_task_id: None,
+        referer: payload.referer,
+        metadata,
+        click_sequence: payload.click_sequence.unwrap_or_default(),
+        tag: payload.tag,
+        max_attempts: payload.max_attempts,
+        deep_crawl: payload.deep_crawl.unwrap_or(true),
+        batch_id: payload.batch_id,
+        priority: payload.priority.unwrap_or_default(),
+        storage_prefix: payload
+            .storage_prefix
+            .as_deref()
+            .and_then(|p| crate::storage::validate_storage_prefix(p, &crate::storage::storage_prefix_allowlist()).ok()),
+        extract_entities: payload.extract_entities.unwrap_or_else(|| crate::config::env_flag("EXTRACT_ENTITIES", false)),
+        stealth: payload.stealth,
+        max_deep_extract: payload.max_deep_extract.unwrap_or(1),
+        pages: payload.pages,
+        attempts: 0,
+        sticky: payload.sticky.unwrap_or(false),
+        respect_robots: payload.respect_robots.unwrap_or(false),
+        callback_url: payload.callback_url,
+        enqueued_at: 0,
     };
 
+    // Pending row first, so GET /crawl/{id} sees the task while it waits in the queue
+    if let Err(e) = crate::db::insert_task(&state.pool, &job, "pending").await {
+        eprintln!("⚠️ [API] Failed to record pending task {}: {}", task_id, e);
+    }
+
     // Push to Redis Queue
     match state.queue.push_job(job).await {
         Ok(_) => {
             println!("✅ [API] Job pushed to queue: {}", task_id);
-            Json(CrawlResponse {
+            Ok(Json(CrawlResponse {
                 task_id,
                 message: "Crawl job queued successfully".to_string(),
-            })
+            }))
         },
         Err(e) => {
             eprintln!("❌ [API] Failed to queue job: {}", e);
-            Json(CrawlResponse {
-                task_id,
-                message: "Failed to queue job".to_string(),
-            })
+            // The job never ran, so it doesn't count against today's quota
+            if let Some(ref reservation) = quota {
+                if let Err(e) = state.queue.release_daily_crawl(reservation).await {
+                    eprintln!("⚠️ [API] Failed to release quota for {}: {}", task_id, e);
+                }
+            }
+            let error = format!("Failed to queue job: {}", e);
+            if let Err(e) = crate::db::update_task_status(&state.pool, &task_id, "failed", Some(&error), None).await {
+                eprintln!("⚠️ [API] Failed to mark task {} failed: {}", task_id, e);
+            }
+            Err((StatusCode::SERVICE_UNAVAILABLE, Json(CrawlResponse { task_id, message: error })).into_response())
         }
     }
 }
@@ -133,30 +709,149 @@ pub async fn get_crawl_status(
     Path(task_id): Path<String>,
 ) -> Json<Option<TaskResult>> {
     let rec = sqlx::query_as::<_, TaskResult>(
-        "SELECT id, keyword, engine, status, results_json, extracted_text, first_page_html, meta_description, meta_author, meta_date, entities, category FROM tasks WHERE id = $1"
+        "SELECT id, keyword, engine, status, results_json, extracted_text, extracted_markdown, first_page_html, meta_description, meta_author, meta_date, entities, category, thumbnail_key, results_key, parent_task_id, metadata, redirect_chain, error_message, failure_reason, created_at AS crawled_at FROM tasks WHERE id = $1"
     )
     .bind(task_id)
     .fetch_optional(&state.pool)
     .await
     .unwrap_or(None);
 
+    let freshness_secs = crate::config::env_parse("RESULT_FRESHNESS_SECS", 86_400i64);
+    let rec = rec.map(|mut r| {
+        r.apply_freshness(chrono::Utc::now().naive_utc(), freshness_secs);
+        r
+    });
+
     Json(rec)
 }
 
+/// Upper bound on `limit` for GET /tasks
+pub const MAX_TASK_PAGE_SIZE: i64 = 200;
+
+#[derive(Deserialize, Default, utoipa::IntoParams)]
+pub struct ListTasksQuery {
+    /// Page size (default 50, max 200)
+    pub limit: Option<i64>,
+    /// Rows to skip (default 0)
+    pub offset: Option<i64>,
+    /// Exact status: pending, running, completed, failed
+    pub status: Option<String>,
+    /// Exact engine: bing, google, generic
+    pub engine: Option<String>,
+    /// Case-insensitive substring of the keyword
+    pub keyword: Option<String>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct TaskPage {
+    /// Tasks matching the filters, across all pages
+    pub total: i64,
+    pub limit: i64,
+    pub offset: i64,
+    pub items: Vec<TaskSummary>,
+}
+
+/// Escape `%`, `_` and `\` so a keyword filter matches literally inside ILIKE
+fn escape_like(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_")
+}
+
+/// Append the WHERE clause for the non-empty filters in `query`
+fn push_task_filters<'a>(builder: &mut sqlx::QueryBuilder<'a, sqlx::Postgres>, query: &'a ListTasksQuery) {
+    let non_empty = |v: &'a Option<String>| v.as_deref().map(str::trim).filter(|v| !v.is_empty());
+    let mut sep = " WHERE ";
+    if let Some(status) = non_empty(&query.status) {
+        builder.push(sep).push("status = ").push_bind(status);
+        sep = " AND ";
+    }
+    if let Some(engine) = non_empty(&query.engine) {
+        builder.push(sep).push("engine = ").push_bind(engine);
+        sep = " AND ";
+    }
+    if let Some(keyword) = non_empty(&query.keyword) {
+        builder.push(sep).push("keyword ILIKE ").push_bind(format!("%{}%", escape_like(keyword)));
+    }
+}
+
 #[utoipa::path(
     get,
     path = "/tasks",
     tag = "crawler",
+    params(ListTasksQuery),
     responses(
-        (status = 200, description = "List recent tasks", body = Vec<TaskSummary>)
+        (status = 200, description = "Page of tasks, newest first", body = TaskPage)
     )
 )]
 pub async fn list_tasks(
     State(state): State<Arc<AppState>>,
+    Query(query): Query<ListTasksQuery>,
+) -> Result<Json<TaskPage>, (StatusCode, String)> {
+    let limit = query.limit.unwrap_or(50).clamp(1, MAX_TASK_PAGE_SIZE);
+    let offset = query.offset.unwrap_or(0).max(0);
+
+    let mut count = sqlx::QueryBuilder::new("SELECT COUNT(*) FROM tasks");
+    push_task_filters(&mut count, &query);
+    let total: i64 = count
+        .build_query_scalar()
+        .fetch_one(&state.pool)
+        .await
+        .map_err(|e: sqlx::Error| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    let mut select = sqlx::QueryBuilder::new(
+        "SELECT id, keyword, engine, status, created_at, results_json, left(extracted_text, 1000) as extracted_text, error_message, failure_reason FROM tasks"
+    );
+    push_task_filters(&mut select, &query);
+    select.push(" ORDER BY created_at DESC LIMIT ").push_bind(limit).push(" OFFSET ").push_bind(offset);
+    let items = select
+        .build_query_as::<TaskSummary>()
+        .fetch_all(&state.pool)
+        .await
+        .map_err(|e: sqlx::Error| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok(Json(TaskPage { total, limit, offset, items }))
+}
+
+#[derive(Deserialize, utoipa::IntoParams)]
+pub struct SearchTasksQuery {
+    /// Search terms; web-search syntax ("quoted phrase", -exclude, or)
+    pub q: String,
+    /// Max results (default 50, max 200)
+    pub limit: Option<i64>,
+}
+
+/// Full-text search over every task's extracted page text, best match first
+#[utoipa::path(
+    get,
+    path = "/search",
+    tag = "crawler",
+    params(SearchTasksQuery),
+    responses(
+        (status = 200, description = "Matching tasks ranked by ts_rank", body = Vec<TaskSummary>),
+        (status = 400, description = "Empty query")
+    )
+)]
+pub async fn search_tasks(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<SearchTasksQuery>,
 ) -> Result<Json<Vec<TaskSummary>>, (StatusCode, String)> {
+    let q = query.q.trim();
+    if q.is_empty() {
+        return Err((StatusCode::BAD_REQUEST, "q must not be empty".to_string()));
+    }
+    let limit = query.limit.unwrap_or(50).clamp(1, MAX_TASK_PAGE_SIZE);
+
+    // websearch_to_tsquery rather than to_tsquery: free text never raises a syntax error
     let tasks = sqlx::query_as::<sqlx::Postgres, TaskSummary>(
-        "SELECT id, keyword, engine, status, created_at, results_json, left(extracted_text, 1000) as extracted_text FROM tasks ORDER BY created_at DESC LIMIT 50"
+        r#"
+        SELECT id, keyword, engine, status, created_at, results_json, left(extracted_text, 1000) as extracted_text, error_message, failure_reason
+        FROM tasks, websearch_to_tsquery('english', $1) query
+        WHERE search_vector @@ query
+        ORDER BY ts_rank(search_vector, query) DESC, created_at DESC
+        LIMIT $2
+        "#
     )
+    .bind(q)
+    .bind(limit)
     .fetch_all(&state.pool)
     .await
     .map_err(|e: sqlx::Error| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
@@ -164,6 +859,86 @@ pub async fn list_tasks(
     Ok(Json(tasks))
 }
 
+/// Scope of a Parquet export; `batch_id` wins over `date`, default is yesterday (UTC)
+#[derive(Deserialize, ToSchema, Default)]
+pub struct ExportParquetRequest {
+    #[schema(value_type = Option<String>, example = "2024-06-01")]
+    pub date: Option<chrono::NaiveDate>,
+    pub batch_id: Option<String>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct ExportParquetResponse {
+    /// Object key of the written file
+    #[schema(example = "exports/parquet/date=2024-06-01/results.parquet")]
+    pub key: String,
+    pub rows: usize,
+    pub bytes: usize,
+}
+
+/// Write crawl results (one row per SERP result) as a Parquet file to object storage
+#[utoipa::path(
+    post,
+    path = "/export/parquet",
+    tag = "crawler",
+    request_body = ExportParquetRequest,
+    responses(
+        (status = 200, description = "Export written", body = ExportParquetResponse),
+        (status = 503, description = "Object storage unavailable")
+    )
+)]
+pub async fn export_parquet(
+    State(state): State<Arc<AppState>>,
+    _user: crate::auth::AuthUser, // Require Auth
+    payload: Option<Json<ExportParquetRequest>>,
+) -> Result<Json<ExportParquetResponse>, (StatusCode, String)> {
+    let Some(ref storage) = state.storage else {
+        return Err((StatusCode::SERVICE_UNAVAILABLE, "Object storage is not configured".to_string()));
+    };
+    let request = payload.map(|Json(r)| r).unwrap_or_default();
+    let scope = match (request.batch_id.filter(|b| !b.trim().is_empty()), request.date) {
+        (Some(batch_id), _) => crate::export::ExportScope::Batch(batch_id),
+        (None, Some(date)) => crate::export::ExportScope::Day(date),
+        (None, None) => crate::export::ExportScope::Day(chrono::Utc::now().date_naive() - chrono::Duration::days(1)),
+    };
+
+    let summary = crate::export::export_parquet(&state.pool, storage, &scope)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    Ok(Json(ExportParquetResponse { key: summary.key, rows: summary.rows, bytes: summary.bytes }))
+}
+
+/// Live task status changes (queued, running, completed, failed) across the cluster,
+/// relayed from the Redis task events channel. Each SSE `task` event carries a
+/// `TaskStatusEvent` as JSON. Disable with TASK_EVENTS_ENABLED=false.
+#[utoipa::path(
+    get,
+    path = "/events/tasks",
+    tag = "crawler",
+    responses(
+        (status = 200, description = "text/event-stream of `task` events", body = TaskStatusEvent, content_type = "text/event-stream"),
+        (status = 404, description = "Task events disabled"),
+        (status = 503, description = "Could not subscribe to Redis")
+    )
+)]
+pub async fn task_events(
+    State(state): State<Arc<AppState>>,
+) -> Result<Sse<impl futures_util::Stream<Item = Result<SseEvent, std::convert::Infallible>>>, (StatusCode, String)> {
+    use futures_util::StreamExt;
+
+    if !crate::config::env_flag("TASK_EVENTS_ENABLED", true) {
+        return Err((StatusCode::NOT_FOUND, "Task events are disabled".to_string()));
+    }
+    let events = state.queue.subscribe_task_events().await.map_err(|e| {
+        eprintln!("⚠️ [API] Task event subscription failed: {}", e);
+        (StatusCode::SERVICE_UNAVAILABLE, "Task events unavailable".to_string())
+    })?;
+    println!("📡 [API] Task event stream opened");
+
+    let stream = events.map(|payload| Ok(SseEvent::default().event("task").data(payload)));
+    Ok(Sse::new(stream).keep_alive(KeepAlive::default()))
+}
+
 // ============================================================================
 // Proxy Management API
 // ============================================================================
@@ -190,15 +965,24 @@ pub async fn list_proxies() -> Json<Vec<ProxyInfo>> {
 /// Add a new proxy at runtime
 #[derive(Deserialize, ToSchema)]
 pub struct AddProxyRequest {
-    /// Proxy string: host:port or user:pass@host:port
+    /// Proxy string: [http|https|socks5://][user:pass@]host:port.
+    /// SOCKS5 proxies must be unauthenticated (Chrome can't send SOCKS credentials).
     #[schema(example = "user:pass@1.2.3.4:8080")]
     pub proxy: String,
 }
 
+#[derive(Deserialize, utoipa::IntoParams)]
+pub struct AddProxyQuery {
+    /// Update an existing proxy with the same host:port instead of rejecting it
+    pub replace: Option<bool>,
+}
+
 #[derive(Serialize, ToSchema)]
 pub struct AddProxyResponse {
     pub success: bool,
     pub proxy: Option<ProxyInfo>,
+    /// True when an existing entry was updated
+    pub replaced: bool,
     pub error: Option<String>,
 }
 
@@ -206,23 +990,39 @@ pub struct AddProxyResponse {
     post,
     path = "/proxies",
     tag = "proxy",
+    params(AddProxyQuery),
     request_body = AddProxyRequest,
     responses(
         (status = 200, description = "Add a new proxy", body = AddProxyResponse)
     )
 )]
 pub async fn add_proxy(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<AddProxyQuery>,
     Json(payload): Json<AddProxyRequest>,
 ) -> Json<AddProxyResponse> {
-    match PROXY_MANAGER.add_proxy(&payload.proxy) {
-        Ok(info) => Json(AddProxyResponse {
-            success: true,
-            proxy: Some(info),
-            error: None,
-        }),
+    match PROXY_MANAGER.add_proxy(&payload.proxy, query.replace.unwrap_or(false)) {
+        Ok((info, replaced)) => {
+            // The string parsed a moment ago, so this can't fail
+            let persisted = match crate::proxy::Proxy::parse(&payload.proxy) {
+                Ok(proxy) => crate::db::save_proxy(&state.pool, &proxy).await.map_err(|e| e.to_string()),
+                Err(e) => Err(e),
+            };
+            let error = persisted.err().map(|e| {
+                eprintln!("⚠️ [API] Proxy {} added but not persisted: {}", info.id, e);
+                format!("Added, but not persisted (lost on restart): {}", e)
+            });
+            Json(AddProxyResponse {
+                success: true,
+                proxy: Some(info),
+                replaced,
+                error,
+            })
+        }
         Err(e) => Json(AddProxyResponse {
             success: false,
             proxy: None,
+            replaced: false,
             error: Some(e),
         }),
     }
@@ -247,12 +1047,18 @@ pub struct RemoveProxyResponse {
     )
 )]
 pub async fn remove_proxy(
+    State(state): State<Arc<AppState>>,
     Path(proxy_id): Path<String>,
 ) -> Json<RemoveProxyResponse> {
+    // A stored proxy that isn't loaded (e.g. pruned) is still deleted from the table
+    let persisted = crate::db::delete_proxy(&state.pool, &proxy_id).await;
+    if let Err(ref e) = persisted {
+        eprintln!("⚠️ [API] Failed to delete stored proxy {}: {}", proxy_id, e);
+    }
     match PROXY_MANAGER.remove_proxy(&proxy_id) {
         Ok(()) => Json(RemoveProxyResponse {
             success: true,
-            error: None,
+            error: persisted.err().map(|e| format!("Removed, but still stored (returns on restart): {}", e)),
         }),
         Err(e) => Json(RemoveProxyResponse {
             success: false,
@@ -274,13 +1080,17 @@ pub async fn remove_proxy(
     )
 )]
 pub async fn enable_proxy(
+    State(state): State<Arc<AppState>>,
     Path(proxy_id): Path<String>,
 ) -> Json<RemoveProxyResponse> {
     match PROXY_MANAGER.enable_proxy(&proxy_id) {
-        Ok(()) => Json(RemoveProxyResponse {
-            success: true,
-            error: None,
-        }),
+        Ok(()) => {
+            let error = crate::db::set_proxy_enabled(&state.pool, &proxy_id, true).await.err().map(|e| {
+                eprintln!("⚠️ [API] Failed to persist enabled proxy {}: {}", proxy_id, e);
+                format!("Enabled, but not persisted: {}", e)
+            });
+            Json(RemoveProxyResponse { success: true, error })
+        }
         Err(e) => Json(RemoveProxyResponse {
             success: false,
             error: Some(e),
@@ -288,6 +1098,52 @@ pub async fn enable_proxy(
     }
 }
 
+#[derive(Serialize, ToSchema)]
+pub struct ResetProxyStatsResponse {
+    pub success: bool,
+    /// Number of proxies whose counters were zeroed
+    pub reset: usize,
+    pub error: Option<String>,
+}
+
+fn reset_stats_response(result: Result<usize, String>) -> Json<ResetProxyStatsResponse> {
+    match result {
+        Ok(reset) => Json(ResetProxyStatsResponse { success: true, reset, error: None }),
+        Err(e) => Json(ResetProxyStatsResponse { success: false, reset: 0, error: Some(e) }),
+    }
+}
+
+/// Zero a proxy's success/failure counters (health flag is left as is)
+#[utoipa::path(
+    post,
+    path = "/proxies/{proxy_id}/reset-stats",
+    tag = "proxy",
+    params(
+        ("proxy_id" = String, Path, description = "Proxy ID")
+    ),
+    responses(
+        (status = 200, description = "Reset one proxy's stats", body = ResetProxyStatsResponse)
+    )
+)]
+pub async fn reset_proxy_stats(
+    Path(proxy_id): Path<String>,
+) -> Json<ResetProxyStatsResponse> {
+    reset_stats_response(PROXY_MANAGER.reset_stats(Some(&proxy_id)))
+}
+
+/// Zero the success/failure counters of every proxy
+#[utoipa::path(
+    post,
+    path = "/proxies/reset-stats",
+    tag = "proxy",
+    responses(
+        (status = 200, description = "Reset all proxy stats", body = ResetProxyStatsResponse)
+    )
+)]
+pub async fn reset_all_proxy_stats() -> Json<ResetProxyStatsResponse> {
+    reset_stats_response(PROXY_MANAGER.reset_stats(None))
+}
+
 /// Get aggregate proxy stats
 #[utoipa::path(
     get,
@@ -300,3 +1156,921 @@ pub async fn enable_proxy(
 pub async fn proxy_stats() -> Json<ProxyStats> {
     Json(PROXY_MANAGER.get_stats())
 }
+
+#[derive(Deserialize, ToSchema)]
+pub struct SetProxyStrategyRequest {
+    pub strategy: crate::proxy::RotationStrategy,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct SetProxyStrategyResponse {
+    pub previous: crate::proxy::RotationStrategy,
+    pub strategy: crate::proxy::RotationStrategy,
+}
+
+/// Switch how proxies are picked (not persisted; PROXY_ROTATION applies again on restart)
+#[utoipa::path(
+    post,
+    path = "/proxies/strategy",
+    tag = "proxy",
+    request_body = SetProxyStrategyRequest,
+    responses(
+        (status = 200, description = "Strategy changed", body = SetProxyStrategyResponse)
+    )
+)]
+pub async fn set_proxy_strategy(Json(payload): Json<SetProxyStrategyRequest>) -> Json<SetProxyStrategyResponse> {
+    let previous = PROXY_MANAGER.set_strategy(payload.strategy);
+    Json(SetProxyStrategyResponse { previous, strategy: payload.strategy })
+}
+
+// ============================================================================
+// Rank-Tracking Baselines
+// ============================================================================
+
+#[derive(Serialize, ToSchema)]
+pub struct BaselineResponse {
+    pub task_id: String,
+    pub keyword: String,
+    pub engine: String,
+    /// Baseline this one replaced, if any
+    pub previous_task_id: Option<String>,
+}
+
+/// Mark a task as the reference result set for its keyword + engine
+#[utoipa::path(
+    post,
+    path = "/tasks/{task_id}/set-baseline",
+    tag = "crawler",
+    params(
+        ("task_id" = String, Path, description = "Task ID")
+    ),
+    responses(
+        (status = 200, description = "Baseline set", body = BaselineResponse),
+        (status = 404, description = "Task not found")
+    )
+)]
+pub async fn set_baseline(
+    State(state): State<Arc<AppState>>,
+    Path(task_id): Path<String>,
+) -> Result<Json<BaselineResponse>, (StatusCode, String)> {
+    let (keyword, engine, previous_task_id) = crate::db::set_baseline(&state.pool, &task_id)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .ok_or_else(|| (StatusCode::NOT_FOUND, format!("Task {} not found", task_id)))?;
+
+    println!("📌 [API] Baseline for '{}' ({}) set to {}", keyword, engine, task_id);
+    Ok(Json(BaselineResponse { task_id, keyword, engine, previous_task_id }))
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct DeleteTaskResponse {
+    pub task_id: String,
+    pub deleted: bool,
+    /// False if any recorded HTML/screenshot/thumbnail/results object could not be removed
+    pub storage_cleaned: bool,
+}
+
+/// Object key of a task's full results JSON: `{engine}/{task_id}/result.json`
+pub fn results_object_key(engine: &str, task_id: &str) -> String {
+    format!("{}/{}/result.json", engine, task_id)
+}
+
+/// Object key of a deep-extracted page's PNG screenshot: `{engine}/{task_id}/screenshot.png`
+/// for the top page, `screenshot-{index}.png` for the rest
+pub fn screenshot_object_key(engine: &str, task_id: &str, index: usize) -> String {
+    match index {
+        0 => format!("{}/{}/screenshot.png", engine, task_id),
+        i => format!("{}/{}/screenshot-{}.png", engine, task_id, i),
+    }
+}
+
+/// Delete a task, its result rows and the stored objects recorded for it
+/// (see [`task_artifact_keys`])
+#[utoipa::path(
+    delete,
+    path = "/tasks/{task_id}",
+    tag = "crawler",
+    params(
+        ("task_id" = String, Path, description = "Task ID")
+    ),
+    responses(
+        (status = 200, description = "Task deleted", body = DeleteTaskResponse),
+        (status = 404, description = "Task not found")
+    )
+)]
+pub async fn delete_task(
+    State(state): State<Arc<AppState>>,
+    _user: crate::auth::AuthUser, // Require Auth
+    Path(task_id): Path<String>,
+) -> Result<Json<DeleteTaskResponse>, (StatusCode, String)> {
+    let (results_json, page_keys, thumbnail_key, results_key) = crate::db::delete_task(&state.pool, &task_id)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .ok_or_else(|| (StatusCode::NOT_FOUND, format!("Task {} not found", task_id)))?;
+
+    // Storage cleanup is best-effort: the row is already gone
+    let mut storage_cleaned = true;
+    if let Some(ref storage) = state.storage {
+        let pages = stored_page_keys(page_keys, results_json.as_deref());
+        for (_, _, key) in task_artifact_keys(pages.as_ref(), thumbnail_key.as_deref(), results_key.as_deref()) {
+            if let Err(e) = storage.delete_html(&key).await {
+                eprintln!("⚠️ [API] Failed to delete {} for task {}: {}", key, task_id, e);
+                storage_cleaned = false;
+            }
+        }
+    }
+
+    println!("🗑️ [API] Deleted task {}", task_id);
+    Ok(Json(DeleteTaskResponse { task_id, deleted: true, storage_cleaned }))
+}
+
+/// Presigned URL lifetime when `expires_in` is not given (`ARTIFACT_URL_EXPIRY_SECS`, default 15 min)
+pub const DEFAULT_ARTIFACT_URL_EXPIRY_SECS: u64 = 900;
+/// Longest lifetime S3 SigV4 allows for a presigned URL (7 days)
+pub const MAX_ARTIFACT_URL_EXPIRY_SECS: u64 = 604_800;
+
+#[derive(Deserialize, Default, utoipa::IntoParams)]
+pub struct ArtifactsQuery {
+    /// URL lifetime in seconds (default 900, max 604800)
+    pub expires_in: Option<u64>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct TaskArtifact {
+    /// html, screenshot, thumbnail or results
+    #[schema(example = "html")]
+    pub kind: String,
+    /// Position in `results_json.pages` (rank order); None for task-level objects
+    pub page: Option<usize>,
+    /// Object key in the primary bucket
+    pub key: String,
+    /// Presigned GET URL
+    pub url: String,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct TaskArtifactsResponse {
+    pub task_id: String,
+    pub expires_in_secs: u64,
+    pub artifacts: Vec<TaskArtifact>,
+}
+
+/// The task's per-page `{html_key, screenshot_key}` list: the `page_keys` column, or
+/// `results_json.pages` for rows stored before that column existed
+pub fn stored_page_keys(page_keys: Option<serde_json::Value>, results_json: Option<&str>) -> Option<serde_json::Value> {
+    page_keys.or_else(|| {
+        let mut results: serde_json::Value = serde_json::from_str(results_json?).ok()?;
+        Some(results.get_mut("pages")?.take())
+    })
+}
+
+/// Stored objects recorded for a task: each page's `html_key` / `screenshot_key` from
+/// [`stored_page_keys`], then the thumbnail and full results JSON. Pages of legacy rows
+/// stored before keys were recorded are not listed.
+pub fn task_artifact_keys(
+    page_keys: Option<&serde_json::Value>,
+    thumbnail_key: Option<&str>,
+    results_key: Option<&str>,
+) -> Vec<(&'static str, Option<usize>, String)> {
+    let pages = page_keys
+        .and_then(|p| p.as_array())
+        .map(|p| p.as_slice())
+        .unwrap_or_default();
+    let mut keys = Vec::new();
+    for (page, data) in pages.iter().enumerate() {
+        for (kind, field) in [("html", "html_key"), ("screenshot", "screenshot_key")] {
+            if let Some(key) = data.get(field).and_then(|k| k.as_str()) {
+                keys.push((kind, Some(page), key.to_string()));
+            }
+        }
+    }
+    keys.extend(thumbnail_key.map(|k| ("thumbnail", None, k.to_string())));
+    keys.extend(results_key.map(|k| ("results", None, k.to_string())));
+    keys
+}
+
+/// Presigned download URLs for a task's stored HTML, screenshots, thumbnail and results JSON
+#[utoipa::path(
+    get,
+    path = "/tasks/{task_id}/artifacts",
+    tag = "crawler",
+    params(
+        ("task_id" = String, Path, description = "Task ID"),
+        ArtifactsQuery
+    ),
+    responses(
+        (status = 200, description = "Presigned URLs for the task's stored objects", body = TaskArtifactsResponse),
+        (status = 404, description = "Task not found"),
+        (status = 503, description = "Object storage not configured")
+    )
+)]
+pub async fn get_task_artifacts(
+    State(state): State<Arc<AppState>>,
+    _user: crate::auth::AuthUser, // Require Auth
+    Path(task_id): Path<String>,
+    Query(query): Query<ArtifactsQuery>,
+) -> Result<Json<TaskArtifactsResponse>, (StatusCode, String)> {
+    let storage = state
+        .storage
+        .as_ref()
+        .ok_or_else(|| (StatusCode::SERVICE_UNAVAILABLE, "Object storage is not configured".to_string()))?;
+
+    let (results_json, page_keys, thumbnail_key, results_key): crate::db::TaskObjectColumns =
+        sqlx::query_as("SELECT results_json, page_keys, thumbnail_key, results_key FROM tasks WHERE id = $1")
+            .bind(&task_id)
+            .fetch_optional(&state.pool)
+            .await
+            .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+            .ok_or_else(|| (StatusCode::NOT_FOUND, format!("Task {} not found", task_id)))?;
+
+    let expires_in_secs = query
+        .expires_in
+        .unwrap_or_else(|| crate::config::env_parse("ARTIFACT_URL_EXPIRY_SECS", DEFAULT_ARTIFACT_URL_EXPIRY_SECS))
+        .clamp(1, MAX_ARTIFACT_URL_EXPIRY_SECS);
+    let expires = std::time::Duration::from_secs(expires_in_secs);
+
+    let mut artifacts = Vec::new();
+    let pages = stored_page_keys(page_keys, results_json.as_deref());
+    for (kind, page, key) in task_artifact_keys(pages.as_ref(), thumbnail_key.as_deref(), results_key.as_deref()) {
+        let url = storage
+            .presign_get(&key, expires)
+            .await
+            .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to presign {}: {}", key, e)))?;
+        artifacts.push(TaskArtifact { kind: kind.to_string(), page, key, url });
+    }
+
+    Ok(Json(TaskArtifactsResponse { task_id, expires_in_secs, artifacts }))
+}
+
+#[derive(Deserialize, Default, utoipa::IntoParams)]
+pub struct ExportCsvQuery {
+    /// Prefix a UTF-8 BOM so Excel detects the encoding (default false)
+    pub bom: Option<bool>,
+}
+
+/// One CSV record, quoted as needed
+fn csv_record(fields: [&str; 3]) -> Result<Vec<u8>, csv::Error> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    writer.write_record(fields)?;
+    writer.into_inner().map_err(|e| e.into_error().into())
+}
+
+/// CSV body chunks for SERP results: optional BOM, the `title,link,snippet` header, then one chunk per result
+pub fn serp_results_csv(results: Vec<crawler::SearchResult>, bom: bool) -> impl Iterator<Item = Result<Vec<u8>, csv::Error>> {
+    let bom = bom.then(|| Ok("\u{feff}".as_bytes().to_vec()));
+    bom.into_iter()
+        .chain(std::iter::once(csv_record(["title", "link", "snippet"])))
+        .chain(results.into_iter().map(|r| csv_record([&r.title, &r.link, &r.snippet])))
+}
+
+/// A task's SERP results as CSV (title, link, snippet)
+#[utoipa::path(
+    get,
+    path = "/tasks/{task_id}/export.csv",
+    tag = "crawler",
+    params(
+        ("task_id" = String, Path, description = "Task ID"),
+        ExportCsvQuery
+    ),
+    responses(
+        (status = 200, description = "CSV of the task's search results", content_type = "text/csv", body = String),
+        (status = 404, description = "Task not found or has no results yet")
+    )
+)]
+pub async fn export_task_csv(
+    State(state): State<Arc<AppState>>,
+    Path(task_id): Path<String>,
+    Query(query): Query<ExportCsvQuery>,
+) -> Result<Response, (StatusCode, String)> {
+    let results_json: Option<Option<String>> = sqlx::query_scalar("SELECT results_json FROM tasks WHERE id = $1")
+        .bind(&task_id)
+        .fetch_optional(&state.pool)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    let results_json = results_json
+        .ok_or_else(|| (StatusCode::NOT_FOUND, format!("Task {} not found", task_id)))?
+        .filter(|r| !r.is_empty())
+        .ok_or_else(|| (StatusCode::NOT_FOUND, format!("Task {} has no results", task_id)))?;
+    let serp: crawler::SerpData = serde_json::from_str(&results_json)
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Stored results are not valid SerpData: {}", e)))?;
+
+    let filename: String = task_id.chars().filter(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_')).collect();
+    let body = axum::body::Body::from_stream(futures_util::stream::iter(serp_results_csv(serp.results, query.bom.unwrap_or(false))));
+    Ok((
+        [
+            (axum::http::header::CONTENT_TYPE, "text/csv; charset=utf-8".to_string()),
+            (axum::http::header::CONTENT_DISPOSITION, format!("attachment; filename=\"{}.csv\"", filename)),
+        ],
+        body,
+    )
+        .into_response())
+}
+
+// ============================================================================
+// Quota
+// ============================================================================
+
+#[derive(Serialize, ToSchema)]
+pub struct QuotaStats {
+    /// Current quota day (UTC)
+    #[schema(example = "2024-06-01")]
+    pub date: String,
+    pub used: u64,
+    /// DAILY_CRAWL_LIMIT; None when unlimited
+    pub limit: Option<u64>,
+    pub remaining: Option<u64>,
+    /// When the counter resets (next UTC midnight)
+    pub resets_at: String,
+}
+
+#[utoipa::path(
+    get,
+    path = "/stats/quota",
+    tag = "crawler",
+    responses(
+        (status = 200, description = "Today's cluster-wide crawl usage against DAILY_CRAWL_LIMIT", body = QuotaStats),
+        (status = 503, description = "Redis unavailable")
+    )
+)]
+pub async fn quota_stats(
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<QuotaStats>, (StatusCode, String)> {
+    let used = state.queue.daily_crawl_count().await
+        .map_err(|e| (StatusCode::SERVICE_UNAVAILABLE, e.to_string()))?;
+    let limit = Some(crate::config::env_parse("DAILY_CRAWL_LIMIT", 0u64)).filter(|l| *l > 0);
+    let today = chrono::Utc::now().date_naive();
+    let resets_at = today
+        .succ_opt()
+        .and_then(|d| d.and_hms_opt(0, 0, 0))
+        .map(|t| t.and_utc().to_rfc3339())
+        .unwrap_or_default();
+
+    Ok(Json(QuotaStats {
+        date: today.format("%Y-%m-%d").to_string(),
+        used,
+        limit,
+        remaining: limit.map(|l| l.saturating_sub(used)),
+        resets_at,
+    }))
+}
+
+// ============================================================================
+// Worker Control
+// ============================================================================
+
+#[derive(Serialize, ToSchema)]
+pub struct WorkerStatusResponse {
+    /// True while the worker is not picking up new jobs
+    pub paused: bool,
+    /// Jobs waiting in the queue (None if Redis is unreachable)
+    pub pending_jobs: Option<usize>,
+}
+
+async fn worker_status_response(state: &AppState) -> WorkerStatusResponse {
+    WorkerStatusResponse {
+        paused: state.worker_paused.load(Ordering::Relaxed),
+        pending_jobs: state.queue.queue_len().await.ok(),
+    }
+}
+
+/// Stop picking up new jobs; the job in progress (if any) finishes normally
+#[utoipa::path(
+    post,
+    path = "/admin/worker/pause",
+    tag = "admin",
+    responses(
+        (status = 200, description = "Worker paused", body = WorkerStatusResponse)
+    )
+)]
+pub async fn pause_worker(State(state): State<Arc<AppState>>) -> Json<WorkerStatusResponse> {
+    if !state.worker_paused.swap(true, Ordering::Relaxed) {
+        println!("⏸️ [API] Worker paused");
+    }
+    Json(worker_status_response(&state).await)
+}
+
+#[utoipa::path(
+    post,
+    path = "/admin/worker/resume",
+    tag = "admin",
+    responses(
+        (status = 200, description = "Worker resumed", body = WorkerStatusResponse)
+    )
+)]
+pub async fn resume_worker(State(state): State<Arc<AppState>>) -> Json<WorkerStatusResponse> {
+    if state.worker_paused.swap(false, Ordering::Relaxed) {
+        println!("▶️ [API] Worker resumed");
+    }
+    Json(worker_status_response(&state).await)
+}
+
+#[utoipa::path(
+    get,
+    path = "/admin/worker/status",
+    tag = "admin",
+    responses(
+        (status = 200, description = "Whether the worker is paused", body = WorkerStatusResponse)
+    )
+)]
+pub async fn worker_status(State(state): State<Arc<AppState>>) -> Json<WorkerStatusResponse> {
+    Json(worker_status_response(&state).await)
+}
+
+// ============================================================================
+// Batch Results
+// ============================================================================
+
+#[derive(Deserialize, utoipa::IntoParams)]
+pub struct UniqueResultsQuery {
+    /// Collapse results by `domain` (default) or by `url`
+    pub by: Option<String>,
+}
+
+/// One distinct site/URL across a batch
+#[derive(Serialize, ToSchema, Debug)]
+pub struct UniqueResult {
+    /// Normalized domain or URL the results were collapsed on
+    #[schema(example = "rust-lang.org")]
+    pub key: String,
+    /// Best-ranked link seen for this key
+    pub link: String,
+    pub title: Option<String>,
+    /// Best SERP position across the batch
+    pub best_position: i32,
+    /// Number of results across the batch that collapsed into this entry
+    pub occurrences: usize,
+    /// Keywords whose SERPs contained this key
+    pub keywords: Vec<String>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct BatchUniqueResults {
+    pub batch_id: String,
+    /// `domain` or `url`
+    pub by: String,
+    pub task_count: i64,
+    pub total_results: usize,
+    pub unique: Vec<UniqueResult>,
+}
+
+/// Normalized dedup key for a result link. Domains drop `www.`; URLs drop the
+/// fragment and trailing slash. Unparseable links are used as-is.
+fn dedup_key(link: &str, by_domain: bool) -> String {
+    let url = crawler::decode_search_url(link);
+    match reqwest::Url::parse(&url) {
+        Ok(mut parsed) => {
+            let host = parsed.host_str().unwrap_or("").to_lowercase();
+            let host = host.trim_start_matches("www.").to_string();
+            if by_domain {
+                return host;
+            }
+            parsed.set_fragment(None);
+            format!("{}{}{}", host, parsed.path().trim_end_matches('/'), parsed.query().map(|q| format!("?{}", q)).unwrap_or_default())
+        }
+        Err(_) => url,
+    }
+}
+
+/// Collapse (keyword, position, title, link) rows into unique entries,
+/// ordered by best position, then by how many SERPs they appeared in.
+fn collapse_results(rows: Vec<(String, i32, Option<String>, String)>, by_domain: bool) -> Vec<UniqueResult> {
+    let mut unique: Vec<UniqueResult> = Vec::new();
+    let mut index: std::collections::HashMap<String, usize> = std::collections::HashMap::new();
+
+    for (keyword, position, title, link) in rows {
+        let key = dedup_key(&link, by_domain);
+        if key.is_empty() {
+            continue;
+        }
+        match index.get(&key) {
+            Some(&i) => {
+                let entry = &mut unique[i];
+                entry.occurrences += 1;
+                if position < entry.best_position {
+                    entry.best_position = position;
+                    entry.link = link;
+                    entry.title = title;
+                }
+                if !entry.keywords.contains(&keyword) {
+                    entry.keywords.push(keyword);
+                }
+            }
+            None => {
+                index.insert(key.clone(), unique.len());
+                unique.push(UniqueResult { key, link, title, best_position: position, occurrences: 1, keywords: vec![keyword] });
+            }
+        }
+    }
+
+    unique.sort_by(|a, b| a.best_position.cmp(&b.best_position).then(b.keywords.len().cmp(&a.keywords.len())));
+    unique
+}
+
+#[utoipa::path(
+    get,
+    path = "/batch/{batch_id}/unique-results",
+    tag = "crawler",
+    params(
+        ("batch_id" = String, Path, description = "Batch ID given at submission"),
+        UniqueResultsQuery
+    ),
+    responses(
+        (status = 200, description = "Distinct domains/URLs across every task in the batch", body = BatchUniqueResults),
+        (status = 400, description = "Invalid `by` value"),
+        (status = 404, description = "No tasks in this batch")
+    )
+)]
+pub async fn batch_unique_results(
+    State(state): State<Arc<AppState>>,
+    Path(batch_id): Path<String>,
+    Query(query): Query<UniqueResultsQuery>,
+) -> Result<Json<BatchUniqueResults>, (StatusCode, String)> {
+    let by = query.by.unwrap_or_else(|| "domain".to_string()).to_lowercase();
+    let by_domain = match by.as_str() {
+        "domain" => true,
+        "url" => false,
+        _ => return Err((StatusCode::BAD_REQUEST, "`by` must be `domain` or `url`".to_string())),
+    };
+
+    let task_count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM tasks WHERE batch_id = $1")
+        .bind(&batch_id)
+        .fetch_one(&state.pool)
+        .await
+        .map_err(|e: sqlx::Error| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    if task_count == 0 {
+        return Err((StatusCode::NOT_FOUND, format!("No tasks found for batch {}", batch_id)));
+    }
+
+    let rows: Vec<(String, i32, Option<String>, String)> = sqlx::query_as(
+        "SELECT t.keyword, r.position, r.title, r.link FROM task_results r JOIN tasks t ON t.id = r.task_id WHERE t.batch_id = $1 ORDER BY t.created_at, r.position"
+    )
+    .bind(&batch_id)
+    .fetch_all(&state.pool)
+    .await
+    .map_err(|e: sqlx::Error| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    let total_results = rows.len();
+    let unique = collapse_results(rows, by_domain);
+
+    Ok(Json(BatchUniqueResults { batch_id, by, task_count, total_results, unique }))
+}
+
+// ============================================================================
+// Dashboard Feed
+// ============================================================================
+
+#[derive(Deserialize, utoipa::IntoParams)]
+pub struct DashboardFeedQuery {
+    /// Number of recent tasks to include (default DASHBOARD_RECENT_LIMIT or 20, max 200)
+    pub limit: Option<i64>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct QueueStats {
+    pub pending_jobs: Option<usize>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct ActivityStats {
+    pub tasks_last_24h: i64,
+    pub completed_last_24h: i64,
+    pub success_rate_24h: f64,
+}
+
+/// Everything the dashboard needs in a single round-trip
+#[derive(Serialize, ToSchema)]
+pub struct DashboardFeed {
+    pub recent_tasks: Vec<TaskSummary>,
+    pub queue: QueueStats,
+    pub proxies: ProxyStats,
+    pub activity: ActivityStats,
+}
+
+#[utoipa::path(
+    get,
+    path = "/dashboard/feed",
+    tag = "crawler",
+    params(DashboardFeedQuery),
+    responses(
+        (status = 200, description = "Recent tasks, queue depth, proxy health and 24h success rate", body = DashboardFeed)
+    )
+)]
+pub async fn dashboard_feed(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<DashboardFeedQuery>,
+) -> Result<Json<DashboardFeed>, (StatusCode, String)> {
+    let limit = query
+        .limit
+        .unwrap_or_else(|| crate::config::env_parse("DASHBOARD_RECENT_LIMIT", 20))
+        .clamp(1, 200);
+
+    let recent_tasks = sqlx::query_as::<sqlx::Postgres, TaskSummary>(
+        "SELECT id, keyword, engine, status, created_at, results_json, left(extracted_text, 1000) as extracted_text, error_message, failure_reason FROM tasks ORDER BY created_at DESC LIMIT $1"
+    )
+    .bind(limit)
+    .fetch_all(&state.pool)
+    .await
+    .map_err(|e: sqlx::Error| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    let (total, completed): (i64, i64) = sqlx::query_as(
+        "SELECT COUNT(*), COUNT(*) FILTER (WHERE status = 'completed') FROM tasks WHERE created_at > NOW() - INTERVAL '24 hours'"
+    )
+    .fetch_one(&state.pool)
+    .await
+    .map_err(|e: sqlx::Error| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    // Queue depth is best-effort: a Redis hiccup shouldn't blank the whole dashboard
+    let pending_jobs = match state.queue.queue_len().await {
+        Ok(n) => Some(n),
+        Err(e) => {
+            eprintln!("⚠️ [API] Failed to read queue length: {}", e);
+            None
+        }
+    };
+
+    Ok(Json(DashboardFeed {
+        recent_tasks,
+        queue: QueueStats { pending_jobs },
+        proxies: PROXY_MANAGER.get_stats(),
+        activity: ActivityStats {
+            tasks_last_24h: total,
+            completed_last_24h: completed,
+            success_rate_24h: if total > 0 { completed as f64 / total as f64 } else { 1.0 },
+        },
+    }))
+}
+
+// ============================================================================
+// Result Integrity Verification
+// ============================================================================
+
+#[derive(Serialize, ToSchema)]
+pub struct VerifyResponse {
+    pub task_id: String,
+    /// Whether the task has a stored signature
+    pub signed: bool,
+    /// Whether the signature matches the stored HTML + results
+    pub valid: bool,
+    pub signed_at: Option<String>,
+    /// Hex-encoded Ed25519 public key that signed the result
+    pub key_id: Option<String>,
+    /// Whether key_id is the key currently configured on this server
+    pub current_key: bool,
+    pub message: String,
+}
+
+#[utoipa::path(
+    get,
+    path = "/tasks/{task_id}/verify",
+    tag = "crawler",
+    params(
+        ("task_id" = String, Path, description = "Task ID")
+    ),
+    responses(
+        (status = 200, description = "Signature verification result", body = VerifyResponse),
+        (status = 404, description = "Task not found")
+    )
+)]
+pub async fn verify_task(
+    State(state): State<Arc<AppState>>,
+    Path(task_id): Path<String>,
+) -> Result<Json<VerifyResponse>, (StatusCode, String)> {
+    let row: Option<(Option<String>, Option<String>, Option<String>, Option<String>, Option<String>)> = sqlx::query_as(
+        "SELECT first_page_html, results_json, signature, signed_at, signature_key_id FROM tasks WHERE id = $1"
+    )
+    .bind(&task_id)
+    .fetch_optional(&state.pool)
+    .await
+    .map_err(|e: sqlx::Error| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    let (html, results_json, signature, signed_at, key_id) =
+        row.ok_or_else(|| (StatusCode::NOT_FOUND, format!("Task {} not found", task_id)))?;
+
+    let current_key = match (&key_id, crate::signing::RESULT_SIGNER.as_ref()) {
+        (Some(k), Some(signer)) => *k == signer.key_id(),
+        _ => false,
+    };
+
+    let (signed, valid, message) = match (signature, signed_at.clone(), key_id.clone()) {
+        (Some(signature), Some(signed_at), Some(key_id)) => {
+            let sig = crate::signing::ResultSignature { signature, signed_at, key_id };
+            match crate::signing::verify(&task_id, html.as_deref().unwrap_or(""), results_json.as_deref().unwrap_or(""), &sig) {
+                Ok(()) => (true, true, "Signature valid".to_string()),
+                Err(e) => (true, false, e),
+            }
+        }
+        _ => (false, false, "Task has no signature".to_string()),
+    };
+
+    Ok(Json(VerifyResponse {
+        task_id,
+        signed,
+        valid,
+        signed_at,
+        key_id,
+        current_key,
+        message,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(keyword: &str) -> CrawlRequest {
+        CrawlRequest { keyword: keyword.to_string(), ..Default::default() }
+    }
+
+    #[test]
+    fn test_crawl_request_validation() {
+        assert!(request("rust programming").validate().is_ok());
+
+        let errors = request("   ").validate().unwrap_err();
+        assert_eq!(errors[0].field, "keyword");
+
+        assert!(request(&"a".repeat(501)).validate().is_err());
+
+        let mut bad_referer = request("rust");
+        bad_referer.referer = Some("google".to_string());
+        assert_eq!(bad_referer.validate().unwrap_err()[0].field, "referer");
+
+        let mut bad_callback = request("rust");
+        bad_callback.callback_url = Some("ftp://example.com/hook".to_string());
+        assert_eq!(bad_callback.validate().unwrap_err()[0].field, "callback_url");
+        bad_callback.callback_url = Some("http://169.254.169.254/latest/meta-data".to_string());
+        assert_eq!(bad_callback.validate().unwrap_err()[0].field, "callback_url");
+
+        let mut too_many = request("rust");
+        too_many.selectors = Some((0..51).map(|i| (format!("f{}", i), "h1".to_string())).collect());
+        let errors = too_many.validate().unwrap_err();
+        assert_eq!(errors[0].field, "selectors");
+
+        let mut bad_stealth = request("rust");
+        bad_stealth.stealth = Some(crate::stealth::StealthConfig { hardware_concurrency: Some(0), ..Default::default() });
+        assert_eq!(bad_stealth.validate().unwrap_err()[0].field, "stealth");
+
+        let mut too_deep = request("rust");
+        too_deep.max_deep_extract = Some(crawler::MAX_DEEP_EXTRACT + 1);
+        assert_eq!(too_deep.validate().unwrap_err()[0].field, "max_deep_extract");
+    }
+
+    #[test]
+    fn test_task_result_freshness() {
+        let crawled = chrono::NaiveDate::from_ymd_opt(2024, 1, 1).unwrap().and_hms_opt(0, 0, 0).unwrap();
+        let mut task = TaskResult {
+            id: "t".to_string(),
+            keyword: "rust".to_string(),
+            engine: "bing".to_string(),
+            status: "completed".to_string(),
+            results_json: None,
+            extracted_text: None,
+            extracted_markdown: None,
+            first_page_html: None,
+            meta_description: None,
+            meta_author: None,
+            meta_date: None,
+            entities: None,
+            category: None,
+            thumbnail_key: None,
+            results_key: None,
+            parent_task_id: None,
+            metadata: None,
+            redirect_chain: None,
+            error_message: None,
+            failure_reason: None,
+            crawled_at: Some(crawled),
+            age_seconds: None,
+            stale: false,
+        };
+
+        task.apply_freshness(crawled + chrono::Duration::seconds(90), 3600);
+        assert_eq!(task.age_seconds, Some(90));
+        assert!(!task.stale);
+
+        task.apply_freshness(crawled + chrono::Duration::hours(2), 3600);
+        assert!(task.stale);
+    }
+
+    #[test]
+    fn test_collapse_results_by_domain_and_url() {
+        let rows = vec![
+            ("rust".to_string(), 3, Some("Rust Blog".to_string()), "https://blog.rust-lang.org/".to_string()),
+            ("rust".to_string(), 1, Some("Rust".to_string()), "https://www.rust-lang.org/".to_string()),
+            ("cargo".to_string(), 2, Some("Learn".to_string()), "https://rust-lang.org/learn#intro".to_string()),
+            ("cargo".to_string(), 4, None, "https://www.rust-lang.org".to_string()),
+        ];
+
+        let by_domain = collapse_results(rows.clone(), true);
+        assert_eq!(by_domain.len(), 2);
+        assert_eq!(by_domain[0].key, "rust-lang.org");
+        assert_eq!(by_domain[0].occurrences, 3);
+        assert_eq!(by_domain[0].best_position, 1);
+        assert_eq!(by_domain[0].keywords, vec!["rust", "cargo"]);
+
+        let by_url = collapse_results(rows, false);
+        assert_eq!(by_url.len(), 3);
+        let home = by_url.iter().find(|u| u.key == "rust-lang.org").unwrap();
+        assert_eq!(home.occurrences, 2);
+        assert!(by_url.iter().any(|u| u.key == "rust-lang.org/learn"));
+    }
+
+    #[test]
+    fn test_parse_upload_csv() {
+        let csv = "\u{feff}Engine,keyword,selectors\n\
+                   google,rust programming,\n\
+                   ,\"coffee, beans\",\"title=h1;price=.price\"\n\
+                   \n\
+                   bing,widgets,\"{\"\"title\"\": \"\"h1\"\"}\"\n\
+                   bing,broken,nope\n";
+        let rows = parse_upload_csv(csv.as_bytes(), 10).unwrap();
+        assert_eq!(rows.len(), 4);
+        assert_eq!(rows[0].keyword, "rust programming");
+        assert_eq!(rows[0].engine.as_deref(), Some("google"));
+        assert_eq!(rows[0].selectors, Ok(None));
+        assert_eq!(rows[1].keyword, "coffee, beans");
+        assert_eq!(rows[1].engine, None);
+        assert_eq!(rows[1].selectors.as_ref().unwrap().as_ref().unwrap()["price"], ".price");
+        assert_eq!(rows[2].selectors.as_ref().unwrap().as_ref().unwrap()["title"], "h1");
+        assert!(rows[3].selectors.is_err());
+        assert_eq!(rows[3].line, 6);
+
+        assert!(parse_upload_csv(b"engine\nbing\n", 10).is_err());
+        assert!(parse_upload_csv(csv.as_bytes(), 2).is_err());
+    }
+
+    #[test]
+    fn test_normalize_keyword() {
+        assert_eq!(normalize_keyword(" Rust  Programming \t", false), "Rust Programming");
+        assert_eq!(normalize_keyword(" Rust  Programming ", true), "rust programming");
+        assert_eq!(normalize_keyword("rust", true), "rust");
+    }
+
+    #[test]
+    fn test_task_filters_sql() {
+        let query = ListTasksQuery {
+            status: Some("failed".to_string()),
+            engine: Some(" ".to_string()),
+            keyword: Some("50%_off".to_string()),
+            ..Default::default()
+        };
+        let mut builder = sqlx::QueryBuilder::new("SELECT COUNT(*) FROM tasks");
+        push_task_filters(&mut builder, &query);
+        assert_eq!(builder.sql(), "SELECT COUNT(*) FROM tasks WHERE status = $1 AND keyword ILIKE $2");
+        assert_eq!(escape_like("50%_off"), "50\\%\\_off");
+
+        let mut builder = sqlx::QueryBuilder::new("SELECT COUNT(*) FROM tasks");
+        push_task_filters(&mut builder, &ListTasksQuery::default());
+        assert_eq!(builder.sql(), "SELECT COUNT(*) FROM tasks");
+    }
+
+    #[test]
+    fn test_task_artifact_keys() {
+        let pages = serde_json::json!([
+            { "html_key": "bing/t1/0.html", "screenshot_key": "bing/t1/screenshot.png" },
+            { "html_key": null, "screenshot_key": null },
+            { "html_key": "bing/t1/2.html" }
+        ]);
+        let keys = task_artifact_keys(Some(&pages), Some("bing/t1-thumb.jpg"), Some("bing/t1/result.json"));
+        assert_eq!(keys, vec![
+            ("html", Some(0), "bing/t1/0.html".to_string()),
+            ("screenshot", Some(0), "bing/t1/screenshot.png".to_string()),
+            ("html", Some(2), "bing/t1/2.html".to_string()),
+            ("thumbnail", None, "bing/t1-thumb.jpg".to_string()),
+            ("results", None, "bing/t1/result.json".to_string()),
+        ]);
+        assert!(task_artifact_keys(None, None, None).is_empty());
+        assert_eq!(screenshot_object_key("bing", "t1", 0), "bing/t1/screenshot.png");
+        assert_eq!(screenshot_object_key("bing", "t1", 2), "bing/t1/screenshot-2.png");
+        assert_eq!(results_object_key("bing", "t1"), "bing/t1/result.json");
+    }
+
+    #[test]
+    fn test_stored_page_keys_prefers_column() {
+        let column = serde_json::json!([{ "html_key": "bing/t1/0.html" }]);
+        // Trimmed results_json: no pages left, but the column still has the keys
+        let trimmed = r#"{"results": [], "truncated_fields": ["pages"]}"#;
+        assert_eq!(stored_page_keys(Some(column.clone()), Some(trimmed)), Some(column));
+        assert_eq!(stored_page_keys(None, Some(trimmed)), None);
+
+        // Legacy rows: fall back to results_json.pages
+        let legacy = r#"{"pages": [{ "html_key": "bing/t1/0.html", "title": "x" }]}"#;
+        let pages = stored_page_keys(None, Some(legacy)).unwrap();
+        assert_eq!(task_artifact_keys(Some(&pages), None, None), vec![("html", Some(0), "bing/t1/0.html".to_string())]);
+    }
+
+    #[test]
+    fn test_serp_results_csv() {
+        let results = vec![crawler::SearchResult {
+            title: "Rust, the language".to_string(),
+            link: "https://www.rust-lang.org/".to_string(),
+            snippet: "A \"safe\" systems language".to_string(),
+        }];
+        let csv: Vec<u8> = serp_results_csv(results.clone(), false).flat_map(Result::unwrap).collect();
+        assert_eq!(
+            String::from_utf8(csv).unwrap(),
+            "title,link,snippet\n\"Rust, the language\",https://www.rust-lang.org/,\"A \"\"safe\"\" systems language\"\n"
+        );
+        let with_bom: Vec<u8> = serp_results_csv(results, true).flat_map(Result::unwrap).collect();
+        assert!(with_bom.starts_with("\u{feff}title,".as_bytes()));
+    }
+}
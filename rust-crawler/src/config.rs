@@ -0,0 +1,20 @@
+//! Small helpers for reading optional settings from the environment.
+
+use std::str::FromStr;
+
+/// Parse an env var, falling back to `default` when unset or malformed
+pub fn env_parse<T: FromStr>(key: &str, default: T) -> T {
+    std::env::var(key)
+        .ok()
+        .and_then(|s| s.trim().parse().ok())
+        .unwrap_or(default)
+}
+
+/// Read a boolean flag (`true/1/yes/on` vs `false/0/no/off`), falling back to `default`
+pub fn env_flag(key: &str, default: bool) -> bool {
+    match std::env::var(key).map(|v| v.trim().to_lowercase()) {
+        Ok(v) if matches!(v.as_str(), "true" | "1" | "yes" | "on") => true,
+        Ok(v) if matches!(v.as_str(), "false" | "0" | "no" | "off") => false,
+        _ => default,
+    }
+}
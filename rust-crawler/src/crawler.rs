@@ -1,4 +1,3 @@
-use headless_chrome::{Browser, LaunchOptions};
 use anyhow::Result;
 use scraper::{Html, Selector};
 use serde::{Deserialize, Serialize};
@@ -7,21 +6,160 @@ use std::time::Duration;
 use tokio::time::sleep;
 use once_cell::sync::Lazy;
 use regex::Regex;
+use crate::config::env_parse;
+
+pub mod browser;
+pub mod markdown;
+pub mod politeness;
+pub mod pool;
+pub mod reaper;
+pub mod robots;
 
 // Import from new proxy module
-use crate::proxy::{PROXY_MANAGER, generate_proxy_auth_extension};
+use crate::proxy::PROXY_MANAGER;
+use browser::StealthBrowser;
+
+/// Operating system a User-Agent claims
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Platform {
+    Windows,
+    MacOs,
+    Android,
+    Ios,
+}
+
+impl Platform {
+    /// Substring identifying this platform in a User-Agent
+    pub fn ua_token(self) -> &'static str {
+        match self {
+            Platform::Windows => "Windows NT",
+            Platform::MacOs => "Macintosh",
+            Platform::Android => "Android",
+            Platform::Ios => "iPhone",
+        }
+    }
+
+    /// `navigator.platform` reported by real browsers on this OS
+    pub fn navigator_platform(self) -> &'static str {
+        match self {
+            Platform::Windows => "Win32",
+            Platform::MacOs => "MacIntel",
+            Platform::Android => "Linux armv81",
+            Platform::Ios => "iPhone",
+        }
+    }
+}
+
+/// A User-Agent and the fingerprint a real browser sending it would expose,
+/// so the stealth script never pairs a Mac UA with an Intel-on-Windows GPU
+#[derive(Debug, Clone, Copy)]
+pub struct UserAgentProfile {
+    pub user_agent: &'static str,
+    pub platform: Platform,
+    pub webgl_vendor: &'static str,
+    pub webgl_renderer: &'static str,
+    pub hardware_concurrency: u32,
+}
+
+impl UserAgentProfile {
+    pub fn stealth_config(&self) -> crate::stealth::StealthConfig {
+        crate::stealth::StealthConfig {
+            hardware_concurrency: Some(self.hardware_concurrency),
+            platform: Some(self.platform.navigator_platform().to_string()),
+            webgl_vendor: self.webgl_vendor.to_string(),
+            webgl_renderer: self.webgl_renderer.to_string(),
+            ..Default::default()
+        }
+    }
+}
 
-static USER_AGENTS: Lazy<Vec<&'static str>> = Lazy::new(|| {
+static USER_AGENTS: Lazy<Vec<UserAgentProfile>> = Lazy::new(|| {
     vec![
-        "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/123.0.0.0 Safari/537.36",
-        "Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/123.0.0.0 Safari/537.36",
-        "Mozilla/5.0 (Windows NT 10.0; Win64; x64; rv:124.0) Gecko/20100101 Firefox/124.0",
-        "Mozilla/5.0 (Macintosh; Intel Mac OS X 10.15; rv:124.0) Gecko/20100101 Firefox/124.0",
-        "Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7) AppleWebKit/605.1.15 (KHTML, like Gecko) Version/17.4 Safari/605.1.15",
-        "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Edge/123.0.0.0 Safari/537.36",
+        UserAgentProfile {
+            user_agent: "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/123.0.0.0 Safari/537.36",
+            platform: Platform::Windows,
+            webgl_vendor: "Google Inc. (Intel)",
+            webgl_renderer: "ANGLE (Intel, Intel(R) UHD Graphics 630 Direct3D11 vs_5_0 ps_5_0, D3D11)",
+            hardware_concurrency: 8,
+        },
+        UserAgentProfile {
+            user_agent: "Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/123.0.0.0 Safari/537.36",
+            platform: Platform::MacOs,
+            webgl_vendor: "Google Inc. (Apple)",
+            webgl_renderer: "ANGLE (Apple, Apple M1, OpenGL 4.1)",
+            hardware_concurrency: 8,
+        },
+        UserAgentProfile {
+            user_agent: "Mozilla/5.0 (Windows NT 10.0; Win64; x64; rv:124.0) Gecko/20100101 Firefox/124.0",
+            platform: Platform::Windows,
+            webgl_vendor: "NVIDIA Corporation",
+            webgl_renderer: "NVIDIA GeForce GTX 980, or similar",
+            hardware_concurrency: 12,
+        },
+        UserAgentProfile {
+            user_agent: "Mozilla/5.0 (Macintosh; Intel Mac OS X 10.15; rv:124.0) Gecko/20100101 Firefox/124.0",
+            platform: Platform::MacOs,
+            webgl_vendor: "Apple",
+            webgl_renderer: "Apple M1, or similar",
+            hardware_concurrency: 8,
+        },
+        UserAgentProfile {
+            user_agent: "Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7) AppleWebKit/605.1.15 (KHTML, like Gecko) Version/17.4 Safari/605.1.15",
+            platform: Platform::MacOs,
+            webgl_vendor: "Apple Inc.",
+            webgl_renderer: "Apple GPU",
+            hardware_concurrency: 8,
+        },
+        UserAgentProfile {
+            user_agent: "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Edge/123.0.0.0 Safari/537.36",
+            platform: Platform::Windows,
+            webgl_vendor: "Google Inc. (NVIDIA)",
+            webgl_renderer: "ANGLE (NVIDIA, NVIDIA GeForce RTX 3060 Direct3D11 vs_5_0 ps_5_0, D3D11)",
+            hardware_concurrency: 12,
+        },
     ]
 });
 
+/// Mobile profiles (Google's last-ditch attempt)
+static MOBILE_USER_AGENTS: Lazy<Vec<UserAgentProfile>> = Lazy::new(|| {
+    vec![
+        UserAgentProfile {
+            user_agent: "Mozilla/5.0 (Linux; Android 10; K) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/124.0.0.0 Mobile Safari/537.36",
+            platform: Platform::Android,
+            webgl_vendor: "Qualcomm",
+            webgl_renderer: "Adreno (TM) 640",
+            hardware_concurrency: 8,
+        },
+        UserAgentProfile {
+            user_agent: "Mozilla/5.0 (iPhone; CPU iPhone OS 17_4_1 like Mac OS X) AppleWebKit/605.1.15 (KHTML, like Gecko) Version/17.4.1 Mobile/15E148 Safari/604.1",
+            platform: Platform::Ios,
+            webgl_vendor: "Apple Inc.",
+            webgl_renderer: "Apple GPU",
+            hardware_concurrency: 4,
+        },
+    ]
+});
+
+/// A random desktop profile
+pub fn random_profile() -> &'static UserAgentProfile {
+    use rand::seq::SliceRandom;
+    USER_AGENTS.choose(&mut rand::thread_rng()).unwrap_or(&USER_AGENTS[0])
+}
+
+/// The known profile for an exact User-Agent string
+pub fn profile_for(user_agent: &str) -> Option<&'static UserAgentProfile> {
+    USER_AGENTS.iter().chain(MOBILE_USER_AGENTS.iter()).find(|p| p.user_agent == user_agent)
+}
+
+/// Stealth values for a launch: the request's override if any, else the
+/// profile matching `user_agent`, else the built-in default
+pub fn stealth_for(user_agent: &str, requested: Option<&crate::stealth::StealthConfig>) -> crate::stealth::StealthConfig {
+    requested
+        .cloned()
+        .or_else(|| profile_for(user_agent).map(UserAgentProfile::stealth_config))
+        .unwrap_or_default()
+}
+
 // ============================================================================
 // Enhanced Data Structures for Deep Extraction
 // ============================================================================
@@ -45,8 +183,23 @@ pub struct SerpData {
     pub related_searches: Vec<String>,
     /// Featured snippet if present
     pub featured_snippet: Option<FeaturedSnippet>,
-    /// Total results count (if shown)
+    /// Total results count (if shown), raw engine text
     pub total_results: Option<String>,
+    /// `total_results` parsed to a number (locale separators and "About"-style wording stripped)
+    #[serde(default)]
+    pub total_results_count: Option<u64>,
+    /// Results removed by include/exclude URL patterns
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub filtered_results: Vec<SearchResult>,
+    /// Fields emptied to keep the stored result under RESULT_MAX_BYTES
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub truncated_fields: Vec<String>,
+    /// Deep-extracted pages of the top `max_deep_extract` results, in rank order
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub pages: Vec<WebsiteData>,
+    /// Result URLs not deep-extracted because robots.txt disallows them (`respect_robots`)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub robots_disallowed: Vec<String>,
 }
 
 /// Featured snippet content
@@ -68,14 +221,48 @@ pub struct WebsiteData {
     pub meta_keywords: Option<String>,
     pub meta_author: Option<String>,
     pub meta_date: Option<String>,
+    /// Publication date (ISO-8601), first match across meta/JSON-LD/microdata/<time>
+    pub published_date: Option<String>,
+    /// Last-modified date (ISO-8601)
+    pub modified_date: Option<String>,
+    /// Author/byline, first match across meta/JSON-LD/rel=author/microdata/byline markup
+    pub author: Option<String>,
     
     // Content extraction
     pub main_text: String,
+    /// Readability's article HTML as Markdown (headings, lists, links kept)
+    #[serde(default)]
+    pub main_markdown: String,
     // HTML content (for saving to file)
     #[serde(skip)] 
     pub html: String,
     pub word_count: u32,
+    /// word_count / READING_WPM (default 230), rounded up
+    #[serde(default)]
+    pub reading_time_minutes: u32,
+    #[serde(default)]
+    pub text_stats: TextStats,
     pub html_size: u32,
+    /// Charset from the Content-Type header, else from <meta>
+    #[serde(default)]
+    pub declared_charset: Option<String>,
+    /// Charset the raw response bytes point to (see `detect_charset`); None when
+    /// the bytes were unavailable or pure ASCII
+    #[serde(default)]
+    pub detected_charset: Option<String>,
+    /// Declared and detected charsets disagree (misconfigured server; text may be garbled)
+    #[serde(default)]
+    pub charset_mismatch: bool,
+    /// Every hop from the requested URL to `final_url` (requested URL first)
+    #[serde(default)]
+    pub redirect_chain: Vec<RedirectHop>,
+
+    // E-commerce (schema.org Offer, product meta, microdata, .price markup)
+    #[serde(default)]
+    pub price: Option<f64>,
+    /// ISO 4217 code
+    #[serde(default)]
+    pub currency: Option<String>,
     
     // Structured data (JSON-LD, Schema.org)
     pub schema_org: Vec<serde_json::Value>,
@@ -85,6 +272,14 @@ pub struct WebsiteData {
     pub og_description: Option<String>,
     pub og_image: Option<String>,
     pub og_type: Option<String>,
+
+    /// AMP alternate (`<link rel="amphtml">`), absolute URL
+    #[serde(default)]
+    pub amp_url: Option<String>,
+
+    /// Forms on the page (contact, newsletter, demo request, ...)
+    #[serde(default)]
+    pub forms: Vec<FormData>,
     
     // Contact information
     pub emails: Vec<String>,
@@ -94,13 +289,108 @@ pub struct WebsiteData {
     pub images: Vec<ImageData>,
     
     // Links
-    pub outbound_links: Vec<String>,
+    pub outbound_links: Vec<OutboundLink>,
+    /// Social profile per platform (linkedin, twitter, facebook, instagram, github)
+    #[serde(default)]
+    pub social_links: std::collections::HashMap<String, String>,
+
+    // Resource inventory (absolute URLs of external scripts / stylesheets)
+    #[serde(default)]
+    pub scripts: Vec<String>,
+    #[serde(default)]
+    pub stylesheets: Vec<String>,
     
     // ML Analysis
     pub sentiment: Option<String>,
+    /// Organizations, locations and dates mentioned in `main_text` (only with `extract_entities`)
+    #[serde(default)]
+    pub entities: Vec<crate::ml::EntityMention>,
+    /// Coarse page kind (article, product, forum, homepage, directory, listing)
+    pub page_type: Option<String>,
+    /// 0-100 review priority from content length, structured data, meta completeness,
+    /// images and text-to-HTML ratio (see `compute_quality_score`)
+    #[serde(default)]
+    pub quality_score: u8,
     
     // Marketing / Selling Points
     pub marketing_data: Option<MarketingData>,
+
+    // Full-page JPEG screenshot (only when requested; stored in MinIO, not in JSON)
+    #[serde(skip)]
+    pub thumbnail: Option<Vec<u8>>,
+    // Full-resolution PNG screenshot (`screenshot` requests); uploaded by the worker
+    #[serde(skip)]
+    pub screenshot: Option<Vec<u8>>,
+    /// MinIO key of this page's PNG screenshot, once uploaded
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub screenshot_key: Option<String>,
+    /// MinIO key of this page's raw HTML, once uploaded
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub html_key: Option<String>,
+}
+
+/// Cheap readability signals computed from `main_text`
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+pub struct TextStats {
+    pub sentence_count: u32,
+    /// Words per sentence
+    pub avg_sentence_length: f64,
+    pub avg_syllables_per_word: f64,
+    /// Flesch reading ease (higher = easier; English heuristics, None for empty text)
+    pub flesch_reading_ease: Option<f64>,
+}
+
+/// Rough English syllable count: vowel groups, minus a silent trailing "e"
+fn count_syllables(word: &str) -> u32 {
+    let letters: Vec<char> = word.chars().filter(|c| c.is_alphabetic()).flat_map(|c| c.to_lowercase()).collect();
+    if letters.is_empty() {
+        return 0;
+    }
+    let is_vowel = |c: char| "aeiouy".contains(c);
+    let mut count = 0;
+    let mut prev_vowel = false;
+    for &c in &letters {
+        let vowel = is_vowel(c);
+        if vowel && !prev_vowel {
+            count += 1;
+        }
+        prev_vowel = vowel;
+    }
+    let n = letters.len();
+    if n > 2 && letters[n - 1] == 'e' && letters[n - 2] != 'l' && !is_vowel(letters[n - 2]) && count > 1 {
+        count -= 1;
+    }
+    count.max(1)
+}
+
+pub fn compute_text_stats(text: &str) -> TextStats {
+    let words: Vec<&str> = text.split_whitespace().collect();
+    if words.is_empty() {
+        return TextStats::default();
+    }
+    let sentence_count = text
+        .split(['.', '!', '?'])
+        .filter(|s| s.split_whitespace().next().is_some())
+        .count()
+        .max(1) as u32;
+    let syllables: u32 = words.iter().map(|w| count_syllables(w)).sum();
+
+    let words_per_sentence = words.len() as f64 / sentence_count as f64;
+    let syllables_per_word = syllables as f64 / words.len() as f64;
+    let round2 = |v: f64| (v * 100.0).round() / 100.0;
+
+    TextStats {
+        sentence_count,
+        avg_sentence_length: round2(words_per_sentence),
+        avg_syllables_per_word: round2(syllables_per_word),
+        flesch_reading_ease: Some(round2(206.835 - 1.015 * words_per_sentence - 84.6 * syllables_per_word)),
+    }
+}
+
+/// Minutes to read `word_count` words at READING_WPM (default 230)
+pub fn reading_time_minutes(word_count: u32) -> u32 {
+    let wpm: u32 = env_parse("READING_WPM", 230u32).max(1);
+    word_count.div_ceil(wpm)
 }
 
 /// Marketing and Selling Point Data
@@ -122,6 +412,24 @@ pub struct ImageData {
     pub title: Option<String>,
 }
 
+/// Summary of a `<form>` on the page
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct FormData {
+    /// Absolute submit URL (None = submits to the page itself)
+    pub action: Option<String>,
+    /// Upper-cased HTTP method (GET when unspecified)
+    pub method: String,
+    pub fields: Vec<FieldData>,
+}
+
+/// A named input/select/textarea inside a form
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct FieldData {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub field_type: String,
+}
+
 /// Complete crawl result with all extracted data
 #[derive(Debug, Serialize, Deserialize, Clone, Default)]
 pub struct CrawlResult {
@@ -153,6 +461,802 @@ pub struct Cookie {
 // Map domain to list of cookies
 pub type CookieMap = std::collections::HashMap<String, Vec<Cookie>>;
 
+// Digit groups with thousands separators ("1,230,000", "1.230.000", "1 230 000", "1'230'000") or plain digits
+static RESULT_COUNT_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"\d{1,3}(?:[,.'\s\u{a0}\u{202f}]\d{3})+|\d+").unwrap()
+});
+
+/// Parse an engine's result-count text into a number.
+/// Handles "About 1,230,000 results (0.45 seconds)", "1.230.000 Ergebnisse",
+/// "Environ 1 230 000 résultats", "約 1,230,000 件" and "1-10 of 1,230,000 results"
+/// by taking the largest number in the text.
+pub fn parse_result_count(raw: &str) -> Option<u64> {
+    RESULT_COUNT_REGEX
+        .find_iter(raw)
+        .filter_map(|m| {
+            let digits: String = m.as_str().chars().filter(|c| c.is_ascii_digit()).collect();
+            digits.parse::<u64>().ok()
+        })
+        .max()
+}
+
+/// Split an EXTRA_CHROME_ARGS-style string on whitespace, keeping
+/// double-quoted sections together (quotes removed):
+/// `--host-resolver-rules="MAP * 1.2.3.4"` stays one argument.
+pub fn split_chrome_args(raw: &str) -> Vec<String> {
+    let mut args = Vec::new();
+    let mut current = String::new();
+    let mut in_quotes = false;
+    for c in raw.chars() {
+        match c {
+            '"' => in_quotes = !in_quotes,
+            c if c.is_whitespace() && !in_quotes => {
+                if !current.is_empty() {
+                    args.push(std::mem::take(&mut current));
+                }
+            }
+            c => current.push(c),
+        }
+    }
+    if !current.is_empty() {
+        args.push(current);
+    }
+    args
+}
+
+/// A well-formed Chrome switch: `--name` or `--name=value`
+pub fn is_valid_chrome_flag(arg: &str) -> bool {
+    static FLAG_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"^--[a-zA-Z0-9][a-zA-Z0-9-]*(=.*)?$").unwrap());
+    FLAG_REGEX.is_match(arg)
+}
+
+/// Per-phase browser waits, tunable per deployment (static sites need far less
+/// than heavy SPAs). Randomized "human" pauses are pacing, not timeouts, and stay as-is.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Timeouts {
+    /// Wait for a navigated page's `<body>` (`NAVIGATION_TIMEOUT_MS`, default 15000)
+    pub navigation: Duration,
+    /// Pause for the DOM to settle after a search submit, consent click or autocorrect
+    /// reload; shorter in-page pauses take a third of it (`DOM_SETTLE_MS`, default 3000)
+    pub dom_settle: Duration,
+    /// Hydration wait before deep extraction reads the DOM (`EXTRACTION_WAIT_MS`, default 4000)
+    pub extraction: Duration,
+    /// Wait for an interactive element to appear (`ELEMENT_WAIT_TIMEOUT_MS`, default 10000)
+    pub element_wait: Duration,
+}
+
+impl Default for Timeouts {
+    fn default() -> Self {
+        Self {
+            navigation: Duration::from_secs(15),
+            dom_settle: Duration::from_secs(3),
+            extraction: Duration::from_secs(4),
+            element_wait: Duration::from_secs(10),
+        }
+    }
+}
+
+impl Timeouts {
+    pub fn from_env() -> Self {
+        let default = Self::default();
+        let ms = |key: &str, default: Duration| Duration::from_millis(env_parse(key, default.as_millis() as u64));
+        Self {
+            navigation: ms("NAVIGATION_TIMEOUT_MS", default.navigation),
+            dom_settle: ms("DOM_SETTLE_MS", default.dom_settle),
+            extraction: ms("EXTRACTION_WAIT_MS", default.extraction),
+            element_wait: ms("ELEMENT_WAIT_TIMEOUT_MS", default.element_wait),
+        }
+    }
+}
+
+pub static TIMEOUTS: Lazy<Timeouts> = Lazy::new(Timeouts::from_env);
+
+/// Per-request knobs for SERP crawls
+#[derive(Debug, Clone, Default)]
+pub struct SearchOptions {
+    /// Engine-specific URL parameters (e.g. Google `tbs`, Bing `freshness`)
+    pub engine_options: std::collections::HashMap<String, String>,
+    /// Visit a neutral page and idle before searching (see `warmup_navigation`)
+    pub warmup: bool,
+    /// Per-request retry budget; falls back to `<ENGINE>_MAX_ATTEMPTS`
+    pub max_attempts: Option<u32>,
+    /// SERP pages to scrape by following "Next" (0 or 1 = first page only)
+    pub pages: u32,
+    /// Fingerprint override for the injected stealth script; None matches the UA's profile
+    pub stealth: Option<crate::stealth::StealthConfig>,
+    /// Keep every browser on the proxy bound to this key (see `ProxyManager::get_sticky_proxy`)
+    pub session_key: Option<String>,
+}
+
+/// Next proxy from the pool, or the one bound to `session_key` for sticky crawls.
+/// When every proxy is at PROXY_MAX_CONCURRENT, waits up to PROXY_WAIT_SECS
+/// (default 30) for a lease to free up. `Ok(None)` means no proxies are configured.
+async fn select_proxy(engine: Option<&str>, session_key: Option<&str>) -> Result<Option<crate::proxy::ProxyLease>> {
+    let deadline = std::time::Instant::now() + Duration::from_secs(env_parse("PROXY_WAIT_SECS", 30));
+    loop {
+        let lease = match session_key {
+            Some(key) => PROXY_MANAGER.get_sticky_proxy_for(key, engine),
+            None => PROXY_MANAGER.get_next_proxy_for(engine),
+        };
+        if lease.is_some() || !PROXY_MANAGER.has_proxies() {
+            return Ok(lease);
+        }
+        if std::time::Instant::now() >= deadline {
+            anyhow::bail!("No proxy under PROXY_MAX_CONCURRENT became free within PROXY_WAIT_SECS");
+        }
+        // Idle warm browsers would otherwise keep their proxy slots indefinitely
+        if let Some(browser_pool) = pool::BROWSER_POOL.as_ref() {
+            let retired = browser_pool.retire_idle();
+            if retired > 0 {
+                println!("🧰 [BrowserPool] Retired {} idle browser(s) to free proxy slots", retired);
+                continue;
+            }
+        }
+        sleep(Duration::from_millis(250)).await;
+    }
+}
+
+/// Upper bound on search attempts, whatever the config says
+pub const MAX_SEARCH_ATTEMPTS: u32 = 10;
+
+/// Retry budget for an engine: the per-request override if given, else
+/// GOOGLE_MAX_ATTEMPTS / BING_MAX_ATTEMPTS (default 3), clamped to 1..=10
+pub fn max_attempts_for(engine: &str, override_attempts: Option<u32>) -> u32 {
+    let configured = || env_parse(&format!("{}_MAX_ATTEMPTS", engine.to_uppercase()), 3u32);
+    override_attempts.unwrap_or_else(configured).clamp(1, MAX_SEARCH_ATTEMPTS)
+}
+
+// ============================================================================
+// HTTP-level rate limiting (429/503 on the engine's own pages)
+// ============================================================================
+
+/// The engine answered the main document with 429/503. Chrome still renders
+/// the error page, so without this the attempt just looks like "0 results".
+#[derive(Debug)]
+pub struct RateLimited {
+    pub engine: &'static str,
+    pub status: u16,
+}
+
+impl std::fmt::Display for RateLimited {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} rate limited (HTTP {})", self.engine, self.status)
+    }
+}
+
+impl std::error::Error for RateLimited {}
+
+/// Why a search ended without results, stored with the failed task
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FailureReason {
+    /// The engine served a captcha / challenge page
+    ChallengeDetected,
+    /// The SERP came back smaller than SERP_MIN_HTML_BYTES (blank or error page)
+    PageTooSmall,
+    /// The page rendered but no organic results were found
+    NoResultsFound,
+}
+
+impl FailureReason {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FailureReason::ChallengeDetected => "challenge_detected",
+            FailureReason::PageTooSmall => "page_too_small",
+            FailureReason::NoResultsFound => "no_results_found",
+        }
+    }
+}
+
+/// A search failure with a known category; other errors carry no reason
+#[derive(Debug)]
+pub struct SearchFailed {
+    pub reason: FailureReason,
+    pub message: String,
+}
+
+impl std::fmt::Display for SearchFailed {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} ({})", self.message, self.reason.as_str())
+    }
+}
+
+impl std::error::Error for SearchFailed {}
+
+impl SearchFailed {
+    fn new(reason: FailureReason, message: impl Into<String>) -> anyhow::Error {
+        anyhow::Error::new(SearchFailed { reason, message: message.into() })
+    }
+}
+
+/// Failure category of a search error, if it has one
+pub fn failure_reason(error: &anyhow::Error) -> Option<FailureReason> {
+    error.downcast_ref::<SearchFailed>().map(|e| e.reason)
+}
+
+/// A SERP under SERP_MIN_HTML_BYTES (default 2048) is a blank or error page, not results
+fn is_serp_too_small(html: &str) -> bool {
+    html.len() < env_parse("SERP_MIN_HTML_BYTES", 2048usize)
+}
+
+pub fn is_rate_limit_status(status: u16) -> bool {
+    status == 429 || status == 503
+}
+
+/// Backoff before retrying a rate-limited attempt: RATE_LIMIT_BACKOFF_SECS
+/// (default 30) doubled per attempt, capped at RATE_LIMIT_BACKOFF_MAX_SECS (default 300)
+pub fn rate_limit_backoff(attempt: u32) -> Duration {
+    let base: u64 = env_parse("RATE_LIMIT_BACKOFF_SECS", 30);
+    let max: u64 = env_parse("RATE_LIMIT_BACKOFF_MAX_SECS", 300);
+    let factor = 1u64 << attempt.saturating_sub(1).min(16);
+    Duration::from_secs(base.saturating_mul(factor).min(max))
+}
+
+/// External link with its `rel` tokens (nofollow, sponsored, ugc, ...) and anchor text
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct OutboundLink {
+    pub url: String,
+    pub rel: Vec<String>,
+    pub anchor_text: String,
+}
+
+/// One hop of the main document's redirect chain
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct RedirectHop {
+    pub url: String,
+    pub status: u16,
+}
+
+/// Latest main-frame document response seen on a tab
+#[derive(Default)]
+pub struct DocumentResponse {
+    /// HTTP status (0 = none yet)
+    status: std::sync::atomic::AtomicU32,
+    /// Content-Type response header
+    content_type: std::sync::Mutex<Option<String>>,
+    /// URL of the final (non-redirect) response
+    url: std::sync::Mutex<Option<String>>,
+    /// 3xx hops of the current navigation, in order
+    redirects: std::sync::Mutex<Vec<RedirectHop>>,
+}
+
+impl DocumentResponse {
+    /// Every hop of the last navigation, ending with the final response
+    pub fn redirect_chain(&self) -> Vec<RedirectHop> {
+        let mut chain = self.redirects.lock().map(|r| r.clone()).unwrap_or_default();
+        if let Some(url) = self.url.lock().ok().and_then(|u| u.clone()) {
+            chain.push(RedirectHop { url, status: self.status() });
+        }
+        chain
+    }
+
+    pub fn status(&self) -> u16 {
+        self.status.load(std::sync::atomic::Ordering::Relaxed) as u16
+    }
+
+    pub fn content_type(&self) -> Option<String> {
+        self.content_type.lock().ok().and_then(|c| c.clone())
+    }
+}
+
+/// Record status, Content-Type and redirect hops of main-frame document
+/// responses via CDP Network events.
+fn track_document_response(tab: &std::sync::Arc<headless_chrome::Tab>) -> std::sync::Arc<DocumentResponse> {
+    use headless_chrome::protocol::cdp::Network::{events::ResponseReceivedEventParams, GetResponseBodyReturnObject, ResourceType};
+    use headless_chrome::protocol::cdp::types::Event;
+
+    let document = std::sync::Arc::new(DocumentResponse::default());
+    let recorder = document.clone();
+    let main_frame = tab.get_target_id().clone();
+    let handler = Box::new(move |params: ResponseReceivedEventParams, _body: &dyn Fn() -> Result<GetResponseBodyReturnObject>| {
+        if params.Type == ResourceType::Document && params.frame_id.as_deref() == Some(main_frame.as_str()) {
+            recorder.status.store(params.response.status as u32, std::sync::atomic::Ordering::Relaxed);
+            if let Ok(mut url) = recorder.url.lock() {
+                *url = Some(params.response.url.clone());
+            }
+            // Header names are case-insensitive; CDP passes them as a JSON object
+            let content_type = serde_json::to_value(&params.response.headers)
+                .ok()
+                .and_then(|h| h.as_object().and_then(|h| {
+                    h.iter()
+                        .find(|(name, _)| name.eq_ignore_ascii_case("content-type"))
+                        .and_then(|(_, v)| v.as_str().map(|s| s.to_string()))
+                }));
+            if let Ok(mut slot) = recorder.content_type.lock() {
+                *slot = content_type;
+            }
+        }
+    });
+    if let Err(e) = tab.register_response_handling("document_response", handler) {
+        eprintln!("⚠️ Could not track document responses: {}", e);
+    }
+
+    // Redirects never reach responseReceived; each hop shows up as a new
+    // request carrying the previous 3xx response
+    let hops = document.clone();
+    let main_frame = tab.get_target_id().clone();
+    let listener = std::sync::Arc::new(move |event: &Event| {
+        if let Event::NetworkRequestWillBeSent(e) = event {
+            let params = &e.params;
+            if params.Type != Some(ResourceType::Document) || params.frame_id.as_deref() != Some(main_frame.as_str()) {
+                return;
+            }
+            if let Ok(mut redirects) = hops.redirects.lock() {
+                match params.redirect_response {
+                    Some(ref response) => redirects.push(RedirectHop { url: response.url.clone(), status: response.status as u16 }),
+                    // A fresh navigation starts a new chain
+                    None => redirects.clear(),
+                }
+            }
+        }
+    });
+    if let Err(e) = tab.add_event_listener(listener) {
+        eprintln!("⚠️ Could not track redirects: {}", e);
+    }
+    document
+}
+
+/// How long a proxy is kept off an engine after a challenge/rate limit:
+/// GOOGLE_CHALLENGE_COOLDOWN_SECS (default 900; Google IP cooldowns run to minutes)
+/// or BING_CHALLENGE_COOLDOWN_SECS (default 300), <ENGINE>_CHALLENGE_COOLDOWN_SECS otherwise.
+pub fn challenge_cooldown_secs(engine: &str) -> i64 {
+    let default = if engine == "google" { 900 } else { 300 };
+    env_parse(&format!("{}_CHALLENGE_COOLDOWN_SECS", engine.to_uppercase()), default)
+}
+
+/// Put the proxy that hit a challenge/rate limit into cooldown for this engine only
+fn cool_down_proxy(engine: &str, proxy_id: Option<&str>) {
+    if let Some(id) = proxy_id {
+        PROXY_MANAGER.block_for_engine(id, engine, challenge_cooldown_secs(engine));
+    }
+}
+
+/// Fail the attempt with `RateLimited` if the last document came back 429/503,
+/// counting it against the proxy and cooling it down on this engine.
+fn check_rate_limit(
+    document: &DocumentResponse,
+    engine: &'static str,
+    proxy_id: Option<&str>,
+) -> Result<()> {
+    let status = document.status();
+    if !is_rate_limit_status(status) {
+        return Ok(());
+    }
+    println!("🚦 RATE LIMITED: {} answered HTTP {}", engine, status);
+    if let Some(id) = proxy_id {
+        PROXY_MANAGER.mark_failure(id);
+    }
+    cool_down_proxy(engine, proxy_id);
+    Err(RateLimited { engine, status }.into())
+}
+
+// ============================================================================
+// Charset reporting
+// ============================================================================
+
+static META_CHARSET_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"(?i)charset\s*=\s*["']?([A-Za-z0-9._:-]+)"#).unwrap()
+});
+
+/// `charset=` parameter of a Content-Type value, lowercased
+pub fn charset_from_content_type(content_type: &str) -> Option<String> {
+    META_CHARSET_REGEX
+        .captures(content_type)
+        .map(|c| c[1].to_lowercase())
+}
+
+/// Charset declared in markup: `<meta charset>` or `<meta http-equiv="Content-Type">`
+pub fn extract_meta_charset(document: &Html) -> Option<String> {
+    let charset_sel = Selector::parse("meta[charset]").unwrap();
+    if let Some(charset) = document.select(&charset_sel).next().and_then(|e| e.value().attr("charset")) {
+        let charset = charset.trim().to_lowercase();
+        if !charset.is_empty() {
+            return Some(charset);
+        }
+    }
+    let http_equiv_sel = Selector::parse("meta[http-equiv][content]").unwrap();
+    document
+        .select(&http_equiv_sel)
+        .filter(|e| e.value().attr("http-equiv").is_some_and(|v| v.eq_ignore_ascii_case("content-type")))
+        .find_map(|e| e.value().attr("content").and_then(charset_from_content_type))
+}
+
+/// Canonical label for comparing charsets: case/punctuation-insensitive, with
+/// the aliases browsers treat as identical (latin1 is decoded as windows-1252).
+fn canonical_charset(charset: &str) -> String {
+    let label: String = charset.chars().filter(|c| c.is_ascii_alphanumeric()).collect::<String>().to_lowercase();
+    match label.as_str() {
+        "utf8" | "unicode11utf8" => "utf8".to_string(),
+        "latin1" | "iso88591" | "usascii" | "ascii" | "windows1252" | "cp1252" => "windows1252".to_string(),
+        "shiftjis" | "sjis" | "mskanji" | "windows31j" => "shiftjis".to_string(),
+        "gb2312" | "gbk" => "gbk".to_string(),
+        _ => label,
+    }
+}
+
+/// How many leading body bytes are inspected for charset detection
+const CHARSET_SNIFF_BYTES: usize = 64 * 1024;
+
+/// Re-reads the main document's body from Chrome's HTTP cache (never the network)
+/// as hex, truncated to CHARSET_SNIFF_BYTES. Fails for uncacheable (no-store) pages.
+const RAW_DOCUMENT_BYTES_JS: &str = r#"
+(async () => {
+    const resp = await fetch(location.href, { cache: 'only-if-cached', mode: 'same-origin' });
+    const bytes = new Uint8Array(await resp.arrayBuffer()).subarray(0, 65536);
+    return Array.from(bytes, b => b.toString(16).padStart(2, '0')).join('');
+})()
+"#;
+
+/// Charset the raw body bytes are actually in, judged by UTF-8 validity:
+/// - valid UTF-8 with multi-byte sequences -> "utf-8"
+/// - not valid UTF-8 -> the declared charset when that is a legacy one (the bytes
+///   are consistent with it), else "windows-1252", the WHATWG fallback
+/// - pure ASCII -> None, since every ASCII-compatible charset decodes it the same
+pub fn detect_charset(bytes: &[u8], declared: Option<&str>) -> Option<String> {
+    if bytes.is_ascii() {
+        return None;
+    }
+    let valid_utf8 = match std::str::from_utf8(bytes) {
+        Ok(_) => true,
+        // A multi-byte sequence cut off by the sniff limit still counts as valid
+        Err(e) => e.error_len().is_none(),
+    };
+    if valid_utf8 {
+        return Some("utf-8".to_string());
+    }
+    match declared {
+        Some(declared) if canonical_charset(declared) != "utf8" => Some(declared.to_lowercase()),
+        _ => Some("windows-1252".to_string()),
+    }
+}
+
+/// True when a declared charset disagrees with the detected one
+pub fn charset_mismatch(declared: Option<&str>, detected: Option<&str>) -> bool {
+    match (declared, detected) {
+        (Some(declared), Some(detected)) => canonical_charset(declared) != canonical_charset(detected),
+        _ => false,
+    }
+}
+
+// Neutral pages for pre-search warm-up (override with WARMUP_URLS, comma-separated)
+static WARMUP_URLS: Lazy<Vec<String>> = Lazy::new(|| {
+    let configured: Vec<String> = std::env::var("WARMUP_URLS")
+        .unwrap_or_default()
+        .split(',')
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .collect();
+    if !configured.is_empty() {
+        return configured;
+    }
+    [
+        "https://www.wikipedia.org/",
+        "https://www.bbc.com/news",
+        "https://www.weather.com/",
+        "https://www.imdb.com/",
+        "https://www.reddit.com/",
+    ]
+    .iter()
+    .map(|s| s.to_string())
+    .collect()
+});
+
+/// Build a little browsing history before the real search: visit either the
+/// engine homepage or a random neutral site, scroll a bit and idle for
+/// WARMUP_IDLE_SECS (default 4) plus jitter. Failures are logged, never fatal.
+pub async fn warmup_navigation(tab: &std::sync::Arc<headless_chrome::Tab>, engine_home: &str) {
+    use rand::seq::SliceRandom;
+    let target = if rand::random::<bool>() {
+        engine_home.to_string()
+    } else {
+        WARMUP_URLS.choose(&mut rand::thread_rng()).cloned().unwrap_or_else(|| engine_home.to_string())
+    };
+
+    println!("☕ Warm-up: visiting {}", target);
+    if let Err(e) = tab.navigate_to(&target).and_then(|t| t.wait_until_navigated()) {
+        println!("⚠️ Warm-up navigation failed: {}", e);
+        return;
+    }
+    let _ = tab.evaluate("window.scrollBy(0, 300 + Math.floor(Math.random() * 600))", false);
+
+    let idle_secs: u64 = env_parse("WARMUP_IDLE_SECS", 4);
+    sleep(Duration::from_millis(idle_secs * 1000 + rand::random::<u64>() % 3000)).await;
+}
+
+/// Upper bound on results deep-extracted per job
+pub const MAX_DEEP_EXTRACT: u32 = 10;
+
+/// Per-request knobs for deep extraction
+#[derive(Debug, Clone, Default)]
+pub struct ExtractOptions {
+    /// Capture a full-page screenshot into `WebsiteData::thumbnail`
+    pub capture_thumbnail: bool,
+    /// Capture a full-page PNG into `WebsiteData::screenshot`
+    pub capture_screenshot: bool,
+    /// `Referer` header sent with the page request (e.g. https://www.google.com/)
+    pub referer: Option<String>,
+    /// Fixed User-Agent; a random one from the pool when None
+    pub user_agent: Option<String>,
+    /// Retry once with a different browser family's UA when the page yields
+    /// fewer words than this (0 = never). Defaults to EXTRACT_MIN_WORD_COUNT.
+    pub min_word_count: u32,
+    /// Run the local entity-mention pass over `main_text` (see `ml::extract_entity_mentions`)
+    pub extract_entities: bool,
+    /// Fingerprint override for the injected stealth script; None matches the UA's profile
+    pub stealth: Option<crate::stealth::StealthConfig>,
+    /// Keep every browser on the proxy bound to this key (see `ProxyManager::get_sticky_proxy`)
+    pub session_key: Option<String>,
+    /// Skip URLs the site's robots.txt disallows (fails with `robots::RobotsDisallowed`)
+    pub respect_robots: bool,
+    /// Let file:// URLs through (saved HTML fixtures); the worker sets it from ALLOW_FILE_URLS
+    pub allow_file_urls: bool,
+}
+
+/// Browser family of a User-Agent string
+pub fn ua_family(user_agent: &str) -> &'static str {
+    if user_agent.contains("Firefox/") {
+        "firefox"
+    } else if user_agent.contains("Edg/") || user_agent.contains("Edge/") {
+        "edge"
+    } else if user_agent.contains("Chrome/") {
+        "chrome"
+    } else if user_agent.contains("Safari/") {
+        "safari"
+    } else {
+        "other"
+    }
+}
+
+/// A UA from the pool that is structurally different from `user_agent`:
+/// Firefox for Chromium-based browsers, Chrome for everything else.
+pub fn alternate_user_agent(user_agent: &str) -> Option<&'static str> {
+    use rand::seq::SliceRandom;
+    let family = ua_family(user_agent);
+    let preferred = if family == "firefox" { "chrome" } else { "firefox" };
+    let pick = |want: &dyn Fn(&str) -> bool| {
+        USER_AGENTS
+            .iter()
+            .map(|p| p.user_agent)
+            .filter(|ua| want(ua_family(ua)))
+            .collect::<Vec<_>>()
+            .choose(&mut rand::thread_rng())
+            .copied()
+    };
+    pick(&|f| f == preferred).or_else(|| pick(&|f| f != family))
+}
+
+// ============================================================================
+// Engine Options
+// ============================================================================
+
+/// URL parameters each engine accepts via `engine_options`. Mirrors the worker's
+/// routing: google and generic run as such, every other engine runs on Bing.
+pub fn allowed_engine_options(engine: &str) -> &'static [&'static str] {
+    match engine {
+        "google" => &["tbs", "tbm", "lr", "cr", "gl", "hl", "safe", "num", "filter"],
+        "generic" => &[],
+        _ => &["freshness", "qft", "filters", "cc", "setlang", "mkt", "safesearch", "count"],
+    }
+}
+
+/// `a-z` letters only, `min..=max` long
+fn is_alpha_code(value: &str, min: usize, max: usize) -> bool {
+    (min..=max).contains(&value.len()) && value.chars().all(|c| c.is_ascii_alphabetic())
+}
+
+/// Language tag such as `en`, `de` or `zh-TW`
+fn is_language_tag(value: &str) -> bool {
+    let mut parts = value.splitn(2, '-');
+    let lang = parts.next().unwrap_or_default();
+    let region_ok = match parts.next() {
+        Some(region) => (2..=8).contains(&region.len()) && region.chars().all(|c| c.is_ascii_alphanumeric()),
+        None => true,
+    };
+    is_alpha_code(lang, 2, 3) && region_ok
+}
+
+/// Integer in `min..=max`
+fn is_count(value: &str, min: u32, max: u32) -> bool {
+    value.parse::<u32>().is_ok_and(|n| (min..=max).contains(&n))
+}
+
+/// Whether `value` is well-formed for option `key` (already known to be whitelisted)
+fn is_valid_engine_option_value(key: &str, value: &str) -> bool {
+    let one_of = |allowed: &[&str]| allowed.iter().any(|a| a.eq_ignore_ascii_case(value));
+    // Free-form filter expressions: no control characters, bounded length
+    let filter_expr = || !value.is_empty() && value.len() <= 200 && !value.chars().any(|c| c.is_control());
+    match key {
+        // e.g. qdr:w, li:1, cdr:1,cd_min:1/1/2024,cd_max:2/1/2024
+        "tbs" => filter_expr() && value.split(',').all(|part| {
+            part.split_once(':').is_some_and(|(name, arg)| {
+                !name.is_empty()
+                    && name.chars().all(|c| c.is_ascii_lowercase() || c == '_')
+                    && arg.chars().all(|c| c.is_ascii_alphanumeric() || "/._-".contains(c))
+            })
+        }),
+        "tbm" => one_of(&["isch", "nws", "vid", "shop", "bks", "lcl"]),
+        // lang_de|lang_fr
+        "lr" => value.split('|').all(|l| l.strip_prefix("lang_").is_some_and(is_language_tag)),
+        // countryDE|countryFR
+        "cr" => value.split('|').all(|c| c.strip_prefix("country").is_some_and(|code| is_alpha_code(code, 2, 2))),
+        "gl" | "cc" => is_alpha_code(value, 2, 2),
+        "hl" | "setlang" | "mkt" => is_language_tag(value),
+        "safe" => one_of(&["active", "off", "images", "strict"]),
+        "num" => is_count(value, 1, 100),
+        "filter" => one_of(&["0", "1"]),
+        "freshness" => one_of(&["day", "week", "month"]),
+        "qft" | "filters" => filter_expr(),
+        "safesearch" => one_of(&["off", "moderate", "strict"]),
+        "count" => is_count(value, 1, 50),
+        _ => false,
+    }
+}
+
+/// Reject keys that aren't whitelisted for the engine, and malformed values
+pub fn validate_engine_options(engine: &str, options: &std::collections::HashMap<String, String>) -> Result<(), String> {
+    let allowed = allowed_engine_options(engine);
+    let mut rejected: Vec<&str> = options
+        .keys()
+        .map(|k| k.as_str())
+        .filter(|k| !allowed.contains(k))
+        .collect();
+
+    if rejected.is_empty() {
+        let mut invalid: Vec<String> = options
+            .iter()
+            .filter(|(k, v)| !is_valid_engine_option_value(k, v))
+            .map(|(k, v)| format!("{}={:?}", k, v))
+            .collect();
+        if invalid.is_empty() {
+            return Ok(());
+        }
+        invalid.sort();
+        return Err(format!("Invalid engine_options values for '{}': {}", engine, invalid.join(", ")));
+    }
+    rejected.sort();
+    if allowed.is_empty() {
+        Err(format!("Engine '{}' does not accept engine_options (got: {})", engine, rejected.join(", ")))
+    } else {
+        Err(format!(
+            "Unsupported engine_options for '{}': {} (allowed: {})",
+            engine,
+            rejected.join(", "),
+            allowed.join(", ")
+        ))
+    }
+}
+
+/// Set (or override) query parameters on a URL
+pub fn apply_url_params(url: &str, params: &std::collections::HashMap<String, String>) -> String {
+    let mut parsed = match reqwest::Url::parse(url) {
+        Ok(u) => u,
+        Err(_) => return url.to_string(),
+    };
+
+    let kept: Vec<(String, String)> = parsed
+        .query_pairs()
+        .filter(|(k, _)| !params.contains_key(k.as_ref()))
+        .map(|(k, v)| (k.into_owned(), v.into_owned()))
+        .collect();
+
+    let mut extra: Vec<(&String, &String)> = params.iter().collect();
+    extra.sort();
+
+    parsed
+        .query_pairs_mut()
+        .clear()
+        .extend_pairs(kept.iter().map(|(k, v)| (k.as_str(), v.as_str())))
+        .extend_pairs(extra.iter().map(|(k, v)| (k.as_str(), v.as_str())));
+    parsed.to_string()
+}
+
+/// Put the engine options on the search box's form as hidden fields, so the
+/// search submission itself requests the filtered SERP (one navigation, not a
+/// second load of the results page). Returns false if there is no such form.
+fn add_engine_options_to_form(tab: &std::sync::Arc<headless_chrome::Tab>, options: &SearchOptions) -> Result<bool> {
+    if options.engine_options.is_empty() {
+        return Ok(true);
+    }
+    let script = format!(
+        r#"
+        (() => {{
+            const params = {};
+            const input = document.querySelector("textarea[name='q'], input[name='q']");
+            const form = input && input.form;
+            if (!form) return false;
+            for (const [name, value] of Object.entries(params)) {{
+                let field = Array.from(form.elements).find(e => e.name === name);
+                if (!field) {{
+                    field = document.createElement('input');
+                    field.type = 'hidden';
+                    field.name = name;
+                    form.appendChild(field);
+                }}
+                field.value = value;
+            }}
+            return true;
+        }})()
+        "#,
+        serde_json::to_string(&options.engine_options)?
+    );
+    Ok(tab.evaluate(&script, false)?.value.and_then(|v| v.as_bool()).unwrap_or(false))
+}
+
+/// Fallback when the search form couldn't carry the options: re-navigate the SERP
+fn reload_with_engine_options(tab: &std::sync::Arc<headless_chrome::Tab>, options: &SearchOptions) -> Result<()> {
+    let url = apply_url_params(&tab.get_url(), &options.engine_options);
+    println!("⚠️ Search form not found, reloading with engine options: {}", url);
+    tab.navigate_to(&url)?;
+    tab.wait_until_navigated()?;
+    Ok(())
+}
+
+// ============================================================================
+// SERP Pagination
+// ============================================================================
+
+/// Upper bound on SERP pages scraped per search
+pub const MAX_SERP_PAGES: u32 = 10;
+
+/// "Next page" controls
+const BING_NEXT_SELECTOR: &str = "a.sb_pagN";
+const GOOGLE_NEXT_SELECTOR: &str = "#pnnext";
+
+fn is_bing_challenge(html: &str) -> bool {
+    html.contains("Challenge") || html.contains("needs to review the security")
+}
+
+fn is_google_challenge(html: &str) -> bool {
+    html.contains("unusual traffic") || html.contains("captcha-form") || html.contains("systems have detected")
+}
+
+/// Append results whose link isn't already present. Returns how many were added.
+pub fn merge_results(results: &mut Vec<SearchResult>, more: Vec<SearchResult>) -> usize {
+    let mut seen: std::collections::HashSet<String> = results.iter().map(|r| r.link.clone()).collect();
+    let before = results.len();
+    results.extend(more.into_iter().filter(|r| seen.insert(r.link.clone())));
+    results.len() - before
+}
+
+/// Click the engine's "Next" control up to `extra_pages` times, merging each
+/// page's results into `results`. A missing control, a navigation error or a
+/// challenge page ends pagination early, keeping what was gathered so far.
+async fn paginate_serp<F>(
+    tab: &std::sync::Arc<headless_chrome::Tab>,
+    engine: &str,
+    proxy_id: Option<&str>,
+    extra_pages: u32,
+    results: &mut Vec<SearchResult>,
+    page_results: F,
+) where
+    F: Fn(&std::sync::Arc<headless_chrome::Tab>, &str) -> Vec<SearchResult>,
+{
+    let (next_selector, is_challenge): (&str, fn(&str) -> bool) = match engine {
+        "google" => (GOOGLE_NEXT_SELECTOR, is_google_challenge),
+        _ => (BING_NEXT_SELECTOR, is_bing_challenge),
+    };
+
+    for page in 2..=extra_pages.min(MAX_SERP_PAGES - 1) + 1 {
+        let clicked = tab
+            .find_element(next_selector)
+            .and_then(|next| next.click().map(|_| ()))
+            .and_then(|_| tab.wait_until_navigated().map(|_| ()));
+        if let Err(e) = clicked {
+            println!("📄 No {} page {} ({}), stopping pagination", engine, page, e);
+            return;
+        }
+        sleep(Duration::from_millis(2000 + (rand::random::<u64>() % 2000))).await;
+
+        let html = tab.get_content().unwrap_or_default();
+        if is_challenge(&html) {
+            println!("⚠️ {} challenge on page {}, keeping {} results", engine, page, results.len());
+            cool_down_proxy(engine, proxy_id);
+            return;
+        }
+        let added = merge_results(results, page_results(tab, &html));
+        println!("📄 {} page {}: {} new results ({} total)", engine, page, added, results.len());
+    }
+}
+
 // ============================================================================
 // Cookie Helper Functions
 // ============================================================================
@@ -270,58 +1374,383 @@ pub fn check_for_ban(tab: &std::sync::Arc<headless_chrome::Tab>) -> Result<()> {
         return Err(anyhow::anyhow!("🛑 CRITICAL: Checkpoint/Ban URL Detected: {}", url));
     }
 
-    // Deep check content if URL is generic
-    match tab.get_content() {
-        Ok(html) => {
-            if html.contains("Verify it's you") || html.contains("security check") || html.contains("temporarily locked") {
-                 return Err(anyhow::anyhow!("🛑 CRITICAL: Checkpoint Content Detected"));
+    // Deep check content if URL is generic
+    match tab.get_content() {
+        Ok(html) => {
+            if html.contains("Verify it's you") || html.contains("security check") || html.contains("temporarily locked") {
+                 return Err(anyhow::anyhow!("🛑 CRITICAL: Checkpoint Content Detected"));
+            }
+        },
+        Err(_) => {} // Ignore content check failure
+    }
+    
+    Ok(())
+}
+
+// ============================================================================
+// Extraction Helper Functions
+// ============================================================================
+
+/// Extract emails from text using regex
+pub fn extract_emails(text: &str) -> Vec<String> {
+    let email_regex = Regex::new(r"[a-zA-Z0-9._%+-]+@[a-zA-Z0-9.-]+\.[a-zA-Z]{2,}").unwrap();
+    email_regex
+        .find_iter(text)
+        .map(|m| m.as_str().to_string())
+        .collect::<std::collections::HashSet<_>>()
+        .into_iter()
+        .collect()
+}
+
+/// Extract phone numbers from text using regex
+pub fn extract_phone_numbers(text: &str) -> Vec<String> {
+    let phone_regex = Regex::new(r"[\+]?[(]?[0-9]{1,3}[)]?[-\s\.]?[(]?[0-9]{1,4}[)]?[-\s\.]?[0-9]{1,4}[-\s\.]?[0-9]{1,9}").unwrap();
+    phone_regex
+        .find_iter(text)
+        .map(|m| m.as_str().to_string())
+        .filter(|p| p.len() >= 7) // Filter out short matches
+        .collect::<std::collections::HashSet<_>>()
+        .into_iter()
+        .collect()
+}
+
+/// Emails and phone numbers from `mailto:` / `tel:` links (the most reliable source)
+pub fn extract_contact_links(document: &Html) -> (Vec<String>, Vec<String>) {
+    let selector = Selector::parse("a[href^='mailto:'], a[href^='tel:'], a[href^='MAILTO:'], a[href^='TEL:']").unwrap();
+    let mut emails = Vec::new();
+    let mut phones = Vec::new();
+
+    for el in document.select(&selector) {
+        let href = el.value().attr("href").unwrap_or_default();
+        let (scheme, rest) = href.split_once(':').unwrap_or(("", ""));
+        // Drop ?subject=... and decode the common escapes
+        let value = rest.split('?').next().unwrap_or_default()
+            .replace("%20", " ")
+            .replace("%40", "@")
+            .replace("%2B", "+");
+        if scheme.eq_ignore_ascii_case("mailto") {
+            emails.extend(value.split(',').map(|s| s.trim().to_string()).filter(|s| s.contains('@')));
+        } else if !value.trim().is_empty() {
+            phones.push(value.trim().to_string());
+        }
+    }
+    (emails, phones)
+}
+
+/// Emails and phone numbers from schema.org (ContactPoint, Organization, Person, ...),
+/// microdata (`itemprop`) and hCard/vCard markup (`.vcard .email`, `.vcard .tel`)
+pub fn extract_structured_contacts(document: &Html, schema_org: &[serde_json::Value]) -> (Vec<String>, Vec<String>) {
+    fn walk(value: &serde_json::Value, emails: &mut Vec<String>, phones: &mut Vec<String>) {
+        match value {
+            serde_json::Value::Object(map) => {
+                for (key, v) in map {
+                    match (key.as_str(), v) {
+                        ("email", serde_json::Value::String(s)) => emails.push(s.trim_start_matches("mailto:").to_string()),
+                        ("telephone" | "faxNumber", serde_json::Value::String(s)) => phones.push(s.to_string()),
+                        _ => walk(v, emails, phones),
+                    }
+                }
+            }
+            serde_json::Value::Array(items) => items.iter().for_each(|v| walk(v, emails, phones)),
+            _ => {}
+        }
+    }
+
+    let mut emails = Vec::new();
+    let mut phones = Vec::new();
+    schema_org.iter().for_each(|v| walk(v, &mut emails, &mut phones));
+
+    let text_or_content = |el: scraper::ElementRef| -> String {
+        el.value().attr("content").map(|s| s.to_string())
+            .unwrap_or_else(|| el.text().collect::<String>())
+            .trim()
+            .to_string()
+    };
+    let email_sel = Selector::parse("[itemprop='email'], .vcard .email, .h-card .u-email").unwrap();
+    let phone_sel = Selector::parse("[itemprop='telephone'], .vcard .tel, .h-card .p-tel").unwrap();
+    emails.extend(document.select(&email_sel).map(text_or_content).map(|s| s.trim_start_matches("mailto:").to_string()).filter(|s| s.contains('@')));
+    phones.extend(document.select(&phone_sel).map(text_or_content).filter(|s| !s.is_empty()));
+
+    (emails, phones)
+}
+
+/// Merge contacts from all sources, most reliable first, deduplicating emails
+/// case-insensitively and phone numbers by their digits.
+/// The raw-HTML email / main-text phone regex scan can be disabled with
+/// CONTACT_REGEX_SCAN=false to keep only link/structured sources.
+pub fn extract_contacts(document: &Html, html: &str, main_text: &str, schema_org: &[serde_json::Value]) -> (Vec<String>, Vec<String>) {
+    let (link_emails, link_phones) = extract_contact_links(document);
+    let (structured_emails, structured_phones) = extract_structured_contacts(document, schema_org);
+    let (regex_emails, regex_phones) = if crate::config::env_flag("CONTACT_REGEX_SCAN", true) {
+        (extract_emails(html), extract_phone_numbers(main_text))
+    } else {
+        (Vec::new(), Vec::new())
+    };
+
+    let mut seen = std::collections::HashSet::new();
+    let emails = link_emails.into_iter()
+        .chain(structured_emails)
+        .chain(regex_emails)
+        .filter(|e| seen.insert(e.to_lowercase()))
+        .collect();
+
+    let mut seen = std::collections::HashSet::new();
+    let phones = link_phones.into_iter()
+        .chain(structured_phones)
+        .chain(regex_phones)
+        .filter(|p| {
+            let digits: String = p.chars().filter(|c| c.is_ascii_digit()).collect();
+            digits.len() >= 7 && seen.insert(digits)
+        })
+        .collect();
+
+    (emails, phones)
+}
+
+/// Extract Schema.org JSON-LD data from HTML
+pub fn extract_schema_org(html: &str) -> Vec<serde_json::Value> {
+    let document = Html::parse_document(html);
+    let selector = Selector::parse("script[type='application/ld+json']").unwrap();
+    
+    document
+        .select(&selector)
+        .filter_map(|el| {
+            let json_text = el.text().collect::<String>();
+            serde_json::from_str(&json_text).ok()
+        })
+        .collect()
+}
+
+/// Normalize a date string from the wild into ISO-8601.
+/// Timezone-aware inputs keep their offset; date-only inputs stay date-only.
+pub fn normalize_date(raw: &str) -> Option<String> {
+    use chrono::{DateTime, NaiveDate, NaiveDateTime};
+
+    let raw = raw.trim();
+    if raw.is_empty() {
+        return None;
+    }
+
+    if let Ok(dt) = DateTime::parse_from_rfc3339(raw) {
+        return Some(dt.to_rfc3339());
+    }
+    if let Ok(dt) = DateTime::parse_from_str(raw, "%Y-%m-%dT%H:%M:%S%z") {
+        return Some(dt.to_rfc3339());
+    }
+    if let Ok(dt) = DateTime::parse_from_rfc2822(raw) {
+        return Some(dt.to_rfc3339());
+    }
+    for fmt in ["%Y-%m-%dT%H:%M:%S%.f", "%Y-%m-%dT%H:%M:%S", "%Y-%m-%d %H:%M:%S", "%Y-%m-%dT%H:%M"] {
+        if let Ok(dt) = NaiveDateTime::parse_from_str(raw, fmt) {
+            return Some(dt.format("%Y-%m-%dT%H:%M:%S").to_string());
+        }
+    }
+    for fmt in ["%Y-%m-%d", "%Y/%m/%d", "%B %d, %Y", "%b %d, %Y", "%d %B %Y"] {
+        if let Ok(d) = NaiveDate::parse_from_str(raw, fmt) {
+            return Some(d.format("%Y-%m-%d").to_string());
+        }
+    }
+    None
+}
+
+/// Recursively look up a string field in JSON-LD (handles arrays and @graph)
+fn find_json_ld_field(value: &serde_json::Value, key: &str) -> Option<String> {
+    match value {
+        serde_json::Value::Object(map) => {
+            if let Some(serde_json::Value::String(s)) = map.get(key) {
+                return Some(s.clone());
+            }
+            map.values().find_map(|v| find_json_ld_field(v, key))
+        }
+        serde_json::Value::Array(items) => items.iter().find_map(|v| find_json_ld_field(v, key)),
+        _ => None,
+    }
+}
+
+/// Extract (published, modified) dates, trying each source in priority order:
+/// meta tags, JSON-LD, microdata, then `<time datetime>`
+pub fn extract_dates(document: &Html, schema_org: &[serde_json::Value]) -> (Option<String>, Option<String>) {
+    let first_attr = |selector: &str, attr: &str| -> Option<String> {
+        document
+            .select(&Selector::parse(selector).unwrap())
+            .filter_map(|el| el.value().attr(attr))
+            .find_map(normalize_date)
+    };
+    let from_json_ld = |key: &str| -> Option<String> {
+        schema_org
+            .iter()
+            .filter_map(|v| find_json_ld_field(v, key))
+            .find_map(|s| normalize_date(&s))
+    };
+
+    let published = first_attr("meta[property='article:published_time']", "content")
+        .or_else(|| from_json_ld("datePublished"))
+        .or_else(|| first_attr("meta[itemprop='datePublished'], [itemprop='datePublished'][content]", "content"))
+        .or_else(|| first_attr("meta[name='date'], meta[name='pubdate'], meta[name='publish-date']", "content"))
+        .or_else(|| first_attr("time[datetime]", "datetime"));
+
+    let modified = first_attr("meta[property='article:modified_time']", "content")
+        .or_else(|| from_json_ld("dateModified"))
+        .or_else(|| first_attr("meta[itemprop='dateModified'], [itemprop='dateModified'][content]", "content"))
+        .or_else(|| first_attr("meta[property='og:updated_time']", "content"))
+        .or_else(|| first_attr("meta[http-equiv='last-modified']", "content"));
+
+    (published, modified)
+}
+
+/// Tidy a byline: collapse whitespace and strip a leading "By"
+fn clean_byline(raw: &str) -> Option<String> {
+    let collapsed = raw.split_whitespace().collect::<Vec<_>>().join(" ");
+    let trimmed = collapsed
+        .strip_prefix("By ")
+        .or_else(|| collapsed.strip_prefix("by "))
+        .unwrap_or(&collapsed)
+        .trim_matches(|c: char| c == ',' || c == '|' || c.is_whitespace());
+    if trimmed.is_empty() || trimmed.len() > 100 {
+        None
+    } else {
+        Some(trimmed.to_string())
+    }
+}
+
+/// JSON-LD `author` can be a string, a Person/Organization object, or an array of either
+fn json_ld_author_name(value: &serde_json::Value) -> Option<String> {
+    match value {
+        serde_json::Value::String(s) => Some(s.clone()),
+        serde_json::Value::Object(map) => map.get("name").and_then(|n| n.as_str()).map(|s| s.to_string()),
+        serde_json::Value::Array(items) => {
+            let names: Vec<String> = items.iter().filter_map(json_ld_author_name).collect();
+            if names.is_empty() { None } else { Some(names.join(", ")) }
+        }
+        _ => None,
+    }
+}
+
+fn find_json_ld_author(value: &serde_json::Value) -> Option<String> {
+    match value {
+        serde_json::Value::Object(map) => map
+            .get("author")
+            .and_then(json_ld_author_name)
+            .or_else(|| map.values().find_map(find_json_ld_author)),
+        serde_json::Value::Array(items) => items.iter().find_map(find_json_ld_author),
+        _ => None,
+    }
+}
+
+/// Extract the page author, trying each source in priority order:
+/// meta tags, JSON-LD, `rel="author"`, `[itemprop=author]`, then byline markup
+pub fn extract_author(document: &Html, schema_org: &[serde_json::Value]) -> Option<String> {
+    let from_attr = |selector: &str, attr: &str| -> Option<String> {
+        document
+            .select(&Selector::parse(selector).unwrap())
+            .filter_map(|el| el.value().attr(attr))
+            .find_map(clean_byline)
+    };
+    let from_text = |selector: &str| -> Option<String> {
+        document
+            .select(&Selector::parse(selector).unwrap())
+            .map(|el| el.text().collect::<String>())
+            .find_map(|t| clean_byline(&t))
+    };
+
+    from_attr("meta[name='author']", "content")
+        .or_else(|| schema_org.iter().find_map(find_json_ld_author).and_then(|a| clean_byline(&a)))
+        .or_else(|| from_text("a[rel='author'], link[rel='author'][title]"))
+        .or_else(|| from_attr("[itemprop='author'][content], [itemprop='author'] [itemprop='name'][content]", "content"))
+        .or_else(|| from_text("[itemprop='author'] [itemprop='name'], [itemprop='author']"))
+        .or_else(|| from_attr("meta[property='article:author'], meta[name='byl'], meta[name='parsely-author']", "content"))
+        .or_else(|| from_text(".byline, .author-name, .post-author, .entry-author, [class*='byline'], .author"))
+}
+
+/// Currency symbols mapped to ISO 4217 codes (longest first so "US$" beats "$")
+const CURRENCY_SYMBOLS: &[(&str, &str)] = &[
+    ("US$", "USD"), ("C$", "CAD"), ("A$", "AUD"), ("R$", "BRL"),
+    ("$", "USD"), ("€", "EUR"), ("£", "GBP"), ("¥", "JPY"), ("₹", "INR"), ("₩", "KRW"), ("฿", "THB"),
+];
+
+static PRICE_NUMBER_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"\d[\d.,\s]*\d|\d").unwrap());
+static CURRENCY_CODE_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"\b[A-Z]{3}\b").unwrap());
+
+/// Parse a price string such as "$1,299.99", "1.299,99 €" or "EUR 20".
+/// The last `.`/`,` followed by 1-2 digits is the decimal separator; other
+/// separators are thousands groupings.
+pub fn parse_price(raw: &str) -> Option<(f64, Option<String>)> {
+    let number = PRICE_NUMBER_REGEX.find(raw)?.as_str();
+    let digits: String = number.chars().filter(|c| !c.is_whitespace()).collect();
+    let normalized = match digits.rfind(['.', ',']) {
+        Some(pos) if (1..=2).contains(&(digits.len() - pos - 1)) => {
+            let (int_part, frac) = digits.split_at(pos);
+            format!("{}.{}", int_part.replace(['.', ','], ""), &frac[1..])
+        }
+        _ => digits.replace(['.', ','], ""),
+    };
+    let price: f64 = normalized.parse().ok().filter(|p: &f64| p.is_finite() && *p >= 0.0)?;
+
+    let currency = CURRENCY_SYMBOLS
+        .iter()
+        .find(|(symbol, _)| raw.contains(symbol))
+        .map(|(_, code)| code.to_string())
+        .or_else(|| CURRENCY_CODE_REGEX.find(raw).map(|m| m.as_str().to_string()));
+    Some((price, currency))
+}
+
+/// First schema.org Offer/AggregateOffer price in JSON-LD: (price, priceCurrency)
+fn find_json_ld_offer(value: &serde_json::Value) -> Option<(String, Option<String>)> {
+    match value {
+        serde_json::Value::Object(map) => {
+            let price = ["price", "lowPrice"].iter().find_map(|key| match map.get(*key) {
+                Some(serde_json::Value::String(s)) if !s.trim().is_empty() => Some(s.clone()),
+                Some(serde_json::Value::Number(n)) => Some(n.to_string()),
+                _ => None,
+            });
+            if let Some(price) = price {
+                let currency = map.get("priceCurrency").and_then(|c| c.as_str()).map(|c| c.to_uppercase());
+                return Some((price, currency));
             }
-        },
-        Err(_) => {} // Ignore content check failure
+            map.values().find_map(find_json_ld_offer)
+        }
+        serde_json::Value::Array(items) => items.iter().find_map(find_json_ld_offer),
+        _ => None,
     }
-    
-    Ok(())
 }
 
-// ============================================================================
-// Extraction Helper Functions
-// ============================================================================
+/// Product price and ISO currency, trying schema.org JSON-LD offers, Open Graph
+/// product meta, microdata (`itemprop=price`), then common `.price` markup.
+/// Disabled with EXTRACT_PRICE=false.
+pub fn extract_price(document: &Html, schema_org: &[serde_json::Value]) -> (Option<f64>, Option<String>) {
+    if !crate::config::env_flag("EXTRACT_PRICE", true) {
+        return (None, None);
+    }
+    let attr = |selector: &str, attr: &str| -> Option<String> {
+        document
+            .select(&Selector::parse(selector).unwrap())
+            .find_map(|el| el.value().attr(attr).map(|v| v.trim().to_string()).filter(|v| !v.is_empty()))
+    };
+    let text = |selector: &str| -> Option<String> {
+        document
+            .select(&Selector::parse(selector).unwrap())
+            .map(|el| el.text().collect::<String>().trim().to_string())
+            .find(|t| !t.is_empty() && t.chars().any(|c| c.is_ascii_digit()))
+    };
 
-/// Extract emails from text using regex
-pub fn extract_emails(text: &str) -> Vec<String> {
-    let email_regex = Regex::new(r"[a-zA-Z0-9._%+-]+@[a-zA-Z0-9.-]+\.[a-zA-Z]{2,}").unwrap();
-    email_regex
-        .find_iter(text)
-        .map(|m| m.as_str().to_string())
-        .collect::<std::collections::HashSet<_>>()
-        .into_iter()
-        .collect()
-}
+    let candidates = [
+        schema_org.iter().find_map(find_json_ld_offer),
+        attr("meta[property='product:price:amount'], meta[property='og:price:amount']", "content")
+            .map(|p| (p, attr("meta[property='product:price:currency'], meta[property='og:price:currency']", "content"))),
+        attr("[itemprop='price'][content]", "content")
+            .or_else(|| text("[itemprop='price']"))
+            .map(|p| (p, attr("[itemprop='priceCurrency'][content]", "content"))),
+        text(".price, [class*='product-price'], [data-price]").map(|p| (p, None)),
+    ];
 
-/// Extract phone numbers from text using regex
-pub fn extract_phone_numbers(text: &str) -> Vec<String> {
-    let phone_regex = Regex::new(r"[\+]?[(]?[0-9]{1,3}[)]?[-\s\.]?[(]?[0-9]{1,4}[)]?[-\s\.]?[0-9]{1,4}[-\s\.]?[0-9]{1,9}").unwrap();
-    phone_regex
-        .find_iter(text)
-        .map(|m| m.as_str().to_string())
-        .filter(|p| p.len() >= 7) // Filter out short matches
-        .collect::<std::collections::HashSet<_>>()
+    candidates
         .into_iter()
-        .collect()
-}
-
-/// Extract Schema.org JSON-LD data from HTML
-pub fn extract_schema_org(html: &str) -> Vec<serde_json::Value> {
-    let document = Html::parse_document(html);
-    let selector = Selector::parse("script[type='application/ld+json']").unwrap();
-    
-    document
-        .select(&selector)
-        .filter_map(|el| {
-            let json_text = el.text().collect::<String>();
-            serde_json::from_str(&json_text).ok()
+        .flatten()
+        .find_map(|(raw, declared_currency)| {
+            let (price, parsed_currency) = parse_price(&raw)?;
+            Some((Some(price), declared_currency.map(|c| c.to_uppercase()).or(parsed_currency)))
         })
-        .collect()
+        .unwrap_or((None, None))
 }
 
 /// Extract Open Graph metadata
@@ -349,9 +1778,34 @@ pub fn extract_open_graph(document: &Html) -> (Option<String>, Option<String>, O
     (og_title, og_description, og_image, og_type)
 }
 
+/// Base URL relative references resolve against: the page URL, overridden by `<base href>`
+pub fn document_base_url(document: &Html, page_url: &str) -> Option<reqwest::Url> {
+    let page = reqwest::Url::parse(page_url).ok()?;
+    let selector = Selector::parse("base[href]").unwrap();
+    match document.select(&selector).next().and_then(|el| el.value().attr("href")) {
+        Some(href) => Some(page.join(href.trim()).unwrap_or(page)),
+        None => Some(page),
+    }
+}
+
+/// Resolve any href/src form (absolute, `//host/..`, `/root`, `path`, `../up`, `?q`)
+/// to an absolute http(s) URL; other schemes (data:, javascript:, mailto:) yield None
+pub fn resolve_http_url(base: Option<&reqwest::Url>, raw: &str) -> Option<reqwest::Url> {
+    let raw = raw.trim();
+    if raw.is_empty() || raw.starts_with('#') {
+        return None;
+    }
+    let url = match base {
+        Some(base) => base.join(raw).ok()?,
+        None => reqwest::Url::parse(raw).ok()?,
+    };
+    matches!(url.scheme(), "http" | "https").then_some(url)
+}
+
 /// Extract images with metadata
-pub fn extract_images(document: &Html, base_url: &str) -> Vec<ImageData> {
+pub fn extract_images(document: &Html, page_url: &str) -> Vec<ImageData> {
     let img_selector = Selector::parse("img").unwrap();
+    let base = document_base_url(document, page_url);
     
     document
         .select(&img_selector)
@@ -362,7 +1816,7 @@ pub fn extract_images(document: &Html, base_url: &str) -> Vec<ImageData> {
                 return None;
             }
             Some(ImageData {
-                src: if src.starts_with("http") { src.to_string() } else { format!("{}{}", base_url, src) },
+                src: resolve_http_url(base.as_ref(), src)?.to_string(),
                 alt: el.value().attr("alt").map(|s| s.to_string()),
                 title: el.value().attr("title").map(|s| s.to_string()),
             })
@@ -371,116 +1825,549 @@ pub fn extract_images(document: &Html, base_url: &str) -> Vec<ImageData> {
         .collect()
 }
 
-/// Extract outbound links
-pub fn extract_outbound_links(document: &Html, base_domain: &str) -> Vec<String> {
+/// Extract external script and stylesheet URLs (resolved against the document base),
+/// deduplicated in page order
+pub fn extract_resources(document: &Html, page_url: &str) -> (Vec<String>, Vec<String>) {
+    let script_selector = Selector::parse("script[src]").unwrap();
+    let style_selector = Selector::parse("link[rel~='stylesheet'][href]").unwrap();
+    let base = document_base_url(document, page_url);
+
+    let collect = |selector: &Selector, attr: &str| -> Vec<String> {
+        let mut seen = std::collections::HashSet::new();
+        document
+            .select(selector)
+            .filter_map(|el| el.value().attr(attr).and_then(|v| resolve_http_url(base.as_ref(), v)))
+            .map(|url| url.to_string())
+            .filter(|url| seen.insert(url.clone()))
+            .collect()
+    };
+
+    (collect(&script_selector, "src"), collect(&style_selector, "href"))
+}
+
+/// Summarize `<form>` elements: action, method and named fields (submit/button
+/// controls skipped). Disabled with EXTRACT_FORMS=false; capped at MAX_FORMS (default 20).
+pub fn extract_forms(document: &Html, page_url: &str) -> Vec<FormData> {
+    if !crate::config::env_flag("EXTRACT_FORMS", true) {
+        return Vec::new();
+    }
+    let max_forms: usize = env_parse("MAX_FORMS", 20);
+    let form_selector = Selector::parse("form").unwrap();
+    let field_selector = Selector::parse("input, select, textarea").unwrap();
+    let base = document_base_url(document, page_url);
+
+    document
+        .select(&form_selector)
+        .take(max_forms)
+        .map(|form| {
+            let action = form.value().attr("action")
+                .and_then(|a| resolve_http_url(base.as_ref(), a))
+                .map(|u| u.to_string());
+            let method = form.value().attr("method")
+                .map(|m| m.trim().to_uppercase())
+                .filter(|m| !m.is_empty())
+                .unwrap_or_else(|| "GET".to_string());
+
+            let fields = form
+                .select(&field_selector)
+                .filter_map(|el| {
+                    let tag = el.value().name();
+                    let field_type = match tag {
+                        "input" => el.value().attr("type").unwrap_or("text").to_lowercase(),
+                        other => other.to_string(),
+                    };
+                    if matches!(field_type.as_str(), "submit" | "button" | "reset" | "image") {
+                        return None;
+                    }
+                    let name = el.value().attr("name").or_else(|| el.value().attr("id"))?;
+                    Some(FieldData { name: name.to_string(), field_type })
+                })
+                .collect();
+
+            FormData { action, method, fields }
+        })
+        .collect()
+}
+
+/// AMP version of the page from `<link rel="amphtml">`, resolved against the document base
+pub fn extract_amp_url(document: &Html, page_url: &str) -> Option<String> {
+    let selector = Selector::parse("link[rel~='amphtml'][href]").unwrap();
+    let href = document.select(&selector).next()?.value().attr("href")?;
+    resolve_http_url(document_base_url(document, page_url).as_ref(), href).map(|u| u.to_string())
+}
+
+/// Platform name for a social profile URL, ignoring share/intent widgets
+pub fn social_platform(url: &str) -> Option<&'static str> {
+    const PLATFORMS: &[(&str, &str)] = &[
+        ("linkedin.com", "linkedin"),
+        ("twitter.com", "twitter"),
+        ("x.com", "twitter"),
+        ("facebook.com", "facebook"),
+        ("instagram.com", "instagram"),
+        ("github.com", "github"),
+    ];
+    const SHARE_PATHS: &[&str] = &["/share", "/sharer", "/intent", "/dialog"];
+
+    let parsed = reqwest::Url::parse(url).ok()?;
+    let host = parsed.host_str()?.trim_start_matches("www.").trim_start_matches("m.");
+    let path = parsed.path().trim_end_matches('/');
+    // Twitter's legacy tweet composer: /home?status=...
+    let tweet_composer = path == "/home" && parsed.query_pairs().any(|(k, _)| k == "status");
+    if path.is_empty() || tweet_composer || SHARE_PATHS.iter().any(|p| path.starts_with(p)) {
+        return None;
+    }
+    PLATFORMS
+        .iter()
+        .find(|(domain, _)| host == *domain || host.ends_with(&format!(".{}", domain)))
+        .map(|(_, platform)| *platform)
+}
+
+/// First profile link found for each known social platform
+pub fn extract_social_links(document: &Html) -> std::collections::HashMap<String, String> {
     let link_selector = Selector::parse("a[href]").unwrap();
-    
+    let mut social = std::collections::HashMap::new();
+    for href in document.select(&link_selector).filter_map(|el| el.value().attr("href")) {
+        if let Some(platform) = social_platform(href.trim()) {
+            social.entry(platform.to_string()).or_insert_with(|| href.trim().to_string());
+        }
+    }
+    social
+}
+
+/// Links to other hosts (subdomains of the page's host count as internal), resolved
+/// to absolute URLs, in document order with the first occurrence per URL winning.
+/// Capped at MAX_OUTBOUND_LINKS (default 50).
+pub fn extract_outbound_links(document: &Html, page_url: &str) -> Vec<OutboundLink> {
+    let link_selector = Selector::parse("a[href]").unwrap();
+    let max_links = env_parse("MAX_OUTBOUND_LINKS", 50usize);
+    let base = document_base_url(document, page_url);
+    let page_host = reqwest::Url::parse(page_url)
+        .ok()
+        .and_then(|u| u.host_str().map(|h| h.trim_start_matches("www.").to_lowercase()))
+        .unwrap_or_default();
+    let mut seen = std::collections::HashSet::new();
+
     document
         .select(&link_selector)
-        .filter_map(|el| el.value().attr("href").map(|s| s.to_string()))
-        .filter(|href| href.starts_with("http") && !href.contains(base_domain))
-        .collect::<std::collections::HashSet<_>>()
-        .into_iter()
-        .take(50) // Limit to 50 links
+        .filter_map(|el| {
+            let url = resolve_http_url(base.as_ref(), el.value().attr("href")?)?;
+            let host = url.host_str()?.trim_start_matches("www.").to_lowercase();
+            let internal = !page_host.is_empty() && (host == page_host || host.ends_with(&format!(".{}", page_host)));
+            let href = url.to_string();
+            if internal || !seen.insert(href.clone()) {
+                return None;
+            }
+            let rel = el
+                .value()
+                .attr("rel")
+                .map(|r| r.split_whitespace().map(|t| t.to_lowercase()).collect())
+                .unwrap_or_default();
+            let anchor_text = el.text().collect::<String>().split_whitespace().collect::<Vec<_>>().join(" ");
+            Some(OutboundLink { url: href, rel, anchor_text })
+        })
+        .take(max_links)
         .collect()
 }
 
 
+// ============================================================================
+// SERP Result Filtering
+// ============================================================================
+
+/// Include/exclude URL patterns applied to SERP results.
+/// Patterns prefixed with `re:` are regexes matched anywhere in the URL;
+/// anything else is a glob (`*`, `?`) matched against the host or the full URL.
+#[derive(Debug, Default)]
+pub struct ResultFilter {
+    include: Vec<Regex>,
+    exclude: Vec<Regex>,
+}
+
+impl ResultFilter {
+    pub fn new(include: &[String], exclude: &[String]) -> Result<Self, String> {
+        Ok(Self {
+            include: include.iter().map(|p| compile_url_pattern(p)).collect::<Result<_, _>>()?,
+            exclude: exclude.iter().map(|p| compile_url_pattern(p)).collect::<Result<_, _>>()?,
+        })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.include.is_empty() && self.exclude.is_empty()
+    }
+
+    /// Does a (possibly redirect-wrapped) result URL pass the filter?
+    pub fn matches(&self, link: &str) -> bool {
+        let url = decode_search_url(link);
+        let host = reqwest::Url::parse(&url)
+            .ok()
+            .and_then(|u| u.host_str().map(|h| h.to_string()))
+            .unwrap_or_default();
+        let hit = |re: &Regex| re.is_match(&url) || (!host.is_empty() && re.is_match(&host));
+
+        if self.exclude.iter().any(hit) {
+            return false;
+        }
+        self.include.is_empty() || self.include.iter().any(hit)
+    }
+
+    /// Move results that don't pass into `filtered_results`
+    pub fn apply(&self, serp: &mut SerpData) {
+        if self.is_empty() {
+            return;
+        }
+        let (kept, dropped): (Vec<_>, Vec<_>) = std::mem::take(&mut serp.results)
+            .into_iter()
+            .partition(|r| self.matches(&r.link));
+        if !dropped.is_empty() {
+            println!("🧹 Filtered out {} of {} results by URL pattern", dropped.len(), kept.len() + dropped.len());
+        }
+        serp.results = kept;
+        serp.filtered_results.extend(dropped);
+    }
+}
+
+fn compile_url_pattern(pattern: &str) -> Result<Regex, String> {
+    let source = match pattern.strip_prefix("re:") {
+        Some(re) => re.to_string(),
+        None => {
+            let mut glob = String::from("(?i)^");
+            for c in pattern.chars() {
+                match c {
+                    '*' => glob.push_str(".*"),
+                    '?' => glob.push('.'),
+                    c => glob.push_str(&regex::escape(&c.to_string())),
+                }
+            }
+            glob.push('$');
+            glob
+        }
+    };
+    Regex::new(&source).map_err(|e| format!("Invalid URL pattern '{}': {}", pattern, e))
+}
+
+// ============================================================================
+// Page Type Classification
+// ============================================================================
+
+/// Coarse kind of a crawled page
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PageType {
+    Article,
+    Product,
+    Forum,
+    Homepage,
+    Directory,
+    Listing,
+    Other,
+}
+
+impl PageType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PageType::Article => "article",
+            PageType::Product => "product",
+            PageType::Forum => "forum",
+            PageType::Homepage => "homepage",
+            PageType::Directory => "directory",
+            PageType::Listing => "listing",
+            PageType::Other => "other",
+        }
+    }
+}
+
+/// Collect every schema.org `@type` found in the page's JSON-LD
+fn schema_types(schema_org: &[serde_json::Value]) -> Vec<String> {
+    fn walk(value: &serde_json::Value, out: &mut Vec<String>) {
+        match value {
+            serde_json::Value::Object(map) => {
+                match map.get("@type") {
+                    Some(serde_json::Value::String(t)) => out.push(t.to_lowercase()),
+                    Some(serde_json::Value::Array(ts)) => {
+                        out.extend(ts.iter().filter_map(|t| t.as_str()).map(|t| t.to_lowercase()))
+                    }
+                    _ => {}
+                }
+                map.values().for_each(|v| walk(v, out));
+            }
+            serde_json::Value::Array(items) => items.iter().for_each(|v| walk(v, out)),
+            _ => {}
+        }
+    }
+    let mut out = Vec::new();
+    schema_org.iter().for_each(|v| walk(v, &mut out));
+    out
+}
+
+/// Relative weight of each quality signal. QUALITY_WEIGHTS overrides any subset,
+/// e.g. "words:50,images:0"; QUALITY_TARGET_WORDS (default 800) is the word count
+/// that earns the full content-length share.
+#[derive(Debug, Clone, PartialEq)]
+pub struct QualityWeights {
+    pub words: f64,
+    pub schema: f64,
+    pub meta: f64,
+    pub images: f64,
+    pub text_ratio: f64,
+    pub target_words: u32,
+}
+
+impl Default for QualityWeights {
+    fn default() -> Self {
+        Self { words: 35.0, schema: 15.0, meta: 20.0, images: 10.0, text_ratio: 20.0, target_words: 800 }
+    }
+}
+
+impl QualityWeights {
+    pub fn from_env() -> Self {
+        let mut weights = Self { target_words: env_parse("QUALITY_TARGET_WORDS", 800u32).max(1), ..Self::default() };
+        for pair in std::env::var("QUALITY_WEIGHTS").unwrap_or_default().split(',') {
+            let Some((name, value)) = pair.split_once(':') else { continue };
+            let Ok(value) = value.trim().parse::<f64>() else { continue };
+            let value = value.max(0.0);
+            match name.trim() {
+                "words" => weights.words = value,
+                "schema" => weights.schema = value,
+                "meta" => weights.meta = value,
+                "images" => weights.images = value,
+                "text_ratio" | "ratio" => weights.text_ratio = value,
+                other => eprintln!("⚠️ Unknown QUALITY_WEIGHTS signal '{}' ignored", other),
+            }
+        }
+        weights
+    }
+}
+
+static QUALITY_WEIGHTS: Lazy<QualityWeights> = Lazy::new(QualityWeights::from_env);
+
+/// Single sortable 0-100 score for prioritising crawled pages, using QUALITY_WEIGHTS
+pub fn compute_quality_score(data: &WebsiteData) -> u8 {
+    quality_score_with(data, &QUALITY_WEIGHTS)
+}
+
+/// Each signal is scored 0-1, then combined as a weighted average
+pub fn quality_score_with(data: &WebsiteData, weights: &QualityWeights) -> u8 {
+    let words = (data.word_count as f64 / weights.target_words as f64).min(1.0);
+    let schema = if data.schema_org.is_empty() { 0.0 } else { 1.0 };
+
+    let present = |field: &Option<String>| field.as_deref().is_some_and(|v| !v.trim().is_empty());
+    let meta_fields = [
+        !data.title.trim().is_empty(),
+        present(&data.meta_description),
+        present(&data.og_title),
+        present(&data.og_image),
+        present(&data.author),
+        present(&data.published_date),
+    ];
+    let meta = meta_fields.iter().filter(|&&f| f).count() as f64 / meta_fields.len() as f64;
+
+    // A handful of images is plenty; more doesn't make a page better
+    let images = (data.images.len() as f64 / 5.0).min(1.0);
+
+    // Readable text vs. markup: ~25% text is already a content-heavy page
+    let text_ratio = if data.html_size > 0 {
+        (data.main_text.len() as f64 / data.html_size as f64 / 0.25).min(1.0)
+    } else {
+        0.0
+    };
+
+    let total = weights.words + weights.schema + weights.meta + weights.images + weights.text_ratio;
+    if total <= 0.0 {
+        return 0;
+    }
+    let weighted = words * weights.words
+        + schema * weights.schema
+        + meta * weights.meta
+        + images * weights.images
+        + text_ratio * weights.text_ratio;
+    (weighted / total * 100.0).round().clamp(0.0, 100.0) as u8
+}
+
+/// Heuristic page classifier: schema.org types first, then URL structure, then DOM signals
+pub fn classify_page_type(data: &WebsiteData) -> PageType {
+    let types = schema_types(&data.schema_org);
+    let has_type = |names: &[&str]| types.iter().any(|t| names.contains(&t.as_str()));
+
+    // 1. Structured data is the strongest signal
+    if has_type(&["product", "offer", "aggregateoffer"]) {
+        return PageType::Product;
+    }
+    if has_type(&["discussionforumposting", "qapage", "question"]) {
+        return PageType::Forum;
+    }
+    if has_type(&["newsarticle", "article", "blogposting", "techarticle", "report"]) {
+        return PageType::Article;
+    }
+    if has_type(&["itemlist", "collectionpage", "searchresultspage", "offercatalog"]) {
+        return PageType::Listing;
+    }
+    if has_type(&["localbusiness", "organization"]) && data.outbound_links.len() > 30 {
+        return PageType::Directory;
+    }
+
+    match data.og_type.as_deref().map(|t| t.to_lowercase()) {
+        Some(ref t) if t == "article" => return PageType::Article,
+        Some(ref t) if t.contains("product") => return PageType::Product,
+        _ => {}
+    }
+
+    // 2. URL structure
+    let url = if data.final_url.is_empty() { &data.url } else { &data.final_url };
+    let path = reqwest::Url::parse(url)
+        .map(|u| u.path().to_lowercase())
+        .unwrap_or_default();
+    let trimmed = path.trim_matches('/');
+
+    if trimmed.is_empty() || ["index.html", "index.php", "home"].contains(&trimmed) {
+        return PageType::Homepage;
+    }
+    let path_has = |needles: &[&str]| needles.iter().any(|n| path.contains(n));
+    if path_has(&["/forum", "/thread", "/topic", "/discussion", "/community", "/t/", "/r/", "/questions/"]) {
+        return PageType::Forum;
+    }
+    if path_has(&["/product", "/products/", "/item/", "/dp/", "/p/", "/shop/"]) {
+        return PageType::Product;
+    }
+    if path_has(&["/directory", "/listings", "/companies", "/businesses", "/members"]) {
+        return PageType::Directory;
+    }
+    if path_has(&["/category", "/categories", "/collections", "/tag/", "/search", "/archive", "/page/"]) {
+        return PageType::Listing;
+    }
+    if path_has(&["/blog/", "/news/", "/article", "/post", "/story"]) {
+        return PageType::Article;
+    }
+
+    // 3. DOM signals
+    let ctas = data
+        .marketing_data
+        .as_ref()
+        .map(|m| m.ctas.iter().map(|c| c.to_lowercase()).collect::<Vec<_>>())
+        .unwrap_or_default();
+    if ctas.iter().any(|c| c.contains("add to cart") || c.contains("buy now") || c.contains("add to bag")) {
+        return PageType::Product;
+    }
+    if data.word_count > 600 && data.meta_date.is_some() {
+        return PageType::Article;
+    }
+    if data.word_count < 400 && data.images.len() >= 12 {
+        return PageType::Listing;
+    }
+    if data.outbound_links.len() >= 40 {
+        return PageType::Directory;
+    }
+    if data.word_count > 600 {
+        return PageType::Article;
+    }
+
+    PageType::Other
+}
+
+/// Question headers in Bing's "People also ask" / related Q&A blocks
+const BING_PAA_SELECTOR: &str = "#relatedQnAListDisplay .df_qntext, .b_ans .df_qntext, .rqnaContainer .df_qntext, .b_ans [data-tag='RelatedQnA.Item'] .b_1linetrunc";
+
+/// Bing organic results (`#b_results > li.b_algo`)
+pub fn parse_bing_results(document: &Html) -> Vec<SearchResult> {
+    let result_selector = Selector::parse("#b_results > li.b_algo").unwrap();
+    let title_sel = Selector::parse("h2 a").unwrap();
+    let snippet_sel = Selector::parse(".b_caption p").unwrap();
+
+    let mut results = Vec::new();
+    for element in document.select(&result_selector) {
+        let title = element.select(&title_sel).next().map(|e| e.text().collect::<String>()).unwrap_or_default();
+        let link = element.select(&title_sel).next().and_then(|e| e.value().attr("href")).unwrap_or_default().to_string();
+        let snippet = element.select(&snippet_sel).next().map(|e| e.text().collect::<String>()).unwrap_or_default();
+
+        if !title.is_empty() && !link.is_empty() {
+            results.push(SearchResult { title, link, snippet });
+        }
+    }
+    results
+}
+
+/// Bing "People also ask" questions, trimmed and deduplicated in page order
+pub fn extract_bing_paa(document: &Html) -> Vec<String> {
+    let selector = Selector::parse(BING_PAA_SELECTOR).unwrap();
+    let mut seen = std::collections::HashSet::new();
+    document
+        .select(&selector)
+        .map(|el| el.text().collect::<Vec<_>>().join(" ").split_whitespace().collect::<Vec<_>>().join(" "))
+        .filter(|q| !q.is_empty() && seen.insert(q.to_lowercase()))
+        .collect()
+}
+
 // Wrapper with Retry Logic for Bing
-pub async fn search_bing(keyword: &str) -> Result<SerpData> {
+pub async fn search_bing(keyword: &str, options: &SearchOptions) -> Result<SerpData> {
     println!("🔎 Starting Bing Deep Search for: {}", keyword);
     let mut last_error = String::from("No results found");
+    let mut last_reason = Some(FailureReason::NoResultsFound);
+    let max_attempts = max_attempts_for("bing", options.max_attempts);
     
-    // Max 3 attempts
-    for attempt in 1..=3 {
-        if attempt > 1 { println!("🔄 Retry Attempt {}/3...", attempt); }
+    for attempt in 1..=max_attempts {
+        if attempt > 1 { println!("🔄 Retry Attempt {}/{}...", attempt, max_attempts); }
 
-        match search_bing_attempt(keyword).await {
+        match search_bing_attempt(keyword, options).await {
             Ok(data) => {
                 if data.results.is_empty() {
-                    println!("⚠️ Attempt {}/3: Bing returned 0 results.", attempt);
-                    if attempt < 3 {
+                    println!("⚠️ Attempt {}/{}: Bing returned 0 results.", attempt, max_attempts);
+                    last_error = String::from("No results found");
+                    last_reason = Some(FailureReason::NoResultsFound);
+                    if attempt < max_attempts {
                         let wait_time = 5 * attempt as u64;
                         println!("⏳ Waiting {}s before retry...", wait_time);
                         sleep(Duration::from_secs(wait_time)).await;
                         continue;
                     }
                 } else {
-                    println!("✅ Attempt {}/3: Success! Found {} results.", attempt, data.results.len());
+                    println!("✅ Attempt {}/{}: Success! Found {} results.", attempt, max_attempts, data.results.len());
                     return Ok(data);
                 }
             }
             Err(e) => {
-                println!("❌ Attempt {}/3: Error: {}", attempt, e);
+                println!("❌ Attempt {}/{}: Error: {}", attempt, max_attempts, e);
                 last_error = e.to_string();
-                if attempt < 3 { sleep(Duration::from_secs(5)).await; }
+                last_reason = failure_reason(&e);
+                if attempt < max_attempts {
+                    // Rate limits get a longer backoff; the next attempt picks another proxy
+                    let wait = if e.downcast_ref::<RateLimited>().is_some() { rate_limit_backoff(attempt) } else { Duration::from_secs(5) };
+                    println!("⏳ Waiting {}s before retry...", wait.as_secs());
+                    sleep(wait).await;
+                }
             }
         }
     }
-    Err(anyhow::anyhow!("Bing search failed after 3 attempts. Last error: {}", last_error))
+    let message = format!("Bing search failed after {} attempts. Last error: {}", max_attempts, last_error);
+    Err(match last_reason {
+        Some(reason) => SearchFailed::new(reason, message),
+        None => anyhow::anyhow!(message),
+    })
 }
 
 // Internal attempt function for Bing
-async fn search_bing_attempt(keyword: &str) -> Result<SerpData> {
-    use rand::seq::SliceRandom;
-    let user_agent = USER_AGENTS.choose(&mut rand::thread_rng())
-        .unwrap_or(&"Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Edge/123.0.0.0 Safari/537.36");
-    
-    // Use anonymous/incognito mode
-    let mut args = vec![
-        std::ffi::OsStr::new("--disable-blink-features=AutomationControlled"),
-        std::ffi::OsStr::new("--no-sandbox"),
-        std::ffi::OsStr::new("--disable-dev-shm-usage"),
-        std::ffi::OsStr::new("--disable-infobars"),
-        std::ffi::OsStr::new("--window-position=0,0"),
-        std::ffi::OsStr::new("--ignore-certificate-errors"),
-        std::ffi::OsStr::new("--incognito"),
-        std::ffi::OsStr::new("--headless=new"),
-    ];
-    let ua_arg = format!("--user-agent={}", user_agent);
-    args.push(std::ffi::OsStr::new(&ua_arg));
-
-    // Proxy config (same as Google)
-    let current_proxy = PROXY_MANAGER.get_next_proxy();
-    // Keep string alive for args
-    let mut proxy_arg = String::new(); 
-    
-    if let Some(ref proxy) = current_proxy {
-        proxy_arg = format!("--proxy-server={}", proxy.to_chrome_arg());
-        args.push(std::ffi::OsStr::new(&proxy_arg));
-        // Auth extension logic omitted for brevity in this block but should ideally be shared
-    } else {
-        println!("📡 No proxies configured. Using direct connection.");
-    }
-
-    let browser = Browser::new(LaunchOptions {
-        headless: false, 
-        window_size: Some((1920, 1080)),
-        args,
-        ..Default::default()
-    })?;
-
-    let tab = browser.new_tab()?;
+async fn search_bing_attempt(keyword: &str, options: &SearchOptions) -> Result<SerpData> {
+    let user_agent = random_profile().user_agent;
     
-    // Inject Stealth
-    let stealth_script = crate::stealth::get_stealth_script();
-    tab.enable_debugger()?;
-    tab.call_method(headless_chrome::protocol::cdp::Page::AddScriptToEvaluateOnNewDocument {
-        source: stealth_script.to_string(),
-        world_name: None,
-        include_command_line_api: None,
-        run_immediately: None,
-    })?;
+    // Skip proxies cooling down on Bing
+    let current_proxy = select_proxy(Some("bing"), options.session_key.as_deref()).await?;
+    let proxy_id = current_proxy.as_ref().map(|p| p.id.clone());
+    let session = StealthBrowser::launch(current_proxy.as_deref(), user_agent, &stealth_for(user_agent, options.stealth.as_ref()))?;
+    let tab = session.tab.clone();
+    let document_response = track_document_response(&tab);
 
     // Apply Fingerprint Overrides (Timezone/Locale) matching IP
     if let Err(e) = crate::stealth::apply_stealth_settings(&tab, "Asia/Yangon", "en-US").await {
          eprintln!("Failed to apply stealth settings: {}", e);
     }
 
+    if options.warmup {
+        warmup_navigation(&tab, "https://www.bing.com/").await;
+    }
+
     // 1. Navigate to Home (Force US Market)
     println!("Navigating to Bing Home...");
     tab.navigate_to("https://www.bing.com/?setmkt=en-US&setlang=en-us")?;
     tab.wait_until_navigated()?;
+    check_rate_limit(&document_response, "bing", proxy_id.as_deref())?;
     
     sleep(Duration::from_millis(2000 + (rand::random::<u64>() % 2000))).await;
 
@@ -510,175 +2397,222 @@ async fn search_bing_attempt(keyword: &str) -> Result<SerpData> {
     println!("Typing query: {}...", keyword);
     for char in keyword.chars() {
         tab.type_str(&char.to_string())?;
-        sleep(Duration::from_millis(80 + (rand::random::<u64>() % 100))).await;
+        sleep(crate::stealth::TYPING_DELAY.next_delay()).await;
     }
     sleep(Duration::from_millis(500)).await;
 
-    // 3. Submit
+    // 3. Submit (engine options ride along as form fields)
+    let options_on_form = add_engine_options_to_form(&tab, options)?;
     println!("Submitting search...");
     tab.press_key("Enter")?;
     tab.wait_until_navigated()?;
     println!("Search submitted.");
+    if !options_on_form {
+        reload_with_engine_options(&tab, options)?;
+    }
 
     // Check for Challenge AFTER search
-    sleep(Duration::from_secs(3)).await;
+    sleep(TIMEOUTS.dom_settle).await;
+    check_rate_limit(&document_response, "bing", proxy_id.as_deref())?;
     let html_content = tab.get_content()?;
-    if html_content.contains("Challenge") || html_content.contains("needs to review the security") {
+    if is_bing_challenge(&html_content) {
          println!("⚠️ CHALLENGE DETECTED: Bing served Challenge/Captcha page");
+         cool_down_proxy("bing", proxy_id.as_deref());
          let _ = tab.capture_screenshot(headless_chrome::protocol::cdp::Page::CaptureScreenshotFormatOption::Png, None, None, true)
             .map(|s| std::fs::write("debug/debug_bing_challenge.png", s));
-         return Err(anyhow::anyhow!("Bing Challenge Detected"));
+         return Err(SearchFailed::new(FailureReason::ChallengeDetected, "Bing Challenge Detected"));
     }
+    if is_serp_too_small(&html_content) {
+        println!("⚠️ Bing SERP is only {} bytes", html_content.len());
+        return Err(SearchFailed::new(FailureReason::PageTooSmall, format!("Bing SERP too small ({} bytes)", html_content.len())));
+    }
+
+    // Expand "People also ask" entries so the question list is fully rendered
+    let expanded = tab.evaluate(&format!(r#"
+        (() => {{
+            const items = document.querySelectorAll("{}");
+            items.forEach(el => {{ try {{ el.click(); }} catch (e) {{}} }});
+            return items.length;
+        }})()
+    "#, BING_PAA_SELECTOR), false)
+        .ok()
+        .and_then(|r| r.value)
+        .and_then(|v| v.as_u64())
+        .unwrap_or(0);
+    let html_content = if expanded > 0 {
+        println!("❓ Expanded {} Bing PAA entries", expanded);
+        sleep(Duration::from_millis(800)).await;
+        tab.get_content()?
+    } else {
+        html_content
+    };
 
     // Extract Data
     println!("Extraction method: dom");
-    let document = Html::parse_document(&html_content);
-    let mut results = Vec::new();
-    
-    // Bing Organic Selector: #b_results > li.b_algo
-    let result_selector = Selector::parse("#b_results > li.b_algo").unwrap();
-    for element in document.select(&result_selector) {
-        let title_sel = Selector::parse("h2 a").unwrap();
-        let snippet_sel = Selector::parse(".b_caption p").unwrap();
-        
-        let title = element.select(&title_sel).next().map(|e| e.text().collect::<String>()).unwrap_or_default();
-        let link = element.select(&title_sel).next().and_then(|e| e.value().attr("href")).unwrap_or_default().to_string();
-        let snippet = element.select(&snippet_sel).next().map(|e| e.text().collect::<String>()).unwrap_or_default();
-        
-        if !title.is_empty() && !link.is_empty() {
-             results.push(SearchResult { title, link, snippet });
-        }
+    let (mut results, total_results, total_results_count, people_also_ask) = {
+        let document = Html::parse_document(&html_content);
+
+        // Bing result count: "1,230,000 Results" / "1-10 of 1,230,000 results"
+        let count_sel = Selector::parse(".sb_count").unwrap();
+        let total_results = document.select(&count_sel).next()
+            .map(|e| e.text().collect::<String>());
+        let total_results_count = total_results.as_deref().and_then(parse_result_count);
+
+        (parse_bing_results(&document), total_results, total_results_count, extract_bing_paa(&document))
+    };
+
+    if options.pages > 1 {
+        paginate_serp(&tab, "bing", proxy_id.as_deref(), options.pages - 1, &mut results, |_, html| {
+            parse_bing_results(&Html::parse_document(html))
+        }).await;
     }
 
     Ok(SerpData {
          results,
          related_searches: vec![],
-         people_also_ask: vec![],
-         total_results: None,
-         featured_snippet: None
+         people_also_ask,
+         total_results,
+         total_results_count,
+         featured_snippet: None,
+         filtered_results: vec![],
+         truncated_fields: vec![],
+         pages: vec![],
+         robots_disallowed: vec![],
     })
 }
 
-pub async fn search_google(keyword: &str) -> Result<SerpData> {
+pub async fn search_google(keyword: &str, options: &SearchOptions) -> Result<SerpData> {
     println!("🔎 Starting Google Deep Search for: {}", keyword);
     let mut last_error = String::from("No results found");
+    let max_attempts = max_attempts_for("google", options.max_attempts);
+    let mut last_reason = Some(FailureReason::NoResultsFound);
     
-    // Max 3 attempts for resilience
-    for attempt in 1..=3 {
+    for attempt in 1..=max_attempts {
         if attempt > 1 {
-             println!("🔄 Retry Attempt {}/3...", attempt);
+             println!("🔄 Retry Attempt {}/{}...", attempt, max_attempts);
         }
 
-        match search_google_attempt(keyword, attempt).await {
+        match search_google_attempt(keyword, attempt, max_attempts, options).await {
             Ok(data) => {
                 if data.results.is_empty() {
-                    println!("⚠️ Attempt {}/3: Google returned 0 results (Block/Captcha?).", attempt);
-                    if attempt < 3 {
+                    println!("⚠️ Attempt {}/{}: Google returned 0 results (Block/Captcha?).", attempt, max_attempts);
+                    last_error = String::from("No results found");
+                    last_reason = Some(FailureReason::NoResultsFound);
+                    if attempt < max_attempts {
                         let wait_time = 5 * attempt as u64;
                         println!("⏳ Waiting {}s before retry...", wait_time);
                         sleep(Duration::from_secs(wait_time)).await;
                         continue;
                     }
                 } else {
-                    println!("✅ Attempt {}/3: Success! Found {} results.", attempt, data.results.len());
+                    println!("✅ Attempt {}/{}: Success! Found {} results.", attempt, max_attempts, data.results.len());
                     return Ok(data);
                 }
             }
             Err(e) => {
-                println!("❌ Attempt {}/3: Error: {}", attempt, e);
+                println!("❌ Attempt {}/{}: Error: {}", attempt, max_attempts, e);
                 last_error = e.to_string();
-                if attempt < 3 {
-                    sleep(Duration::from_secs(5)).await;
+                last_reason = failure_reason(&e);
+                if attempt < max_attempts {
+                    // Rate limits get a longer backoff; the next attempt picks another proxy
+                    let wait = if e.downcast_ref::<RateLimited>().is_some() { rate_limit_backoff(attempt) } else { Duration::from_secs(5) };
+                    println!("⏳ Waiting {}s before retry...", wait.as_secs());
+                    sleep(wait).await;
                 }
             }
         }
     }
-    
-    Err(anyhow::anyhow!("Google search failed after 3 attempts. Last error: {}", last_error))
+
+    let message = format!("Google search failed after {} attempts. Last error: {}", max_attempts, last_error);
+    Err(match last_reason {
+        Some(reason) => SearchFailed::new(reason, message),
+        None => anyhow::anyhow!(message),
+    })
+}
+
+/// Google organic results from the rendered DOM as JSON `{method, results}` (top 10 per page)
+const GOOGLE_DOM_EXTRACT_JS: &str = r#"
+    (() => {
+        const results = [];
+        const mainContent = document.querySelector('[role="main"]') || document.querySelector('#main');
+        
+        if (!mainContent) {
+            console.log('[EXTRACT] No main content found');
+            return JSON.stringify({method: "dom", results: [], error: "no_main"});
+        }
+        
+        console.log('[EXTRACT] Main content found');
+        
+        // Step 5: Expanded selectors (union of known Google containers)
+        const resultBlocks = mainContent.querySelectorAll(
+            '[data-snf], .g, [jscontroller="SC7lYd"], [data-ved], .Gx5Zad'
+        );
+        
+        console.log(`[EXTRACT] Found ${resultBlocks.length} result blocks`);
+        
+        // Step 4: DOM Snapshot Fallback
+        if (resultBlocks.length === 0 && !document.querySelector('[role="main"] h3')) {
+            console.log('[EXTRACT] No blocks found, trying script tag fallback');
+            const scriptData = Array.from(document.scripts).find(s => 
+                s.textContent?.includes('"results":') || s.textContent?.includes('AF_initDataCallback')
+            );
+            if (scriptData) {
+                return JSON.stringify({
+                    method: "script_fallback", 
+                    results: [], 
+                    raw_snippet: scriptData.textContent.substring(0, 200)
+                });
+            }
+        }
+        
+        resultBlocks.forEach((block, idx) => {
+            const titleEl = block.querySelector('h3, [role="heading"]');
+            const linkEl = block.querySelector('a[href^="http"]:not([href*="google.com"])') || 
+                          block.querySelector('a[jsname]');
+            const snippetEl = block.querySelector('[data-content], [role="text"], .VwiC3b, .IsZvec, .yXK7lf');
+            
+            if (titleEl && linkEl && linkEl.href && !linkEl.href.includes('google.com/search')) {
+                console.log(`[EXTRACT] Block ${idx}: ${titleEl.textContent.trim().substring(0, 30)}`);
+                results.push({
+                    title: titleEl.textContent.trim(),
+                    link: linkEl.href,
+                    snippet: snippetEl ? snippetEl.textContent.trim() : ""
+                });
+            }
+        });
+        
+        console.log(`[EXTRACT] Returning ${results.length} results`);
+        return JSON.stringify({method: "dom", results: results.slice(0, 10)});
+    })();
+"#;
+
+/// Organic results on the current Google page via `GOOGLE_DOM_EXTRACT_JS`
+fn google_dom_results(tab: &std::sync::Arc<headless_chrome::Tab>) -> Vec<SearchResult> {
+    tab.evaluate(GOOGLE_DOM_EXTRACT_JS, true)
+        .ok()
+        .and_then(|r| r.value)
+        .and_then(|v| v.as_str().and_then(|s| serde_json::from_str::<serde_json::Value>(s).ok()))
+        .and_then(|parsed| serde_json::from_value(parsed["results"].clone()).ok())
+        .unwrap_or_default()
 }
 
 // Internal attempt function
-async fn search_google_attempt(keyword: &str, attempt: u32) -> Result<SerpData> {
+async fn search_google_attempt(keyword: &str, attempt: u32, max_attempts: u32, options: &SearchOptions) -> Result<SerpData> {
     use rand::seq::SliceRandom;
-    let user_agent = if attempt == 3 {
-        // Mobile Agents for Attempt 3
-        static MOBILE_AGENTS: &[&str] = &[
-            "Mozilla/5.0 (Linux; Android 10; K) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/124.0.0.0 Mobile Safari/537.36",
-            "Mozilla/5.0 (iPhone; CPU iPhone OS 17_4_1 like Mac OS X) AppleWebKit/605.1.15 (KHTML, like Gecko) Version/17.4.1 Mobile/15E148 Safari/604.1",
-        ];
-        MOBILE_AGENTS.choose(&mut rand::thread_rng()).unwrap()
+    let user_agent = if attempt > 1 && attempt == max_attempts {
+        // Mobile Agents for the last retry
+        MOBILE_USER_AGENTS.choose(&mut rand::thread_rng()).unwrap().user_agent
     } else {
-        USER_AGENTS.choose(&mut rand::thread_rng())
-        .unwrap_or(&"Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/123.0.0.0 Safari/537.36")
+        random_profile().user_agent
     };
     
     println!("Using User-Agent (Attempt {}): {}", attempt, user_agent);
 
-    // Use anonymous/incognito mode (no profile persistence)
-    let mut args = vec![
-        std::ffi::OsStr::new("--disable-blink-features=AutomationControlled"),
-        std::ffi::OsStr::new("--no-sandbox"),
-        std::ffi::OsStr::new("--disable-dev-shm-usage"),
-        std::ffi::OsStr::new("--disable-infobars"),
-        std::ffi::OsStr::new("--window-position=0,0"),
-        std::ffi::OsStr::new("--ignore-certificate-errors"),
-        std::ffi::OsStr::new("--ignore-certificate-errors-spki-list"),
-        std::ffi::OsStr::new("--incognito"),
-    ];
-    let ua_arg = format!("--user-agent={}", user_agent);
-    args.push(std::ffi::OsStr::new(&ua_arg));
-
-    // Use modern headless mode
-    args.push(std::ffi::OsStr::new("--headless=new"));
-
-    // Add proxy if available (using new ProxyManager)
-    let proxy_arg: String;
-    let ext_arg: String;
-    let current_proxy = PROXY_MANAGER.get_next_proxy();
-    let _proxy_id = current_proxy.as_ref().map(|p| p.id.clone());
-    
-    if let Some(ref proxy) = current_proxy {
-        println!("🔄 Using proxy: {} (healthy: {}, success_rate: {:.1}%)", 
-            proxy.id, 
-            proxy.healthy.load(std::sync::atomic::Ordering::Relaxed),
-            proxy.success_rate() * 100.0
-        );
-        proxy_arg = format!("--proxy-server={}", proxy.to_chrome_arg());
-        args.push(std::ffi::OsStr::new(&proxy_arg));
-        
-        // Add auth extension if proxy requires authentication
-        if proxy.requires_auth() {
-            let ext_path = generate_proxy_auth_extension(
-                proxy.username.as_ref().unwrap(),
-                proxy.password.as_ref().unwrap()
-            );
-            ext_arg = format!("--load-extension={}", ext_path);
-            args.push(std::ffi::OsStr::new(&ext_arg));
-            println!("🔐 Proxy auth extension loaded");
-        }
-    }
-
-    let browser = Browser::new(LaunchOptions {
-        headless: false, // Use new headless mode via args
-        window_size: Some((1920, 1080)),
-        args,
-        ..Default::default()
-    })?;
-
-    let tab = browser.new_tab()?;
-
-    // Layer 1: Device & Environment Fingerprinting (JS-Level)
-    // Layer 1: Device & Environment Fingerprinting (JS-Level)
-    // Layer 1: Device & Environment Fingerprinting (JS-Level)
-    let stealth_script = crate::stealth::get_stealth_script();
-
-    tab.enable_debugger()?;
-    tab.call_method(headless_chrome::protocol::cdp::Page::AddScriptToEvaluateOnNewDocument {
-        source: stealth_script.to_string(),
-        world_name: None,
-        include_command_line_api: None,
-        run_immediately: None,
-    })?;
+    let current_proxy = select_proxy(Some("google"), options.session_key.as_deref()).await?;
+    let proxy_id = current_proxy.as_ref().map(|p| p.id.clone());
+    let session = StealthBrowser::launch(current_proxy.as_deref(), user_agent, &stealth_for(user_agent, options.stealth.as_ref()))?;
+    let tab = session.tab.clone();
+    let document_response = track_document_response(&tab);
 
     // Apply Fingerprint Overrides (Timezone/Locale) for Residential IP
     if let Err(e) = crate::stealth::apply_stealth_settings(&tab, "Asia/Yangon", "en-US").await {
@@ -697,9 +2631,14 @@ async fn search_google_attempt(keyword: &str, attempt: u32) -> Result<SerpData>
         let _ = inject_cookies(&tab, &cookies);
     }
     
+    if options.warmup {
+        warmup_navigation(&tab, "https://www.google.com/").await;
+    }
+
     println!("Navigating to Google Home (Attempt {}, URL: {})...", attempt, url);
     tab.navigate_to(&url)?;
     tab.wait_until_navigated()?;
+    check_rate_limit(&document_response, "google", proxy_id.as_deref())?;
     
     // Random wait to simulate reading
     sleep(Duration::from_millis(3000 + (rand::random::<u64>() % 2000))).await;
@@ -735,7 +2674,7 @@ async fn search_google_attempt(keyword: &str, attempt: u32) -> Result<SerpData>
         println!("Consent check result: {}", result);
         if result == "consent_clicked" {
             println!("Consent accepted, waiting for redirect...");
-            sleep(Duration::from_secs(2)).await;
+            sleep(TIMEOUTS.dom_settle).await;
             tab.wait_until_navigated()?;
         }
     }
@@ -751,7 +2690,7 @@ async fn search_google_attempt(keyword: &str, attempt: u32) -> Result<SerpData>
          println!("Native mouse move failed: {}", e);
     }
 
-    sleep(Duration::from_millis(1000)).await;
+    sleep(TIMEOUTS.dom_settle / 3).await;
     
     // Take screenshot for debugging
     println!("Capturing screenshot for debugging...");
@@ -774,7 +2713,7 @@ async fn search_google_attempt(keyword: &str, attempt: u32) -> Result<SerpData>
     
     for selector in selectors {
         println!("Trying selector: {}", selector);
-        match tab.wait_for_element_with_custom_timeout(selector, std::time::Duration::from_secs(10)) {
+        match tab.wait_for_element_with_custom_timeout(selector, TIMEOUTS.element_wait) {
             Ok(el) => {
                 println!("✅ Found search box with: {}", selector);
                 search_box_result = Some(el);
@@ -790,7 +2729,7 @@ async fn search_google_attempt(keyword: &str, attempt: u32) -> Result<SerpData>
     
     // Wait for React/JS to finish rendering
     println!("Waiting for search box to become interactive...");
-    sleep(Duration::from_millis(1000)).await;
+    sleep(TIMEOUTS.dom_settle / 3).await;
     
     // Use JS to click and focus (more reliable than CDP click for dynamic elements)
     println!("Clicking and focusing search box via JS...");
@@ -808,30 +2747,36 @@ async fn search_google_attempt(keyword: &str, attempt: u32) -> Result<SerpData>
     println!("Typing query: {}...", keyword);
     for char in keyword.chars() {
         tab.type_str(&char.to_string())?;
-        sleep(Duration::from_millis(100 + (rand::random::<u64>() % 150))).await;
+        sleep(crate::stealth::TYPING_DELAY.next_delay()).await;
     }
     
     sleep(Duration::from_millis(500)).await;
 
-    // 3. Submit
+    // 3. Submit (engine options ride along as form fields)
+    let options_on_form = add_engine_options_to_form(&tab, options)?;
     println!("Submitting search...");
     tab.press_key("Enter")?;
     tab.wait_until_navigated()?;
     println!("Search submitted.");
+    if !options_on_form {
+        reload_with_engine_options(&tab, options)?;
+    }
 
     // Check for Challenge/Captcha immediately after navigation
-    sleep(Duration::from_secs(2)).await;
+    sleep(TIMEOUTS.dom_settle).await;
+    check_rate_limit(&document_response, "google", proxy_id.as_deref())?;
     let html_content = tab.get_content()?;
-    if html_content.contains("unusual traffic") || html_content.contains("captcha-form") || html_content.contains("systems have detected") {
+    if is_google_challenge(&html_content) {
          println!("⚠️ CHALLENGE DETECTED: Google served Captcha/Unusual Traffic page");
+         cool_down_proxy("google", proxy_id.as_deref());
          let _ = tab.capture_screenshot(headless_chrome::protocol::cdp::Page::CaptureScreenshotFormatOption::Png, None, None, true)
             .map(|s| std::fs::write("debug/debug_google_challenge.png", s));
-         return Err(anyhow::anyhow!("Google Challenge Detected"));
+         return Err(SearchFailed::new(FailureReason::ChallengeDetected, "Google Challenge Detected"));
     }
     
     // Check for Google autocorrection message and click "Search instead for [exact term]"
     // Wait longer for the "Search instead for" link to appear
-    sleep(Duration::from_millis(3000)).await;
+    sleep(TIMEOUTS.dom_settle).await;
     let verbatim_result = tab.evaluate(r#"
         (() => {
             // Helper to find link by text
@@ -872,7 +2817,7 @@ async fn search_google_attempt(keyword: &str, attempt: u32) -> Result<SerpData>
         println!("Verbatim check result: {}", result);
         if result != "no_autocorrect" {
             println!("Clicked verbatim link, waiting for reload...");
-            sleep(Duration::from_secs(2)).await;
+            sleep(TIMEOUTS.dom_settle).await;
             tab.wait_until_navigated()?;
         }
     }
@@ -896,8 +2841,8 @@ async fn search_google_attempt(keyword: &str, attempt: u32) -> Result<SerpData>
     // Step 1: ✅ Already navigating to homepage → typing → submit (not direct SERP URL)
     
     // Add static wait for Google JS to initialize before mutation observer
-    println!("Waiting 3s for Google JS to initialize...");
-    sleep(Duration::from_secs(3)).await;
+    println!("Waiting {:?} for Google JS to initialize...", TIMEOUTS.dom_settle);
+    sleep(TIMEOUTS.dom_settle).await;
     
     // Step 2: Mutation observer with increased timeout (15s) and logging
     println!("Waiting for Google DOM mutations to complete...");
@@ -931,65 +2876,10 @@ async fn search_google_attempt(keyword: &str, attempt: u32) -> Result<SerpData>
     
     // Step 3: Extract via semantic attributes (resilient to class changes)
     let extraction_method: String;
-    let results: Vec<SearchResult>;
+    let mut results: Vec<SearchResult>;
     
     // Method 1: DOM extraction using expanded selectors (Step 5)
-    let dom_extract_script = r#"
-        (() => {
-            const results = [];
-            const mainContent = document.querySelector('[role="main"]') || document.querySelector('#main');
-            
-            if (!mainContent) {
-                console.log('[EXTRACT] No main content found');
-                return JSON.stringify({method: "dom", results: [], error: "no_main"});
-            }
-            
-            console.log('[EXTRACT] Main content found');
-            
-            // Step 5: Expanded selectors (union of known Google containers)
-            const resultBlocks = mainContent.querySelectorAll(
-                '[data-snf], .g, [jscontroller="SC7lYd"], [data-ved], .Gx5Zad'
-            );
-            
-            console.log(`[EXTRACT] Found ${resultBlocks.length} result blocks`);
-            
-            // Step 4: DOM Snapshot Fallback
-            if (resultBlocks.length === 0 && !document.querySelector('[role="main"] h3')) {
-                console.log('[EXTRACT] No blocks found, trying script tag fallback');
-                const scriptData = Array.from(document.scripts).find(s => 
-                    s.textContent?.includes('"results":') || s.textContent?.includes('AF_initDataCallback')
-                );
-                if (scriptData) {
-                    return JSON.stringify({
-                        method: "script_fallback", 
-                        results: [], 
-                        raw_snippet: scriptData.textContent.substring(0, 200)
-                    });
-                }
-            }
-            
-            resultBlocks.forEach((block, idx) => {
-                const titleEl = block.querySelector('h3, [role="heading"]');
-                const linkEl = block.querySelector('a[href^="http"]:not([href*="google.com"])') || 
-                              block.querySelector('a[jsname]');
-                const snippetEl = block.querySelector('[data-content], [role="text"], .VwiC3b, .IsZvec, .yXK7lf');
-                
-                if (titleEl && linkEl && linkEl.href && !linkEl.href.includes('google.com/search')) {
-                    console.log(`[EXTRACT] Block ${idx}: ${titleEl.textContent.trim().substring(0, 30)}`);
-                    results.push({
-                        title: titleEl.textContent.trim(),
-                        link: linkEl.href,
-                        snippet: snippetEl ? snippetEl.textContent.trim() : ""
-                    });
-                }
-            });
-            
-            console.log(`[EXTRACT] Returning ${results.length} results`);
-            return JSON.stringify({method: "dom", results: results.slice(0, 10)});
-        })();
-    "#;
-    
-    match tab.evaluate(dom_extract_script, true) {
+    match tab.evaluate(GOOGLE_DOM_EXTRACT_JS, true) {
         Ok(result) => {
             if let Some(serde_json::Value::String(value_str)) = result.value {
                 let parsed: serde_json::Value = serde_json::from_str(&value_str).unwrap_or_default();
@@ -1050,8 +2940,15 @@ async fn search_google_attempt(keyword: &str, attempt: u32) -> Result<SerpData>
         let _ = std::fs::write("debug/debug_google_tier1.html", &html_content);
     }
 
-    // Extract People Also Ask
+    // PAA, related searches, count and snippet come from page 1
     let html_content = tab.get_content()?;
+    if options.pages > 1 {
+        paginate_serp(&tab, "google", proxy_id.as_deref(), options.pages - 1, &mut results, |tab, _| {
+            google_dom_results(tab)
+        }).await;
+    }
+
+    // Extract People Also Ask
     let document = Html::parse_document(&html_content);
     
     let paa_selector = Selector::parse(".related-question-pair .s75CSd").unwrap();
@@ -1078,6 +2975,7 @@ async fn search_google_attempt(keyword: &str, attempt: u32) -> Result<SerpData>
     let count_selector = Selector::parse("#result-stats").unwrap();
     let total_results = document.select(&count_selector).next()
         .map(|e| e.text().collect::<String>());
+    let total_results_count = total_results.as_deref().and_then(parse_result_count);
         
     // Extract Featured Snippet
     let snippet_selector = Selector::parse(".xpdopen .block-component, .c2xzTb").unwrap();
@@ -1095,38 +2993,108 @@ async fn search_google_attempt(keyword: &str, attempt: u32) -> Result<SerpData>
         related_searches,
         featured_snippet,
         total_results,
+        total_results_count,
+        filtered_results: vec![],
+        truncated_fields: vec![],
+        pages: vec![],
+        robots_disallowed: vec![],
     })
 }
 
-pub async fn extract_content(url: &str) -> Result<ExtractedContent> {
+/// Resolve a `file://` URL to a local path. Local files are only readable when
+/// `allow_file_urls` is set (ALLOW_FILE_URLS=true, dev/testing against saved HTML
+/// fixtures); other schemes return None and go through the network as usual.
+fn local_file_path(url: &str, allow_file_urls: bool) -> Result<Option<std::path::PathBuf>> {
+    match reqwest::Url::parse(url) {
+        Ok(parsed) if parsed.scheme() == "file" => {
+            if !allow_file_urls {
+                return Err(anyhow::anyhow!("file:// URLs are disabled (set ALLOW_FILE_URLS=true to enable)"));
+            }
+            parsed
+                .to_file_path()
+                .map(Some)
+                .map_err(|_| anyhow::anyhow!("Invalid file URL: {}", url))
+        }
+        _ => Ok(None),
+    }
+}
+
+/// Built once; None keeps reqwest's default rustls settings
+static FAST_PATH_TLS: Lazy<Option<rustls::ClientConfig>> = Lazy::new(crate::stealth::fast_path_tls_config);
+
+/// Fetch and parse `url` over HTTP; `allow_file_urls` also lets it read file:// fixtures
+pub async fn extract_content(url: &str, allow_file_urls: bool) -> Result<ExtractedContent> {
     // Decode Bing/Google redirect URLs to get actual destination
     let actual_url = decode_search_url(url);
     println!("Extracting content from: {}", actual_url);
+
+    if let Some(path) = local_file_path(&actual_url, allow_file_urls)? {
+        let body = tokio::fs::read(&path).await?;
+        println!("📂 Read local file {} ({} bytes)", path.display(), body.len());
+        return parse_extracted_content(String::from_utf8_lossy(&body).into_owned(), &actual_url);
+    }
     
     // Use proper User-Agent and follow redirects
-    use rand::seq::SliceRandom;
-    let user_agent = USER_AGENTS.choose(&mut rand::thread_rng())
-        .unwrap_or(&"Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/123.0.0.0 Safari/537.36");
+    let user_agent = random_profile().user_agent;
+
+    // Limits: EXTRACT_CONNECT_TIMEOUT_SECS, EXTRACT_READ_TIMEOUT_SECS (per chunk),
+    // EXTRACT_TIMEOUT_SECS (whole request) and EXTRACT_MAX_BODY_BYTES
+    let connect_timeout = Duration::from_secs(env_parse("EXTRACT_CONNECT_TIMEOUT_SECS", 10));
+    let read_timeout = Duration::from_secs(env_parse("EXTRACT_READ_TIMEOUT_SECS", 15));
+    let total_timeout = Duration::from_secs(env_parse("EXTRACT_TIMEOUT_SECS", 30));
+    let max_body_bytes: usize = env_parse("EXTRACT_MAX_BODY_BYTES", 10 * 1024 * 1024);
 
-    let client = reqwest::Client::builder()
+    let mut builder = reqwest::Client::builder()
         .user_agent(*user_agent)
         .redirect(reqwest::redirect::Policy::limited(10))
-        .timeout(Duration::from_secs(30))
-        .build()?;
+        .connect_timeout(connect_timeout)
+        .timeout(total_timeout);
+    // Chrome-like cipher order + ALPN to soften the JA3 mismatch (see stealth::fast_path_tls_config)
+    if let Some(tls) = FAST_PATH_TLS.as_ref() {
+        builder = builder.use_preconfigured_tls(tls.clone());
+    }
+    let client = builder.build()?;
     
-    let resp: reqwest::Response = client.get(&actual_url)
+    let mut resp: reqwest::Response = client.get(&actual_url)
         .header("Accept-Language", "en-US,en;q=0.9")
         .send().await?;
     let final_url = resp.url().to_string();
     println!("Final URL after redirects: {}", final_url);
-    
-    let html = resp.text().await?;
+
+    if let Some(len) = resp.content_length() {
+        if len as usize > max_body_bytes {
+            return Err(anyhow::anyhow!("Response too large: {} bytes (limit {})", len, max_body_bytes));
+        }
+    }
+
+    // Stream the body with a running byte counter so oversized/slow responses are cut off
+    let mut body: Vec<u8> = Vec::new();
+    loop {
+        let chunk = tokio::time::timeout(read_timeout, resp.chunk())
+            .await
+            .map_err(|_| anyhow::anyhow!("Read timed out after {:?}", read_timeout))??;
+        match chunk {
+            Some(bytes) => {
+                if body.len() + bytes.len() > max_body_bytes {
+                    return Err(anyhow::anyhow!("Response exceeded {} bytes, aborting", max_body_bytes));
+                }
+                body.extend_from_slice(&bytes);
+            }
+            None => break,
+        }
+    }
+
+    let html = String::from_utf8_lossy(&body).into_owned();
     println!("Fetched HTML size: {} bytes", html.len());
-    
+    parse_extracted_content(html, &final_url)
+}
+
+/// Readability text + basic meta tags from fetched (or local) HTML
+fn parse_extracted_content(html: String, final_url: &str) -> Result<ExtractedContent> {
     let mut reader = Cursor::new(html.as_bytes());
     
     // 1. Extract text with Readability
-    let text = match readability::extractor::extract(&mut reader, &reqwest::Url::parse(&final_url)?) {
+    let text = match readability::extractor::extract(&mut reader, &reqwest::Url::parse(final_url)?) {
         Ok(product) => product.text,
         Err(_) => "Failed to extract content".to_string(),
     };
@@ -1147,7 +3115,7 @@ pub async fn extract_content(url: &str) -> Result<ExtractedContent> {
         .and_then(|e| e.value().attr("content").map(|s| s.to_string()));
 
     Ok(ExtractedContent {
-        html: html.clone(),
+        html,
         text,
         meta_description,
         meta_author,
@@ -1155,85 +3123,115 @@ pub async fn extract_content(url: &str) -> Result<ExtractedContent> {
     })
 }
 
+/// Clip covering the whole document (height capped at `max_height` px). Capturing
+/// with it from the surface renders beyond the viewport, i.e. a full-page shot.
+fn full_page_clip(tab: &headless_chrome::Tab, max_height: f64) -> Result<headless_chrome::protocol::cdp::Page::Viewport> {
+    let dims = tab.evaluate(
+        "JSON.stringify([document.documentElement.scrollWidth, document.documentElement.scrollHeight])",
+        false,
+    )?;
+    let (width, height) = dims.value
+        .and_then(|v| v.as_str().and_then(|s| serde_json::from_str::<(f64, f64)>(s).ok()))
+        .unwrap_or((1920.0, 1080.0));
+
+    Ok(headless_chrome::protocol::cdp::Page::Viewport {
+        x: 0.0,
+        y: 0.0,
+        width: width.max(1.0),
+        height: height.clamp(1.0, max_height),
+        scale: 1.0,
+    })
+}
+
+/// Capture a full-page JPEG screenshot via CDP.
+/// Height is capped at THUMBNAIL_MAX_HEIGHT px (default 8000) and the image is
+/// discarded if larger than THUMBNAIL_MAX_BYTES (default 2MB).
+pub fn capture_full_page_thumbnail(tab: &std::sync::Arc<headless_chrome::Tab>) -> Result<Option<Vec<u8>>> {
+    use headless_chrome::protocol::cdp::Page;
+
+    let max_height: f64 = env_parse("THUMBNAIL_MAX_HEIGHT", 8000.0);
+    let max_bytes: usize = env_parse("THUMBNAIL_MAX_BYTES", 2 * 1024 * 1024);
+
+    let clip = full_page_clip(tab, max_height)?;
+    let image = tab.capture_screenshot(Page::CaptureScreenshotFormatOption::Jpeg, Some(70), Some(clip), true)?;
+
+    if image.len() > max_bytes {
+        println!("⚠️ Thumbnail too large ({} bytes > {}), skipping", image.len(), max_bytes);
+        return Ok(None);
+    }
+    Ok(Some(image))
+}
+
+/// Capture a lossless full-page PNG via CDP. Height is capped at
+/// SCREENSHOT_MAX_HEIGHT px (default 16384, Chrome's texture limit); the image
+/// is discarded if larger than SCREENSHOT_MAX_BYTES (default 10MB).
+pub fn capture_full_page_screenshot(tab: &std::sync::Arc<headless_chrome::Tab>) -> Result<Option<Vec<u8>>> {
+    use headless_chrome::protocol::cdp::Page;
+
+    let max_height: f64 = env_parse("SCREENSHOT_MAX_HEIGHT", 16384.0);
+    let max_bytes: usize = env_parse("SCREENSHOT_MAX_BYTES", 10 * 1024 * 1024);
+
+    let clip = full_page_clip(tab, max_height)?;
+    let image = tab.capture_screenshot(Page::CaptureScreenshotFormatOption::Png, None, Some(clip), true)?;
+
+    if image.len() > max_bytes {
+        println!("⚠️ Screenshot too large ({} bytes > {}), skipping", image.len(), max_bytes);
+        return Ok(None);
+    }
+    Ok(Some(image))
+}
+
 /// Deep extraction function that returns comprehensive WebsiteData using Headless Chrome
-pub async fn extract_website_data(url: &str) -> Result<WebsiteData> {
+pub async fn extract_website_data(url: &str, options: &ExtractOptions) -> Result<WebsiteData> {
     // Decode Bing/Google redirect URLs to get actual destination
     let actual_url = decode_search_url(url);
     println!("🔍 Deep integration extracting data from: {}", actual_url);
+    // Chrome opens file:// natively; only the gate is needed
+    if local_file_path(&actual_url, options.allow_file_urls)?.is_some() {
+        println!("📂 Extracting from local file (ALLOW_FILE_URLS=true)");
+    }
+    // Space out requests to the same host (before taking a browser, so none idles meanwhile)
+    politeness::DOMAIN_RATE_LIMITER.wait(&actual_url).await;
     
-    use rand::seq::SliceRandom;
-    let user_agent = USER_AGENTS.choose(&mut rand::thread_rng())
-        .unwrap_or(&"Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/123.0.0.0 Safari/537.36");
-
-    // Configure Chrome arguments for Stealth
-    let mut args = vec![
-        std::ffi::OsStr::new("--disable-blink-features=AutomationControlled"),
-        std::ffi::OsStr::new("--no-sandbox"),
-        std::ffi::OsStr::new("--disable-dev-shm-usage"),
-        std::ffi::OsStr::new("--disable-infobars"),
-        std::ffi::OsStr::new("--window-position=0,0"),
-        std::ffi::OsStr::new("--ignore-certificate-errors"),
-        std::ffi::OsStr::new("--ignore-certificate-errors-spki-list"),
-    ];
-    let ua_arg = format!("--user-agent={}", user_agent);
-    args.push(std::ffi::OsStr::new(&ua_arg));
+    let user_agent = match options.user_agent {
+        Some(ref ua) => ua.as_str(),
+        None => random_profile().user_agent,
+    };
 
-    // Use modern headless mode
-    args.push(std::ffi::OsStr::new("--headless=new"));
+    let stealth = stealth_for(user_agent, options.stealth.as_ref());
+    let session = match (pool::BROWSER_POOL.as_ref(), options.session_key.as_deref()) {
+        (Some(browser_pool), None) => pool::TabHandle::Pooled(browser_pool.acquire_stealth(user_agent, &stealth).await?),
+        // Pooled browsers keep one proxy for life, so a sticky binding needs its own launch
+        (_, session_key) => {
+            let current_proxy = select_proxy(None, session_key).await?;
+            let browser = StealthBrowser::launch(current_proxy.as_deref(), user_agent, &stealth)?;
+            pool::TabHandle::Dedicated { browser, _proxy: current_proxy }
+        }
+    };
+    let tab = session.tab().clone();
+    let document_response = track_document_response(&tab);
 
-    // Add proxy if available
-    let current_proxy = PROXY_MANAGER.get_next_proxy();
-    let proxy_arg: String;
-    let ext_arg: String;
-    
-    if let Some(ref proxy) = current_proxy {
-        proxy_arg = format!("--proxy-server={}", proxy.to_chrome_arg());
-        args.push(std::ffi::OsStr::new(&proxy_arg));
-        
-        if proxy.requires_auth() {
-            let ext_path = generate_proxy_auth_extension(
-                proxy.username.as_ref().unwrap(),
-                proxy.password.as_ref().unwrap()
-            );
-            ext_arg = format!("--load-extension={}", ext_path);
-            args.push(std::ffi::OsStr::new(&ext_arg));
+    // Referer-gated sites often show full content only to search visitors
+    if let Some(ref referer) = options.referer {
+        let mut headers = std::collections::HashMap::new();
+        headers.insert("Referer", referer.as_str());
+        if let Err(e) = tab.set_extra_http_headers(headers) {
+            println!("⚠️ Failed to set Referer header: {}", e);
         }
     }
 
-    // Launch Browser
-    let browser = Browser::new(LaunchOptions {
-        headless: false, // Use new headless mode via args
-        window_size: Some((1920, 1080)),
-        args,
-        ..Default::default()
-    })?;
-
-    let tab = browser.new_tab()?;
-
-    // Inject Stealth Script
-    // Inject Stealth Script
-    let stealth_script = crate::stealth::get_stealth_script();
-
-    tab.enable_debugger()?;
-    tab.call_method(headless_chrome::protocol::cdp::Page::AddScriptToEvaluateOnNewDocument {
-        source: stealth_script.to_string(),
-        world_name: None,
-        include_command_line_api: None,
-        run_immediately: None,
-    })?;
-
     // Navigate
     println!("Navigating to: {}", actual_url);
     tab.navigate_to(&actual_url)?;
     
     // Use softer wait (wait for body) instead of strict load event to prevent timeouts on ads/tracking
-    match tab.wait_for_element_with_custom_timeout("body", Duration::from_secs(15)) {
+    match tab.wait_for_element_with_custom_timeout("body", TIMEOUTS.navigation) {
         Ok(_) => println!("Page body loaded."),
         Err(e) => println!("⚠️ Warning: Body wait timed out: {}. Attempting extraction anyway...", e),
     }
 
     // Wait for JS execution (Hydration)
-    sleep(Duration::from_secs(4)).await;
+    sleep(TIMEOUTS.extraction).await;
 
     // Extract Data via JS
     let html = tab.evaluate("document.documentElement.outerHTML", false)?.value.unwrap().as_str().unwrap().to_string();
@@ -1241,6 +3239,30 @@ pub async fn extract_website_data(url: &str) -> Result<WebsiteData> {
     let html_size = html.len() as u32;
     println!("Extracted HTML size via Browser: {} bytes", html_size);
 
+    // Optional full-page thumbnail (must be done while the tab is alive)
+    let thumbnail = if options.capture_thumbnail {
+        match capture_full_page_thumbnail(&tab) {
+            Ok(image) => image,
+            Err(e) => {
+                println!("⚠️ Thumbnail capture failed: {}", e);
+                None
+            }
+        }
+    } else {
+        None
+    };
+    let screenshot = if options.capture_screenshot {
+        match capture_full_page_screenshot(&tab) {
+            Ok(image) => image,
+            Err(e) => {
+                println!("⚠️ Screenshot capture failed: {}", e);
+                None
+            }
+        }
+    } else {
+        None
+    };
+
     // 10. Marketing Data Extraction (Async - must be done before parsing document)
     let marketing_data = match extract_marketing_data(&tab).await {
         Ok(data) => Some(data),
@@ -1252,11 +3274,28 @@ pub async fn extract_website_data(url: &str) -> Result<WebsiteData> {
 
     // Parse document using Scraper for consistency with previous logic
     let document = Html::parse_document(&html);
-    
-    // Extract base domain
-    let base_domain = reqwest::Url::parse(&final_url)
-        .map(|u| u.host_str().unwrap_or("").to_string())
-        .unwrap_or_default();
+
+    // Charset: what the server/markup claims vs. what the raw bytes are in
+    let declared_charset = document_response
+        .content_type()
+        .as_deref()
+        .and_then(charset_from_content_type)
+        .or_else(|| extract_meta_charset(&document));
+    let raw_bytes = tab.evaluate(RAW_DOCUMENT_BYTES_JS, true)
+        .ok()
+        .and_then(|v| v.value)
+        .and_then(|v| v.as_str().and_then(|s| hex::decode(s).ok()));
+    let detected_charset = raw_bytes
+        .as_deref()
+        .and_then(|bytes| detect_charset(&bytes[..bytes.len().min(CHARSET_SNIFF_BYTES)], declared_charset.as_deref()));
+    let charset_mismatch = charset_mismatch(declared_charset.as_deref(), detected_charset.as_deref());
+    let redirect_chain = document_response.redirect_chain();
+    if redirect_chain.len() > 1 {
+        println!("↪️ Redirect chain: {} hops -> {}", redirect_chain.len() - 1, final_url);
+    }
+    if charset_mismatch {
+        println!("⚠️ Charset mismatch: declared {:?}, bytes look like {:?}", declared_charset, detected_charset);
+    }
     
     // 1. Extract title
     let title = tab.evaluate("document.title", false)?.value.unwrap().as_str().unwrap().to_string();
@@ -1276,18 +3315,25 @@ pub async fn extract_website_data(url: &str) -> Result<WebsiteData> {
     let meta_date = document.select(&date_selector).next()
         .and_then(|e| e.value().attr("content").map(|s| s.to_string()));
     
-    // 3. Extract main text using Readability on the rendered HTML
+    // 3. Extract main text (and its Markdown) using Readability on the rendered HTML
     let mut reader = Cursor::new(html.as_bytes());
-    let main_text = match readability::extractor::extract(&mut reader, &reqwest::Url::parse(&final_url)?) {
-        Ok(product) => product.text,
+    let page_url = reqwest::Url::parse(&final_url)?;
+    let (main_text, main_markdown) = match readability::extractor::extract(&mut reader, &page_url) {
+        Ok(product) => {
+            let main_markdown = markdown::html_to_markdown(&product.content, Some(&page_url));
+            (product.text, main_markdown)
+        }
         Err(_) => {
             // Fallback to body text if Readability fails
-            tab.evaluate("document.body.innerText", false)
+            let text = tab.evaluate("document.body.innerText", false)
                 .map(|v| v.value.unwrap().as_str().unwrap().to_string())
-                .unwrap_or_default()
+                .unwrap_or_default();
+            (text.clone(), text)
         },
     };
     let word_count = main_text.split_whitespace().count() as u32;
+    let reading_time_minutes = reading_time_minutes(word_count);
+    let text_stats = compute_text_stats(&main_text);
     
     // 4. Extract Schema.org/JSON-LD structured data
     let schema_org = extract_schema_org(&html);
@@ -1297,24 +3343,49 @@ pub async fn extract_website_data(url: &str) -> Result<WebsiteData> {
     
     // 5. Extract Open Graph data
     let (og_title, og_description, og_image, og_type) = extract_open_graph(&document);
+    let amp_url = extract_amp_url(&document, &final_url);
+    let forms = extract_forms(&document, &final_url);
+
+    // 5b. Publication / modification dates from all known sources
+    let (published_date, modified_date) = extract_dates(&document, &schema_org);
+    let meta_date = meta_date.or_else(|| published_date.clone());
+
+    // 5c. Author / byline from all known sources
+    let author = extract_author(&document, &schema_org);
+    let meta_author = meta_author.or_else(|| author.clone());
+
+    // 5d. Product price / currency
+    let (price, currency) = extract_price(&document, &schema_org);
     
     // 6. Extract contact information
-    let emails = extract_emails(&html);
-    let phone_numbers = extract_phone_numbers(&main_text);
+    let (emails, phone_numbers) = extract_contacts(&document, &html, &main_text, &schema_org);
     
     // 7. Extract images
-    let images = extract_images(&document, &format!("https://{}", base_domain));
+    let images = extract_images(&document, &final_url);
     
     // 8. Extract outbound links
-    let outbound_links = extract_outbound_links(&document, &base_domain);
+    let outbound_links = extract_outbound_links(&document, &final_url);
+
+    // 8a. Social profiles by platform
+    let social_links = extract_social_links(&document);
+
+    // 8b. Script / stylesheet inventory (tech-stack and tracker fingerprinting)
+    let (scripts, stylesheets) = extract_resources(&document, &final_url);
     
     // 9. ML Sentiment Analysis
     let sentiment = crate::ml::analyze_sentiment(&main_text);
+    let entities = if options.extract_entities {
+        let entities = crate::ml::extract_entity_mentions(&main_text, env_parse("MAX_ENTITIES", 100usize));
+        println!("🏷️ Found {} entity mentions", entities.len());
+        entities
+    } else {
+        Vec::new()
+    };
     if let Some(ref s) = sentiment {
         println!("🧠 Sentiment Analysis Result: {}", s);
     }
 
-    Ok(WebsiteData {
+    let mut data = WebsiteData {
         url: actual_url,
         final_url,
         title,
@@ -1322,22 +3393,119 @@ pub async fn extract_website_data(url: &str) -> Result<WebsiteData> {
         meta_keywords,
         meta_author,
         meta_date,
+        published_date,
+        modified_date,
+        author,
         main_text,
+        main_markdown,
         html: html.clone(),
         word_count,
+        reading_time_minutes,
+        text_stats,
         html_size,
+        declared_charset,
+        detected_charset,
+        charset_mismatch,
+        redirect_chain,
+        price,
+        currency,
         schema_org,
         og_title,
         og_description,
         og_image,
         og_type,
+        amp_url,
+        forms,
         emails,
         phone_numbers,
         images,
         outbound_links,
+        social_links,
+        scripts,
+        stylesheets,
         sentiment,
+        entities,
+        page_type: None,
+        quality_score: 0,
         marketing_data,
-    })
+        thumbnail,
+        screenshot,
+        screenshot_key: None,
+        html_key: None,
+    };
+
+    // 11. Page type classification (needs the assembled data)
+    let page_type = classify_page_type(&data);
+    println!("🗂️ Page type: {}", page_type.as_str());
+    data.page_type = Some(page_type.as_str().to_string());
+    data.quality_score = compute_quality_score(&data);
+
+    Ok(data)
+}
+
+/// Google cache URL for a page (`cache:` operator)
+pub fn google_cache_url(url: &str) -> String {
+    reqwest::Url::parse_with_params(
+        "https://webcache.googleusercontent.com/search",
+        &[("q", format!("cache:{}", url))],
+    )
+    .map(|u| u.to_string())
+    .unwrap_or_else(|_| format!("https://webcache.googleusercontent.com/search?q=cache:{}", url))
+}
+
+/// Deep extraction with an opt-in fallback to Google's cached copy when the
+/// live site can't be extracted (GOOGLE_CACHE_FALLBACK=true). The returned
+/// `url` is the original target; `final_url` shows the cache URL used.
+pub async fn extract_website_data_with_fallback(url: &str, options: &ExtractOptions) -> Result<WebsiteData> {
+    if options.respect_robots {
+        robots::check_allowed(&decode_search_url(url), options.session_key.as_deref()).await?;
+    }
+    match extract_with_ua_retry(url, options).await {
+        Ok(data) => Ok(data),
+        Err(e) if crate::config::env_flag("GOOGLE_CACHE_FALLBACK", false) => {
+            let actual_url = decode_search_url(url);
+            let cache_url = google_cache_url(&actual_url);
+            println!("⚠️ Direct extraction failed ({}), trying Google cache: {}", e, cache_url);
+            let mut data = extract_website_data(&cache_url, options).await?;
+            data.url = actual_url;
+            Ok(data)
+        }
+        Err(e) => Err(e),
+    }
+}
+
+/// Extract, and if the page comes back thin (< `min_word_count` words) retry
+/// once with a UA from a different browser family, keeping the richer result.
+/// Recovers content from sites that cloak by User-Agent.
+pub async fn extract_with_ua_retry(url: &str, options: &ExtractOptions) -> Result<WebsiteData> {
+    if options.min_word_count == 0 {
+        return extract_website_data(url, options).await;
+    }
+
+    let first_ua = options.user_agent.clone().unwrap_or_else(|| random_profile().user_agent.to_string());
+    let first = extract_website_data(url, &ExtractOptions { user_agent: Some(first_ua.clone()), ..options.clone() }).await?;
+    if first.word_count >= options.min_word_count {
+        return Ok(first);
+    }
+
+    let Some(alternate) = alternate_user_agent(&first_ua) else {
+        return Ok(first);
+    };
+    println!(
+        "🔁 Thin content ({} words < {}) with {} UA, retrying as {}",
+        first.word_count, options.min_word_count, ua_family(&first_ua), ua_family(alternate)
+    );
+    match extract_website_data(url, &ExtractOptions { user_agent: Some(alternate.to_string()), ..options.clone() }).await {
+        Ok(retry) if retry.word_count > first.word_count => {
+            println!("✅ Alternate UA recovered {} words", retry.word_count);
+            Ok(retry)
+        }
+        Ok(_) => Ok(first),
+        Err(e) => {
+            println!("⚠️ Alternate UA retry failed: {}", e);
+            Ok(first)
+        }
+    }
 }
 
 /// Extract Marketing Data (Selling Points)
@@ -1470,30 +3638,65 @@ fn base64_decode(input: &str) -> Result<Vec<u8>> {
 // ============================================================================
 // Generic Forum Crawler
 // ============================================================================
-pub async fn generic_crawl(url: &str, selectors: Option<std::collections::HashMap<String, String>>) -> Result<SerpData> {
-    println!("🌐 Starting Generic Crawl for: {}", url);
-    use rand::seq::SliceRandom;
-    
-    // Minimal browser setup for brevity (reusing user agent list from top of file)
-    let user_agent = USER_AGENTS.choose(&mut rand::thread_rng())
-        .unwrap_or(&"Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/123.0.0.0 Safari/537.36");
+/// Wait until the DOM stops mutating for SPA_SETTLE_MS (default 800ms),
+/// giving up after SPA_SETTLE_TIMEOUT_MS (default 10s). Client-side route
+/// changes don't trigger navigations, so this replaces `wait_until_navigated`.
+pub fn wait_for_dom_settle(tab: &std::sync::Arc<headless_chrome::Tab>) -> Result<()> {
+    let quiet_ms: u64 = env_parse("SPA_SETTLE_MS", 800);
+    let timeout_ms: u64 = env_parse("SPA_SETTLE_TIMEOUT_MS", 10_000);
+    let script = format!(r#"
+        new Promise(resolve => {{
+            let timer = setTimeout(done, {quiet});
+            const observer = new MutationObserver(() => {{
+                clearTimeout(timer);
+                timer = setTimeout(done, {quiet});
+            }});
+            const deadline = setTimeout(done, {timeout});
+            function done() {{
+                observer.disconnect();
+                clearTimeout(timer);
+                clearTimeout(deadline);
+                resolve(true);
+            }}
+            observer.observe(document.documentElement, {{ childList: true, subtree: true, attributes: true, characterData: true }});
+        }})
+    "#, quiet = quiet_ms, timeout = timeout_ms);
+    tab.evaluate(&script, true)?;
+    Ok(())
+}
 
-    let args = vec![
-        std::ffi::OsStr::new("--disable-blink-features=AutomationControlled"),
-        std::ffi::OsStr::new("--no-sandbox"),
-        std::ffi::OsStr::new("--disable-dev-shm-usage"),
-        std::ffi::OsStr::new("--headless"),
-        std::ffi::OsStr::new("--ignore-certificate-errors"),
-    ];
+/// Click through an SPA to reach the target view: each selector is awaited
+/// (SPA_CLICK_TIMEOUT_SECS, default 10), clicked, then the DOM is allowed to settle.
+pub fn run_click_sequence(tab: &std::sync::Arc<headless_chrome::Tab>, click_sequence: &[String]) -> Result<()> {
+    let timeout = Duration::from_secs(env_parse("SPA_CLICK_TIMEOUT_SECS", TIMEOUTS.element_wait.as_secs()));
+    for (i, selector) in click_sequence.iter().enumerate() {
+        println!("🖱️ SPA step {}/{}: clicking '{}'", i + 1, click_sequence.len(), selector);
+        tab.wait_for_element_with_custom_timeout(selector, timeout)
+            .and_then(|el| el.click().map(|_| ()))
+            .map_err(|e| anyhow::anyhow!("SPA click step {} ('{}') failed: {}", i + 1, selector, e))?;
+        wait_for_dom_settle(tab)?;
+    }
+    Ok(())
+}
 
-    let browser = Browser::new(LaunchOptions {
-        headless: true, 
-        args,
-        window_size: Some((1920, 1080)),
-        ..Default::default()
-    })?;
+/// Reserved `selectors` key: generic_crawl waits for this CSS selector
+/// (GENERIC_WAIT_FOR_TIMEOUT_SECS, default 15) before extracting. Not extracted itself.
+pub const WAIT_FOR_KEY: &str = "__wait_for";
+
+pub async fn generic_crawl(
+    url: &str,
+    selectors: Option<std::collections::HashMap<String, String>>,
+    click_sequence: &[String],
+    stealth: Option<&crate::stealth::StealthConfig>,
+    session_key: Option<&str>,
+) -> Result<SerpData> {
+    println!("🌐 Starting Generic Crawl for: {}", url);
+    let user_agent = random_profile().user_agent;
 
-    let tab = browser.new_tab()?;
+    // Same stealth launch as the search engines, through the proxy pool
+    let current_proxy = select_proxy(None, session_key).await?;
+    let session = StealthBrowser::launch(current_proxy.as_deref(), user_agent, &stealth_for(user_agent, stealth))?;
+    let tab = session.tab.clone();
     
     // Inject cookies if domain match found in cookies.json
     // Simple domain extraction for key lookup (e.g. "facebook.com")
@@ -1516,6 +3719,12 @@ pub async fn generic_crawl(url: &str, selectors: Option<std::collections::HashMa
     
     // Safety: Sleep before interaction
     safe_sleep().await;
+
+    // SPA: reach the target view via in-app navigation
+    if !click_sequence.is_empty() {
+        wait_for_dom_settle(&tab)?;
+        run_click_sequence(&tab, click_sequence)?;
+    }
     
     // Special handling for Facebook
     if url.contains("facebook.com") {
@@ -1529,7 +3738,14 @@ pub async fn generic_crawl(url: &str, selectors: Option<std::collections::HashMa
         safe_sleep().await;
     }
 
-    // Capture verification screenshot (Critical for User Assurance)
+    // Wait for the targeted content to render (selectors["__wait_for"])
+    if let Some(wait_selector) = selectors.as_ref().and_then(|m| m.get(WAIT_FOR_KEY)) {
+        let timeout = Duration::from_secs(env_parse("GENERIC_WAIT_FOR_TIMEOUT_SECS", 15));
+        match tab.wait_for_element_with_custom_timeout(wait_selector, timeout) {
+            Ok(_) => println!("✅ Wait-for selector '{}' rendered", wait_selector),
+            Err(e) => println!("⚠️ Wait-for selector '{}' not found after {:?}, extracting anyway: {}", wait_selector, timeout, e),
+        }
+    }
 
     // Capture verification screenshot (Critical for User Assurance)
     println!("📸 Capturing Generic Verification Screenshot...");
@@ -1548,7 +3764,7 @@ pub async fn generic_crawl(url: &str, selectors: Option<std::collections::HashMa
     let mut snippet_acc = String::new();
 
     if let Some(sel_map) = selectors {
-        for (key, selector_str) in sel_map {
+        for (key, selector_str) in sel_map.into_iter().filter(|(k, _)| k != WAIT_FOR_KEY) {
              if let Ok(selector) = Selector::parse(&selector_str) {
                  snippet_acc.push_str(&format!("--- {} ---\n", key));
                  for element in document.select(&selector) {
@@ -1575,14 +3791,546 @@ pub async fn generic_crawl(url: &str, selectors: Option<std::collections::HashMa
     Ok(SerpData {
         results,
         total_results: Some("1".to_string()),
+        total_results_count: Some(1),
         ..Default::default()
     })
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_relative_image_and_link_resolution() {
+        let doc = Html::parse_document(r#"<html><body>
+            <img src="//cdn.other.com/img/hero.png">
+            <img src="/img/logo-large.png">
+            <img src="photos/team-2024.jpg">
+            <img src="data:image/png;base64,AAAAAAAAAA">
+            <a href="//partner.net/offer">protocol-relative</a>
+            <a href="../other/page">path-relative</a>
+            <a href="https://blog.example.com/post">subdomain</a>
+            <a href="javascript:void(0)">js</a>
+        </body></html>"#);
+        let srcs: Vec<_> = extract_images(&doc, "https://www.example.com/blog/post.html").into_iter().map(|i| i.src).collect();
+        assert_eq!(srcs, vec![
+            "https://cdn.other.com/img/hero.png",
+            "https://www.example.com/img/logo-large.png",
+            "https://www.example.com/blog/photos/team-2024.jpg",
+        ]);
+
+        let links: Vec<_> = extract_outbound_links(&doc, "https://www.example.com/blog/post.html").into_iter().map(|l| l.url).collect();
+        assert_eq!(links, vec!["https://partner.net/offer"]);
+
+        let based = Html::parse_document(r#"<html><head><base href="https://static.example.org/assets/"></head>
+            <body><img src="banner-wide.png"></body></html>"#);
+        assert_eq!(extract_images(&based, "https://example.com/")[0].src, "https://static.example.org/assets/banner-wide.png");
+    }
+
+    #[test]
+    fn test_quality_score_bounds_and_ordering() {
+        let weights = QualityWeights::default();
+        assert_eq!(quality_score_with(&WebsiteData::default(), &weights), 0);
+
+        let rich = WebsiteData {
+            title: "Guide".to_string(),
+            meta_description: Some("A guide".to_string()),
+            og_title: Some("Guide".to_string()),
+            og_image: Some("https://example.com/a.png".to_string()),
+            author: Some("Jane".to_string()),
+            published_date: Some("2024-01-01".to_string()),
+            schema_org: vec![serde_json::json!({"@type": "Article"})],
+            images: vec![ImageData { src: "https://example.com/a.png".to_string(), alt: None, title: None }; 6],
+            word_count: 1200,
+            main_text: "x".repeat(3000),
+            html_size: 10_000,
+            ..Default::default()
+        };
+        assert_eq!(quality_score_with(&rich, &weights), 100);
+
+        let thin = WebsiteData { title: "Guide".to_string(), word_count: 200, main_text: "x".repeat(300), html_size: 50_000, ..Default::default() };
+        let thin_score = quality_score_with(&thin, &weights);
+        assert!(thin_score > 0 && thin_score < 30, "thin page scored {}", thin_score);
+
+        let words_only = QualityWeights { schema: 0.0, meta: 0.0, images: 0.0, text_ratio: 0.0, ..QualityWeights::default() };
+        assert_eq!(quality_score_with(&thin, &words_only), 25);
+    }
+
+    #[test]
+    fn test_extract_outbound_links_rel_and_anchor() {
+        let doc = Html::parse_document(r#"<html><body>
+            <a href="https://partner.com/deal" rel="Sponsored noopener">Great <b>deal</b></a>
+            <a href="https://forum.net/u/1" rel="ugc nofollow">  profile </a>
+            <a href="https://partner.com/deal">duplicate</a>
+            <a href="https://example.com/about">internal</a>
+            <a href="/relative">relative</a>
+        </body></html>"#);
+        let links = extract_outbound_links(&doc, "https://example.com/");
+        assert_eq!(links.len(), 2);
+        assert_eq!(links[0], OutboundLink {
+            url: "https://partner.com/deal".to_string(),
+            rel: vec!["sponsored".to_string(), "noopener".to_string()],
+            anchor_text: "Great deal".to_string(),
+        });
+        assert_eq!(links[1].rel, vec!["ugc", "nofollow"]);
+        assert_eq!(links[1].anchor_text, "profile");
+    }
+
+    #[test]
+    fn test_parse_price_formats() {
+        assert_eq!(parse_price("$1,299.99"), Some((1299.99, Some("USD".to_string()))));
+        assert_eq!(parse_price("1.299,99 €"), Some((1299.99, Some("EUR".to_string()))));
+        assert_eq!(parse_price("EUR 20"), Some((20.0, Some("EUR".to_string()))));
+        assert_eq!(parse_price("¥ 12,000"), Some((12000.0, Some("JPY".to_string()))));
+        assert_eq!(parse_price("49"), Some((49.0, None)));
+        assert_eq!(parse_price("Call for price"), None);
+    }
+
+    #[test]
+    fn test_extract_price_sources() {
+        let json_ld = Html::parse_document(r#"<html><head><script type="application/ld+json">
+            {"@type":"Product","name":"Widget","offers":{"@type":"Offer","price":19.5,"priceCurrency":"usd"}}
+            </script></head><body><span class="price">£99</span></body></html>"#);
+        let schema = extract_schema_org(&json_ld.html());
+        assert_eq!(extract_price(&json_ld, &schema), (Some(19.5), Some("USD".to_string())));
+
+        let microdata = Html::parse_document(r#"<html><body><div itemscope itemtype="https://schema.org/Product">
+            <meta itemprop="priceCurrency" content="EUR"><span itemprop="price" content="1234.50">1.234,50 €</span>
+            </div></body></html>"#);
+        assert_eq!(extract_price(&microdata, &[]), (Some(1234.5), Some("EUR".to_string())));
+
+        let markup = Html::parse_document(r#"<html><body><p class="price">Now £24.99</p></body></html>"#);
+        assert_eq!(extract_price(&markup, &[]), (Some(24.99), Some("GBP".to_string())));
+    }
+
+    #[test]
+    fn test_alternate_user_agent_switches_family() {
+        let chrome = "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/123.0.0.0 Safari/537.36";
+        let firefox = "Mozilla/5.0 (Windows NT 10.0; Win64; x64; rv:124.0) Gecko/20100101 Firefox/124.0";
+        assert_eq!(ua_family(chrome), "chrome");
+        assert_eq!(ua_family(firefox), "firefox");
+        assert_eq!(ua_family("Mozilla/5.0 (Macintosh) AppleWebKit/605.1.15 (KHTML, like Gecko) Version/17.4 Safari/605.1.15"), "safari");
+
+        assert_eq!(ua_family(alternate_user_agent(chrome).unwrap()), "firefox");
+        assert_eq!(ua_family(alternate_user_agent(firefox).unwrap()), "chrome");
+    }
+
+    #[test]
+    fn test_user_agent_profiles_match_platform() {
+        for profile in USER_AGENTS.iter().chain(MOBILE_USER_AGENTS.iter()) {
+            assert!(
+                profile.user_agent.contains(profile.platform.ua_token()),
+                "{:?} profile doesn't match UA {}",
+                profile.platform,
+                profile.user_agent
+            );
+        }
+
+        let safari = USER_AGENTS.iter().find(|p| ua_family(p.user_agent) == "safari").unwrap();
+        let stealth = stealth_for(safari.user_agent, None);
+        assert_eq!(stealth.platform.as_deref(), Some("MacIntel"));
+        assert!(stealth.webgl_vendor.starts_with("Apple"));
+
+        let requested = crate::stealth::StealthConfig { hardware_concurrency: Some(2), ..Default::default() };
+        assert_eq!(stealth_for(safari.user_agent, Some(&requested)), requested);
+        assert_eq!(stealth_for("curl/8.0", None), crate::stealth::StealthConfig::default());
+    }
+
+    #[test]
+    fn test_charset_reporting() {
+        assert_eq!(charset_from_content_type("text/html; charset=\"ISO-8859-1\""), Some("iso-8859-1".to_string()));
+        assert_eq!(charset_from_content_type("text/html"), None);
+
+        let meta = Html::parse_document(r#"<html><head><meta charset="UTF-8"></head></html>"#);
+        assert_eq!(extract_meta_charset(&meta), Some("utf-8".to_string()));
+        let http_equiv = Html::parse_document(r#"<html><head><meta http-equiv="Content-Type" content="text/html; charset=Shift_JIS"></head></html>"#);
+        assert_eq!(extract_meta_charset(&http_equiv), Some("shift_jis".to_string()));
+
+        assert!(!charset_mismatch(Some("UTF8"), Some("utf-8")));
+        assert!(!charset_mismatch(Some("iso-8859-1"), Some("windows-1252")));
+        assert!(charset_mismatch(Some("utf-8"), Some("windows-1252")));
+        assert!(!charset_mismatch(None, Some("utf-8")));
+    }
+
+    #[test]
+    fn test_detect_charset_from_bytes() {
+        let utf8 = "café".as_bytes();
+        let latin1 = b"caf\xe9";
+        assert_eq!(detect_charset(b"plain ascii", Some("utf-8")), None);
+        assert_eq!(detect_charset(utf8, Some("iso-8859-1")), Some("utf-8".to_string()));
+        // Cut off inside "é" by the sniff limit
+        assert_eq!(detect_charset(&utf8[..4], None), Some("utf-8".to_string()));
+        assert_eq!(detect_charset(latin1, Some("UTF-8")), Some("windows-1252".to_string()));
+        assert_eq!(detect_charset(latin1, Some("Shift_JIS")), Some("shift_jis".to_string()));
 
+        // UTF-8 page served as latin1, and latin1 bytes served as UTF-8, both flag
+        assert!(charset_mismatch(Some("iso-8859-1"), detect_charset(utf8, Some("iso-8859-1")).as_deref()));
+        assert!(charset_mismatch(Some("utf-8"), detect_charset(latin1, Some("utf-8")).as_deref()));
+        assert!(!charset_mismatch(Some("utf-8"), detect_charset(utf8, Some("utf-8")).as_deref()));
+    }
+
+    #[test]
+    fn test_local_file_path_is_gated() {
+        assert!(local_file_path("file:///tmp/page.html", false).is_err());
+        assert_eq!(
+            local_file_path("file:///tmp/page.html", true).unwrap(),
+            Some(std::path::PathBuf::from("/tmp/page.html"))
+        );
+        assert_eq!(local_file_path("https://example.com/", false).unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_extract_content_from_file_fixture() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("article.html");
+        std::fs::write(&path, r#"<html><head><title>Fixture</title>
+            <meta name="description" content="Saved page">
+            <meta name="author" content="Jane Doe">
+            <meta property="article:published_time" content="2024-05-01T10:00:00Z">
+            </head><body><article><p>Offline extraction works against captured pages without any network access at all.</p></article></body></html>"#).unwrap();
+        let url = reqwest::Url::from_file_path(&path).unwrap().to_string();
+
+        let content = extract_content(&url, true).await.unwrap();
+        assert_eq!(content.meta_description.as_deref(), Some("Saved page"));
+        assert_eq!(content.meta_author.as_deref(), Some("Jane Doe"));
+        assert_eq!(content.meta_date.as_deref(), Some("2024-05-01T10:00:00Z"));
+        assert!(content.html.contains("Offline extraction"));
+    }
+
+    #[test]
+    fn test_text_stats_and_reading_time() {
+        assert_eq!(count_syllables("cat"), 1);
+        assert_eq!(count_syllables("reading"), 2);
+        assert_eq!(count_syllables("make"), 1);
+        assert_eq!(count_syllables("table"), 2);
+
+        let stats = compute_text_stats("The cat sat on the mat. It was happy! Was it?");
+        assert_eq!(stats.sentence_count, 3);
+        assert_eq!(stats.avg_sentence_length, 3.67);
+        assert!(stats.flesch_reading_ease.unwrap() > 90.0);
+
+        assert_eq!(compute_text_stats("   "), TextStats::default());
+
+        assert_eq!(reading_time_minutes(0), 0);
+        assert_eq!(reading_time_minutes(1), 1);
+        assert_eq!(reading_time_minutes(460), 2);
+        assert_eq!(reading_time_minutes(461), 3);
+    }
+
+    #[test]
+    fn test_failure_reason_survives_context() {
+        let err = SearchFailed::new(FailureReason::ChallengeDetected, "Bing Challenge Detected");
+        assert_eq!(failure_reason(&err), Some(FailureReason::ChallengeDetected));
+        assert_eq!(err.to_string(), "Bing Challenge Detected (challenge_detected)");
+        assert_eq!(failure_reason(&err.context("job t1")), Some(FailureReason::ChallengeDetected));
+        assert_eq!(failure_reason(&anyhow::anyhow!("timeout")), None);
+        assert!(is_serp_too_small("<html></html>"));
+    }
+
+    #[test]
+    fn test_rate_limit_backoff_doubles_and_caps() {
+        assert!(is_rate_limit_status(429));
+        assert!(is_rate_limit_status(503));
+        assert!(!is_rate_limit_status(200));
+        assert!(!is_rate_limit_status(0));
+
+        assert_eq!(rate_limit_backoff(1), Duration::from_secs(30));
+        assert_eq!(rate_limit_backoff(2), Duration::from_secs(60));
+        assert_eq!(rate_limit_backoff(4), Duration::from_secs(240));
+        assert_eq!(rate_limit_backoff(9), Duration::from_secs(300));
+    }
+
+    #[test]
+    fn test_extract_contacts_dedupes_across_sources() {
+        let html = r#"<html><body>
+            <a href="mailto:Sales@Example.com?subject=Hi">Email us</a>
+            <a href="tel:+1-555-123-4567">Call</a>
+            <div class="vcard"><span class="tel">+1 (555) 123 4567</span><span class="email">info@example.com</span></div>
+            <script type="application/ld+json">
+            {"@type":"Organization","contactPoint":{"@type":"ContactPoint","telephone":"+44 20 7946 0958","email":"support@example.com"}}
+            </script>
+            <p>Write to sales@example.com</p>
+            </body></html>"#;
+        let document = Html::parse_document(html);
+        let (emails, phones) = extract_contacts(&document, html, "", &extract_schema_org(html));
+
+        assert_eq!(emails[0], "Sales@Example.com");
+        assert!(emails.contains(&"support@example.com".to_string()));
+        assert!(emails.contains(&"info@example.com".to_string()));
+        assert_eq!(emails.iter().filter(|e| e.eq_ignore_ascii_case("sales@example.com")).count(), 1);
+
+        assert_eq!(phones, vec!["+1-555-123-4567".to_string(), "+44 20 7946 0958".to_string()]);
+    }
+
+    #[test]
+    fn test_extract_bing_paa() {
+        let html = r#"<html><body><div class="b_ans"><div id="relatedQnAListDisplay">
+            <div class="df_qntext"><span>What is   Rust used for?</span></div>
+            <div class="df_qntext">Is Rust hard to learn?</div>
+            <div class="df_qntext">what is rust used for?</div>
+            </div></div></body></html>"#;
+        assert_eq!(
+            extract_bing_paa(&Html::parse_document(html)),
+            vec!["What is Rust used for?".to_string(), "Is Rust hard to learn?".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_merge_paginated_bing_results() {
+        let page = |links: &[&str]| {
+            let items: String = links
+                .iter()
+                .map(|l| format!(r#"<li class="b_algo"><h2><a href="{}">T</a></h2><div class="b_caption"><p>S</p></div></li>"#, l))
+                .collect();
+            parse_bing_results(&Html::parse_document(&format!(r#"<ol id="b_results">{}</ol>"#, items)))
+        };
+        let mut results = page(&["https://a.com/", "https://b.com/"]);
+        assert_eq!(results.len(), 2);
+
+        // Page 2 repeats b.com at the boundary
+        assert_eq!(merge_results(&mut results, page(&["https://b.com/", "https://c.com/"])), 1);
+        let links: Vec<&str> = results.iter().map(|r| r.link.as_str()).collect();
+        assert_eq!(links, vec!["https://a.com/", "https://b.com/", "https://c.com/"]);
+    }
+
+    #[test]
+    fn test_extract_social_links() {
+        let html = r#"<html><body>
+            <a href="https://twitter.com/intent/tweet?text=hi">Share</a>
+            <a href="https://x.com/acme">X</a>
+            <a href="https://twitter.com/acme_old">Old</a>
+            <a href="https://www.linkedin.com/company/acme/">LinkedIn</a>
+            <a href="https://www.facebook.com/sharer/sharer.php?u=x">Share</a>
+            <a href="https://github.com/acme">GitHub</a>
+            <a href="https://example.com/about">About</a>
+            </body></html>"#;
+        let social = extract_social_links(&Html::parse_document(html));
+        assert_eq!(social.get("twitter").map(String::as_str), Some("https://x.com/acme"));
+        assert_eq!(social.get("linkedin").map(String::as_str), Some("https://www.linkedin.com/company/acme/"));
+        assert_eq!(social.get("github").map(String::as_str), Some("https://github.com/acme"));
+        assert!(!social.contains_key("facebook"));
+        assert_eq!(social.len(), 3);
+
+        assert_eq!(social_platform("https://github.com/acme/shared-lib"), Some("github"));
+        assert_eq!(social_platform("https://linkedin.com/company/intentional"), Some("linkedin"));
+        assert_eq!(social_platform("https://twitter.com/home?status=hello"), None);
+        assert_eq!(social_platform("https://www.linkedin.com/shareArticle?url=x"), None);
+    }
+
+    #[test]
+    fn test_chrome_extra_args_parsing() {
+        let args = split_chrome_args(r#"--enable-ipv6  --host-resolver-rules="MAP * 1.2.3.4" --dns-prefetch-disable"#);
+        assert_eq!(args, vec!["--enable-ipv6", "--host-resolver-rules=MAP * 1.2.3.4", "--dns-prefetch-disable"]);
+
+        assert!(is_valid_chrome_flag("--enable-ipv6"));
+        assert!(is_valid_chrome_flag("--host-resolver-rules=MAP * 1.2.3.4"));
+        assert!(!is_valid_chrome_flag("-x"));
+        assert!(!is_valid_chrome_flag("enable-ipv6"));
+        assert!(!is_valid_chrome_flag("--=value"));
+    }
+
+    #[test]
+    fn test_extract_forms() {
+        let html = r#"<html><body>
+            <form action="/subscribe" method="post">
+                <input type="email" name="email">
+                <input type="hidden" name="list_id" value="7">
+                <select name="country"></select>
+                <textarea id="message"></textarea>
+                <input type="submit" value="Go">
+            </form>
+            <form><input name="q"></form>
+            </body></html>"#;
+        let forms = extract_forms(&Html::parse_document(html), "https://example.com/blog/");
+        assert_eq!(forms.len(), 2);
+
+        assert_eq!(forms[0].action.as_deref(), Some("https://example.com/subscribe"));
+        assert_eq!(forms[0].method, "POST");
+        let fields: Vec<(&str, &str)> = forms[0].fields.iter().map(|f| (f.name.as_str(), f.field_type.as_str())).collect();
+        assert_eq!(fields, vec![("email", "email"), ("list_id", "hidden"), ("country", "select"), ("message", "textarea")]);
+
+        assert_eq!(forms[1].action, None);
+        assert_eq!(forms[1].method, "GET");
+        assert_eq!(forms[1].fields[0].field_type, "text");
+    }
+
+    #[test]
+    fn test_extract_amp_url() {
+        let html = r#"<html><head><link rel="amphtml" href="/amp/story-1"></head></html>"#;
+        let document = Html::parse_document(html);
+        assert_eq!(extract_amp_url(&document, "https://news.example.com/story-1").as_deref(), Some("https://news.example.com/amp/story-1"));
+        assert_eq!(extract_amp_url(&Html::parse_document("<html></html>"), "https://example.com/"), None);
+        let based = Html::parse_document(r#"<html><head><base href="https://cdn.example.com/m/"><link rel="amphtml" href="amp/1"></head></html>"#);
+        assert_eq!(extract_amp_url(&based, "https://example.com/a").as_deref(), Some("https://cdn.example.com/m/amp/1"));
+    }
+
+    #[test]
+    fn test_google_cache_url() {
+        assert_eq!(
+            google_cache_url("https://example.com/a?b=1"),
+            "https://webcache.googleusercontent.com/search?q=cache%3Ahttps%3A%2F%2Fexample.com%2Fa%3Fb%3D1"
+        );
+    }
+
+    #[test]
+    fn test_extract_resources() {
+        let html = r#"<html><head>
+            <script src="/static/app.js"></script>
+            <script src="https://www.googletagmanager.com/gtag/js?id=G-1"></script>
+            <script src="/static/app.js"></script>
+            <script>inline()</script>
+            <link rel="stylesheet" href="css/site.css">
+            <link rel="preload stylesheet" href="//cdn.example.net/x.css">
+            <link rel="icon" href="/favicon.ico">
+            </head></html>"#;
+        let document = Html::parse_document(html);
+        let (scripts, styles) = extract_resources(&document, "https://example.com/blog/post");
+        assert_eq!(scripts, vec![
+            "https://example.com/static/app.js".to_string(),
+            "https://www.googletagmanager.com/gtag/js?id=G-1".to_string(),
+        ]);
+        assert_eq!(styles, vec![
+            "https://example.com/blog/css/site.css".to_string(),
+            "https://cdn.example.net/x.css".to_string(),
+        ]);
+
+        let based = Html::parse_document(r#"<html><head><base href="/v2/"><script src="app.js"></script>
+            <script src="data:text/javascript,1"></script><link rel="stylesheet" href="s.css"></head>
+            <body><form action="send"></form></body></html>"#);
+        let (scripts, styles) = extract_resources(&based, "https://example.com/blog/post");
+        assert_eq!(scripts, vec!["https://example.com/v2/app.js".to_string()]);
+        assert_eq!(styles, vec!["https://example.com/v2/s.css".to_string()]);
+        assert_eq!(extract_forms(&based, "https://example.com/blog/post")[0].action.as_deref(), Some("https://example.com/v2/send"));
+    }
+
+    #[test]
+    fn test_parse_result_count() {
+        assert_eq!(parse_result_count("About 1,230,000 results (0.45 seconds)"), Some(1_230_000));
+        assert_eq!(parse_result_count("1,230,000 Results"), Some(1_230_000));
+        assert_eq!(parse_result_count("Ungefähr 1.230.000 Ergebnisse"), Some(1_230_000));
+        assert_eq!(parse_result_count("Environ 1\u{202f}230\u{202f}000 résultats"), Some(1_230_000));
+        assert_eq!(parse_result_count("1-10 of 52,400 results"), Some(52_400));
+        assert_eq!(parse_result_count("Page 2 of about 5,000 results"), Some(5_000));
+        assert_eq!(parse_result_count("no results"), None);
+    }
+
+    #[test]
+    fn test_engine_options_whitelist() {
+        let mut opts = std::collections::HashMap::new();
+        opts.insert("tbs".to_string(), "qdr:w".to_string());
+        assert!(validate_engine_options("google", &opts).is_ok());
+        assert!(validate_engine_options("bing", &opts).is_err());
 
+        opts.insert("freshness".to_string(), "Week".to_string());
+        assert!(validate_engine_options("google", &opts).is_err());
 
+        // Engines other than google/generic run on Bing, so they take Bing's options
+        let mut bing = std::collections::HashMap::new();
+        bing.insert("freshness".to_string(), "Week".to_string());
+        bing.insert("mkt".to_string(), "en-US".to_string());
+        assert!(validate_engine_options("bing", &bing).is_ok());
+        assert!(validate_engine_options("duckduckgo", &bing).is_ok());
+        assert!(validate_engine_options("generic", &bing).is_err());
+    }
+
+    #[test]
+    fn test_engine_option_values() {
+        let check = |engine: &str, key: &str, value: &str| {
+            let opts = std::collections::HashMap::from([(key.to_string(), value.to_string())]);
+            validate_engine_options(engine, &opts).is_ok()
+        };
+        assert!(check("google", "tbs", "qdr:w"));
+        assert!(check("google", "tbs", "cdr:1,cd_min:1/1/2024,cd_max:2/1/2024"));
+        assert!(!check("google", "tbs", "qdr:w\"><script>"));
+        assert!(check("google", "lr", "lang_de|lang_fr"));
+        assert!(!check("google", "lr", "german"));
+        assert!(check("google", "cr", "countryDE"));
+        assert!(check("google", "num", "50"));
+        assert!(!check("google", "num", "500"));
+        assert!(!check("google", "tbm", "everything"));
+        assert!(check("bing", "safesearch", "Strict"));
+        assert!(!check("bing", "freshness", "Year"));
+        assert!(!check("bing", "count", "0"));
+        assert!(!check("bing", "qft", "a\nb"));
+    }
+
+    #[test]
+    fn test_apply_url_params_overrides() {
+        let mut opts = std::collections::HashMap::new();
+        opts.insert("tbs".to_string(), "qdr:w".to_string());
+        opts.insert("hl".to_string(), "de".to_string());
+        let url = apply_url_params("https://www.google.com/search?q=rust&hl=en", &opts);
+        assert_eq!(url, "https://www.google.com/search?q=rust&hl=de&tbs=qdr%3Aw");
+    }
+
+    #[test]
+    fn test_result_filter_patterns() {
+        let filter = ResultFilter::new(
+            &["*.gov".to_string(), "*.edu".to_string()],
+            &["re:/private/".to_string()],
+        )
+        .unwrap();
+        assert!(filter.matches("https://www.usa.gov/about"));
+        assert!(filter.matches("https://mit.edu/"));
+        assert!(!filter.matches("https://www.pinterest.com/pin/1"));
+        assert!(!filter.matches("https://data.gov/private/x"));
+
+        assert!(ResultFilter::new(&["re:(".to_string()], &[]).is_err());
+    }
 
+    #[test]
+    fn test_classify_page_type() {
+        let product = WebsiteData {
+            url: "https://shop.example.com/item/42".to_string(),
+            schema_org: vec![serde_json::json!({"@context": "https://schema.org", "@type": "Product"})],
+            ..Default::default()
+        };
+        assert_eq!(classify_page_type(&product), PageType::Product);
 
+        let home = WebsiteData {
+            url: "https://example.com/".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(classify_page_type(&home), PageType::Homepage);
 
+        let forum = WebsiteData {
+            final_url: "https://community.example.com/forum/thread-123".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(classify_page_type(&forum), PageType::Forum);
+    }
+
+    #[test]
+    fn test_extract_author_sources() {
+        let json_ld = r#"<html><head><script type="application/ld+json">
+            {"@type":"NewsArticle","author":[{"@type":"Person","name":"Jane Doe"},{"@type":"Person","name":"John Roe"}]}
+            </script></head><body><span class="byline">By Someone Else</span></body></html>"#;
+        let document = Html::parse_document(json_ld);
+        assert_eq!(extract_author(&document, &extract_schema_org(json_ld)).as_deref(), Some("Jane Doe, John Roe"));
+
+        let byline = r#"<html><body><div class="byline">  By   Alex Smith </div></body></html>"#;
+        let document = Html::parse_document(byline);
+        assert_eq!(extract_author(&document, &[]).as_deref(), Some("Alex Smith"));
+    }
 
+    #[test]
+    fn test_normalize_date_formats() {
+        assert_eq!(normalize_date("2024-03-05T10:20:30Z").as_deref(), Some("2024-03-05T10:20:30+00:00"));
+        assert_eq!(normalize_date("2024-03-05T10:20:30+0200").as_deref(), Some("2024-03-05T10:20:30+02:00"));
+        assert_eq!(normalize_date("2024-03-05").as_deref(), Some("2024-03-05"));
+        assert_eq!(normalize_date("March 5, 2024").as_deref(), Some("2024-03-05"));
+        assert_eq!(normalize_date("yesterday"), None);
+    }
+
+    #[test]
+    fn test_extract_dates_priority() {
+        let html = r#"<html><head>
+            <script type="application/ld+json">{"@graph":[{"@type":"Article","datePublished":"2023-01-02","dateModified":"2023-02-03T04:05:06Z"}]}</script>
+            </head><body><time datetime="2020-01-01">old</time></body></html>"#;
+        let document = Html::parse_document(html);
+        let schema = extract_schema_org(html);
+        let (published, modified) = extract_dates(&document, &schema);
+        assert_eq!(published.as_deref(), Some("2023-01-02"));
+        assert_eq!(modified.as_deref(), Some("2023-02-03T04:05:06+00:00"));
+    }
+}
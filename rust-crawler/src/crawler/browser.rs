@@ -0,0 +1,203 @@
+//! Shared Chrome launch for every crawl path.
+//!
+//! Search, deep extraction and generic crawls all start Chrome the same way:
+//! stealth switches, optional proxy (plus auth extension), the operator's
+//! [`CrawlerConfig`], and the stealth script registered before the first navigation.
+
+use anyhow::Result;
+use headless_chrome::protocol::cdp::Page::AddScriptToEvaluateOnNewDocument;
+use headless_chrome::{Browser, LaunchOptions, Tab};
+use once_cell::sync::Lazy;
+use std::ffi::OsStr;
+use std::sync::Arc;
+
+use super::{is_valid_chrome_flag, split_chrome_args};
+use crate::config::{env_flag, env_parse};
+use crate::proxy::{generate_proxy_auth_extension, Proxy, ProxyAuthExtension};
+use crate::stealth::StealthConfig;
+
+/// Switches passed to every launch, before UA / proxy / extra args.
+/// `--no-sandbox` and `--headless=new` are dropped when [`CrawlerConfig`] turns them off.
+const BASE_ARGS: &[&str] = &[
+    "--disable-blink-features=AutomationControlled",
+    "--no-sandbox",
+    "--disable-dev-shm-usage",
+    "--disable-infobars",
+    "--window-position=0,0",
+    "--ignore-certificate-errors",
+    "--ignore-certificate-errors-spki-list",
+    "--incognito",
+    "--headless=new",
+];
+
+/// Operator-level Chrome launch settings, read once from the environment
+#[derive(Debug, Clone, PartialEq)]
+pub struct CrawlerConfig {
+    /// `HEADLESS` (default true); false shows the window for local debugging
+    pub headless: bool,
+    /// `CHROME_NO_SANDBOX` (default true); some sandboxes refuse to start Chrome with it
+    pub no_sandbox: bool,
+    /// `WINDOW_WIDTH` x `WINDOW_HEIGHT` (default 1920x1080)
+    pub window_size: (u32, u32),
+    /// `EXTRA_CHROME_ARGS` (or the older `CHROME_EXTRA_ARGS`), appended to every launch.
+    /// Space-separated, e.g. `--enable-ipv6 --host-resolver-rules="MAP * 1.2.3.4"`;
+    /// malformed entries are dropped.
+    pub extra_args: Vec<String>,
+}
+
+impl Default for CrawlerConfig {
+    fn default() -> Self {
+        Self {
+            headless: true,
+            no_sandbox: true,
+            window_size: (1920, 1080),
+            extra_args: Vec::new(),
+        }
+    }
+}
+
+impl CrawlerConfig {
+    pub fn from_env() -> Self {
+        let default = Self::default();
+        let raw_args = std::env::var("EXTRA_CHROME_ARGS")
+            .or_else(|_| std::env::var("CHROME_EXTRA_ARGS"))
+            .unwrap_or_default();
+        let (extra_args, invalid): (Vec<String>, Vec<String>) = split_chrome_args(&raw_args)
+            .into_iter()
+            .partition(|a| is_valid_chrome_flag(a));
+        for arg in &invalid {
+            eprintln!("⚠️ Ignoring malformed EXTRA_CHROME_ARGS entry: {}", arg);
+        }
+        if !extra_args.is_empty() {
+            println!("🧩 Extra Chrome args: {}", extra_args.join(" "));
+        }
+        Self {
+            headless: env_flag("HEADLESS", default.headless),
+            no_sandbox: env_flag("CHROME_NO_SANDBOX", default.no_sandbox),
+            window_size: (
+                env_parse("WINDOW_WIDTH", default.window_size.0).max(1),
+                env_parse("WINDOW_HEIGHT", default.window_size.1).max(1),
+            ),
+            extra_args,
+        }
+    }
+}
+
+pub static CRAWLER_CONFIG: Lazy<CrawlerConfig> = Lazy::new(|| {
+    let config = CrawlerConfig::from_env();
+    if !config.headless {
+        println!("🖥️ HEADLESS=false: Chrome windows will be visible");
+    }
+    config
+});
+
+/// A launched browser and its first tab, stealth script already installed.
+/// Keep it alive for as long as the tab is in use: dropping it closes Chrome
+/// and removes the proxy auth extension directory.
+pub struct StealthBrowser {
+    pub browser: Browser,
+    pub tab: Arc<Tab>,
+    _auth_ext: Option<ProxyAuthExtension>,
+}
+
+impl StealthBrowser {
+    /// Launch Chrome through `proxy` (direct connection if `None`) with `user_agent`,
+    /// injecting the stealth script rendered from `stealth`
+    pub fn launch(proxy: Option<&Proxy>, user_agent: &str, stealth: &StealthConfig) -> Result<Self> {
+        let (browser, auth_ext) = launch_chrome(proxy, user_agent)?;
+        let tab = browser.new_tab()?;
+        install_stealth(&tab, stealth)?;
+        Ok(Self { browser, tab, _auth_ext: auth_ext })
+    }
+}
+
+/// Start Chrome with the stealth switches and `proxy`. The returned extension (if any)
+/// must outlive the browser, since Chrome reads it from disk.
+pub fn launch_chrome(proxy: Option<&Proxy>, user_agent: &str) -> Result<(Browser, Option<ProxyAuthExtension>)> {
+    let mut auth_ext = None;
+    let mut proxy_args = Vec::new();
+    match proxy {
+        Some(proxy) => {
+            println!(
+                "🔄 Using proxy: {} (healthy: {}, success_rate: {:.1}%)",
+                proxy.id,
+                proxy.healthy.load(std::sync::atomic::Ordering::Relaxed),
+                proxy.success_rate() * 100.0
+            );
+            proxy_args.push(format!("--proxy-server={}", proxy.to_chrome_arg()));
+            if let (Some(username), Some(password)) = (&proxy.username, &proxy.password) {
+                match generate_proxy_auth_extension(username, password) {
+                    Ok(ext) => {
+                        proxy_args.push(format!("--load-extension={}", ext.path()));
+                        auth_ext = Some(ext);
+                        println!("🔐 Proxy auth extension loaded");
+                    }
+                    Err(e) => eprintln!("⚠️ Failed to write proxy auth extension: {}", e),
+                }
+            }
+        }
+        None => println!("📡 No proxies configured. Using direct connection."),
+    }
+
+    let config = &*CRAWLER_CONFIG;
+    let args = launch_args(user_agent, &proxy_args, config);
+    let browser = Browser::new(LaunchOptions {
+        headless: false, // new headless mode is selected via --headless=new
+        window_size: Some(config.window_size),
+        args: args.iter().map(OsStr::new).collect(),
+        ..Default::default()
+    })?;
+    Ok((browser, auth_ext))
+}
+
+/// Register the stealth script on `tab` so it runs before any page script
+pub fn install_stealth(tab: &Tab, stealth: &StealthConfig) -> Result<()> {
+    tab.enable_debugger()?;
+    tab.call_method(AddScriptToEvaluateOnNewDocument {
+        source: crate::stealth::render_stealth_script(stealth),
+        world_name: None,
+        include_command_line_api: None,
+        run_immediately: None,
+    })?;
+    Ok(())
+}
+
+/// Full Chrome argument list: base switches, UA, proxy switches, then operator extras
+pub fn launch_args(user_agent: &str, proxy_args: &[String], config: &CrawlerConfig) -> Vec<String> {
+    BASE_ARGS
+        .iter()
+        .filter(|&&a| match a {
+            "--no-sandbox" => config.no_sandbox,
+            "--headless=new" => config.headless,
+            _ => true,
+        })
+        .map(|a| a.to_string())
+        .chain(std::iter::once(format!("--user-agent={}", user_agent)))
+        .chain(proxy_args.iter().cloned())
+        .chain(config.extra_args.iter().cloned())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_launch_args_order() {
+        let config = CrawlerConfig { extra_args: vec!["--enable-ipv6".to_string()], ..Default::default() };
+        let args = launch_args("UA/1.0", &["--proxy-server=http://1.2.3.4:8080".to_string()], &config);
+        assert_eq!(args[0], "--disable-blink-features=AutomationControlled");
+        assert!(args.contains(&"--headless=new".to_string()));
+        let tail: Vec<&str> = args[BASE_ARGS.len()..].iter().map(String::as_str).collect();
+        assert_eq!(tail, vec!["--user-agent=UA/1.0", "--proxy-server=http://1.2.3.4:8080", "--enable-ipv6"]);
+    }
+
+    #[test]
+    fn test_launch_args_follow_config() {
+        let config = CrawlerConfig { headless: false, no_sandbox: false, ..Default::default() };
+        let args = launch_args("UA/1.0", &[], &config);
+        assert!(!args.contains(&"--headless=new".to_string()));
+        assert!(!args.contains(&"--no-sandbox".to_string()));
+        assert_eq!(args.len(), BASE_ARGS.len() - 2 + 1);
+    }
+}
@@ -0,0 +1,281 @@
+//! HTML to Markdown for Readability's article HTML.
+//!
+//! Article bodies are a small, predictable subset of HTML (headings,
+//! paragraphs, lists, links, emphasis, code, quotes, images, simple tables),
+//! so a compact converter over `scraper`'s tree covers them; any other element
+//! is reduced to its children.
+
+use reqwest::Url;
+use scraper::{ElementRef, Html, Node};
+
+/// Convert an HTML fragment to Markdown, resolving relative links and image
+/// sources against `base` when given
+pub fn html_to_markdown(html: &str, base: Option<&Url>) -> String {
+    let fragment = Html::parse_fragment(html);
+    let mut out = String::new();
+    render_children(fragment.root_element(), &Context { base, list_depth: 0 }, &mut out);
+    normalize(&out)
+}
+
+struct Context<'a> {
+    base: Option<&'a Url>,
+    list_depth: usize,
+}
+
+impl Context<'_> {
+    fn resolve(&self, href: &str) -> String {
+        self.base
+            .and_then(|base| base.join(href).ok())
+            .map(|url| url.to_string())
+            .unwrap_or_else(|| href.to_string())
+    }
+}
+
+fn render_children(element: ElementRef, ctx: &Context, out: &mut String) {
+    for child in element.children() {
+        match child.value() {
+            Node::Text(text) => push_text(out, text),
+            Node::Element(_) => {
+                if let Some(child) = ElementRef::wrap(child) {
+                    render_element(child, ctx, out);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Inline text with whitespace runs collapsed to one space
+fn push_text(out: &mut String, text: &str) {
+    for (i, word) in text.split_whitespace().enumerate() {
+        let at_boundary = out.is_empty() || out.ends_with(char::is_whitespace);
+        if !at_boundary && (i > 0 || text.starts_with(char::is_whitespace)) {
+            out.push(' ');
+        }
+        out.push_str(word);
+    }
+    if text.ends_with(char::is_whitespace) && !text.trim().is_empty() {
+        out.push(' ');
+    }
+}
+
+/// Rendered children of `element`, trimmed, for wrapping in inline markup
+fn inline(element: ElementRef, ctx: &Context) -> String {
+    let mut inner = String::new();
+    render_children(element, ctx, &mut inner);
+    inner.trim().to_string()
+}
+
+fn block_break(out: &mut String) {
+    let trimmed = out.trim_end_matches(' ').len();
+    out.truncate(trimmed);
+    if !out.is_empty() {
+        out.push_str("\n\n");
+    }
+}
+
+fn render_element(element: ElementRef, ctx: &Context, out: &mut String) {
+    let name = element.value().name();
+    match name {
+        "script" | "style" | "noscript" | "template" | "iframe" | "svg" => {}
+        "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => {
+            let level = name[1..].parse::<usize>().unwrap_or(1);
+            let text = inline(element, ctx);
+            if !text.is_empty() {
+                block_break(out);
+                out.push_str(&"#".repeat(level));
+                out.push(' ');
+                out.push_str(&text);
+                block_break(out);
+            }
+        }
+        "p" | "div" | "section" | "article" | "header" | "footer" | "main" | "figure" | "figcaption" => {
+            block_break(out);
+            render_children(element, ctx, out);
+            block_break(out);
+        }
+        "br" => out.push_str("  \n"),
+        "hr" => {
+            block_break(out);
+            out.push_str("---");
+            block_break(out);
+        }
+        "strong" | "b" => wrap_inline(element, ctx, out, "**"),
+        "em" | "i" => wrap_inline(element, ctx, out, "*"),
+        "code" => {
+            let code: String = element.text().collect();
+            if !code.trim().is_empty() {
+                out.push('`');
+                out.push_str(code.trim());
+                out.push('`');
+            }
+        }
+        "pre" => {
+            let code: String = element.text().collect();
+            block_break(out);
+            out.push_str("```\n");
+            out.push_str(code.trim_end());
+            out.push_str("\n```");
+            block_break(out);
+        }
+        "a" => {
+            let text = inline(element, ctx);
+            match element.value().attr("href").filter(|h| !h.starts_with("javascript:")) {
+                Some(href) if !text.is_empty() => {
+                    out.push_str(&format!("[{}]({})", text, ctx.resolve(href)));
+                }
+                _ => out.push_str(&text),
+            }
+        }
+        "img" => {
+            if let Some(src) = element.value().attr("src") {
+                let alt = element.value().attr("alt").unwrap_or("").trim();
+                out.push_str(&format!("![{}]({})", alt, ctx.resolve(src)));
+            }
+        }
+        "blockquote" => {
+            let mut inner = String::new();
+            render_children(element, ctx, &mut inner);
+            let quoted: Vec<String> = normalize(&inner)
+                .lines()
+                .map(|line| if line.is_empty() { ">".to_string() } else { format!("> {}", line) })
+                .collect();
+            block_break(out);
+            out.push_str(&quoted.join("\n"));
+            block_break(out);
+        }
+        "ul" | "ol" => render_list(element, ctx, out, name == "ol"),
+        "table" => render_table(element, ctx, out),
+        _ => render_children(element, ctx, out),
+    }
+}
+
+fn wrap_inline(element: ElementRef, ctx: &Context, out: &mut String, marker: &str) {
+    let text = inline(element, ctx);
+    if !text.is_empty() {
+        out.push_str(marker);
+        out.push_str(&text);
+        out.push_str(marker);
+    }
+}
+
+fn render_list(element: ElementRef, ctx: &Context, out: &mut String, ordered: bool) {
+    let nested = Context { base: ctx.base, list_depth: ctx.list_depth + 1 };
+    let indent = "  ".repeat(ctx.list_depth);
+    let child_indent = "  ".repeat(nested.list_depth);
+    if ctx.list_depth == 0 {
+        block_break(out);
+    } else if !out.ends_with('\n') {
+        out.push('\n');
+    }
+    let items = element.children().filter_map(ElementRef::wrap).filter(|e| e.value().name() == "li");
+    for (i, item) in items.enumerate() {
+        let mut inner = String::new();
+        render_children(item, &nested, &mut inner);
+        let inner = normalize(&inner);
+        let marker = if ordered { format!("{}. ", i + 1) } else { "- ".to_string() };
+        let mut lines = inner.lines().filter(|l| !l.trim().is_empty());
+        out.push_str(&indent);
+        out.push_str(&marker);
+        out.push_str(lines.next().unwrap_or(""));
+        out.push('\n');
+        for line in lines {
+            // Nested lists are already indented for their depth
+            if !line.starts_with(&child_indent) {
+                out.push_str(&child_indent);
+            }
+            out.push_str(line);
+            out.push('\n');
+        }
+    }
+    if ctx.list_depth == 0 {
+        block_break(out);
+    }
+}
+
+fn render_table(element: ElementRef, ctx: &Context, out: &mut String) {
+    let rows: Vec<Vec<String>> = element
+        .descendants()
+        .filter_map(ElementRef::wrap)
+        .filter(|e| e.value().name() == "tr")
+        .map(|row| {
+            row.children()
+                .filter_map(ElementRef::wrap)
+                .filter(|c| matches!(c.value().name(), "td" | "th"))
+                .map(|cell| inline(cell, ctx).replace('|', "\\|").replace('\n', " "))
+                .collect()
+        })
+        .filter(|cells: &Vec<String>| !cells.is_empty())
+        .collect();
+    let Some(columns) = rows.iter().map(Vec::len).max() else {
+        return;
+    };
+
+    block_break(out);
+    for (i, row) in rows.iter().enumerate() {
+        let mut cells = row.clone();
+        cells.resize(columns, String::new());
+        out.push_str(&format!("| {} |\n", cells.join(" | ")));
+        if i == 0 {
+            out.push_str(&format!("|{}\n", " --- |".repeat(columns)));
+        }
+    }
+    block_break(out);
+}
+
+/// Trim trailing spaces (keeping Markdown hard breaks) and collapse runs of blank lines
+fn normalize(markdown: &str) -> String {
+    let mut result = String::new();
+    let mut blank_run = 0;
+    for line in markdown.lines() {
+        let line = if line.ends_with("  ") && !line.trim().is_empty() { line } else { line.trim_end() };
+        if line.trim().is_empty() {
+            blank_run += 1;
+            if blank_run > 1 {
+                continue;
+            }
+            result.push('\n');
+            continue;
+        }
+        blank_run = 0;
+        result.push_str(line);
+        result.push('\n');
+    }
+    result.trim().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_html_to_markdown() {
+        let base = Url::parse("https://example.com/blog/post").unwrap();
+        let html = r#"
+            <div><h2>Getting   started</h2>
+            <p>Read the <a href="/docs">docs</a> and <strong>install</strong> it.</p>
+            <ul><li>one</li><li>two <em>items</em></li></ul>
+            <ol><li>first</li></ol>
+            <script>alert(1)</script>
+            <pre>fn main() {}</pre></div>
+        "#;
+        assert_eq!(
+            html_to_markdown(html, Some(&base)),
+            "## Getting started\n\n\
+             Read the [docs](https://example.com/docs) and **install** it.\n\n\
+             - one\n- two *items*\n\n\
+             1. first\n\n\
+             ```\nfn main() {}\n```"
+        );
+    }
+
+    #[test]
+    fn test_table_and_quote() {
+        let html = "<table><tr><th>Name</th><th>Age</th></tr><tr><td>Ann</td><td>30</td></tr></table>\
+                    <blockquote><p>Quoted</p></blockquote>";
+        assert_eq!(
+            html_to_markdown(html, None),
+            "| Name | Age |\n| --- | --- |\n| Ann | 30 |\n\n> Quoted"
+        );
+    }
+}
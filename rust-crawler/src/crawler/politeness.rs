@@ -0,0 +1,83 @@
+//! Per-host politeness delay for deep extraction.
+//!
+//! A job's top results often share a domain, and concurrent deep extraction
+//! would otherwise hit it several times at once. Every navigation first takes
+//! the next free slot for its host, at least `PER_DOMAIN_DELAY_MS` (default
+//! 1000) after the previous one, and waits for it.
+
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use std::time::Duration;
+use tokio::sync::Mutex;
+use tokio::time::Instant;
+
+use crate::config::env_parse;
+
+/// Hosts tracked before stale entries are pruned
+const MAX_TRACKED_HOSTS: usize = 10_000;
+
+pub struct DomainRateLimiter {
+    delay: Duration,
+    /// Time of the latest request granted per host (may be in the future)
+    last_request: Mutex<HashMap<String, Instant>>,
+}
+
+impl DomainRateLimiter {
+    pub fn new(delay: Duration) -> Self {
+        Self { delay, last_request: Mutex::new(HashMap::new()) }
+    }
+
+    /// Claim the next slot for `host` as of `now`, returning how long to wait for it
+    async fn reserve(&self, host: &str, now: Instant) -> Duration {
+        let mut last_request = self.last_request.lock().await;
+        if last_request.len() >= MAX_TRACKED_HOSTS {
+            let delay = self.delay;
+            last_request.retain(|_, at| *at + delay > now);
+        }
+        let slot = match last_request.get(host) {
+            Some(&last) => (last + self.delay).max(now),
+            None => now,
+        };
+        last_request.insert(host.to_string(), slot);
+        slot - now
+    }
+
+    /// Wait until `url`'s host may be requested again. Non-HTTP URLs pass straight through.
+    pub async fn wait(&self, url: &str) {
+        if self.delay.is_zero() {
+            return;
+        }
+        let Some(host) = reqwest::Url::parse(url).ok().and_then(|u| u.host_str().map(str::to_lowercase)) else {
+            return;
+        };
+        let wait = self.reserve(&host, Instant::now()).await;
+        if !wait.is_zero() {
+            println!("🐢 Waiting {}ms before requesting {} again", wait.as_millis(), host);
+            tokio::time::sleep(wait).await;
+        }
+    }
+}
+
+/// Shared limiter (`PER_DOMAIN_DELAY_MS`, default 1000; 0 = off)
+pub static DOMAIN_RATE_LIMITER: Lazy<DomainRateLimiter> =
+    Lazy::new(|| DomainRateLimiter::new(Duration::from_millis(env_parse("PER_DOMAIN_DELAY_MS", 1000))));
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_reserve_spaces_requests_per_host() {
+        let limiter = DomainRateLimiter::new(Duration::from_millis(1000));
+        let now = Instant::now();
+        assert_eq!(limiter.reserve("example.com", now).await, Duration::ZERO);
+        // Back-to-back requests queue up one delay apart
+        assert_eq!(limiter.reserve("example.com", now).await, Duration::from_millis(1000));
+        assert_eq!(limiter.reserve("example.com", now).await, Duration::from_millis(2000));
+        // Other hosts are independent
+        assert_eq!(limiter.reserve("other.org", now).await, Duration::ZERO);
+        // Once the delay has passed there is nothing to wait for
+        let later = now + Duration::from_secs(5);
+        assert_eq!(limiter.reserve("example.com", later).await, Duration::ZERO);
+    }
+}
@@ -0,0 +1,514 @@
+//! Browser reuse building blocks.
+//!
+//! Long-lived Chrome instances accumulate memory and cookie/cache state that
+//! degrades stealth, so any reused browser is retired after a number of uses
+//! or a maximum lifetime, whichever comes first.
+//!
+//! Headless Chrome also degrades (and eventually crashes) with too many tabs,
+//! so each browser hands out at most `MAX_TABS_PER_BROWSER` tabs at a time and
+//! [`pick_browser`] spreads work to the least-loaded browser with room.
+//!
+//! [`BROWSER_POOL`] keeps `BROWSER_POOL_SIZE` of these warm so deep extraction
+//! opens a tab instead of paying a full Chrome launch per page. Searches still
+//! launch their own browser: each needs a proxy that isn't cooling down on its
+//! engine and a fresh cookie jar, which a shared warm browser can't give.
+
+use anyhow::Result;
+use headless_chrome::{Browser, Tab};
+use once_cell::sync::Lazy;
+use std::sync::atomic::{AtomicU32, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+use super::browser::{install_stealth, launch_chrome, StealthBrowser};
+use crate::config::env_parse;
+use crate::proxy::{Proxy, ProxyAuthExtension, ProxyLease};
+use crate::stealth::StealthConfig;
+
+/// The Chrome operations the pool relies on; `Browser` in production, a fake in tests
+pub trait PoolBackend: Send + Sync + Sized + 'static {
+    type Tab: Send + Sync;
+
+    /// Start a browser behind `proxy`. Blocking.
+    fn launch(proxy: Option<&Proxy>) -> Result<(Self, Option<ProxyAuthExtension>)>;
+    fn new_tab(&self) -> Result<Arc<Self::Tab>>;
+    fn close_tab(tab: &Self::Tab);
+    fn process_id(&self) -> Option<u32>;
+}
+
+impl PoolBackend for Browser {
+    type Tab = Tab;
+
+    fn launch(proxy: Option<&Proxy>) -> Result<(Self, Option<ProxyAuthExtension>)> {
+        launch_chrome(proxy, super::random_profile().user_agent)
+    }
+
+    fn new_tab(&self) -> Result<Arc<Tab>> {
+        Browser::new_tab(self)
+    }
+
+    fn close_tab(tab: &Tab) {
+        let _ = tab.close(false);
+    }
+
+    fn process_id(&self) -> Option<u32> {
+        self.get_process_id()
+    }
+}
+
+/// When a reused browser must be torn down and replaced
+#[derive(Debug, Clone, Copy)]
+pub struct RecyclePolicy {
+    /// Retire after this many jobs (`BROWSER_MAX_USES`, default 10)
+    pub max_uses: u32,
+    /// Retire after this long regardless of use (`BROWSER_MAX_LIFETIME_SECS`, default 600)
+    pub max_lifetime: Duration,
+    /// Max simultaneously open tabs per browser (`MAX_TABS_PER_BROWSER`, default 4)
+    pub max_tabs: usize,
+}
+
+impl Default for RecyclePolicy {
+    fn default() -> Self {
+        Self {
+            max_uses: 10,
+            max_lifetime: Duration::from_secs(600),
+            max_tabs: 4,
+        }
+    }
+}
+
+impl RecyclePolicy {
+    pub fn from_env() -> Self {
+        let default = Self::default();
+        Self {
+            max_uses: env_parse("BROWSER_MAX_USES", default.max_uses).max(1),
+            max_lifetime: Duration::from_secs(env_parse("BROWSER_MAX_LIFETIME_SECS", default.max_lifetime.as_secs())),
+            max_tabs: env_parse("MAX_TABS_PER_BROWSER", default.max_tabs).max(1),
+        }
+    }
+
+    pub fn is_expired(&self, uses: u32, age: Duration) -> bool {
+        uses >= self.max_uses || age >= self.max_lifetime
+    }
+}
+
+/// A browser plus the bookkeeping needed to apply a [`RecyclePolicy`]
+pub struct PooledBrowser<B: PoolBackend = Browser> {
+    pub browser: B,
+    created_at: Instant,
+    uses: AtomicU32,
+    open_tabs: Arc<AtomicUsize>,
+    // Declared after `browser` so Chrome exits before these are released
+    proxy: Option<ProxyLease>,
+    _auth_ext: Option<ProxyAuthExtension>,
+}
+
+/// A tab opened through [`PooledBrowser::open_tab`]; closed and released on drop
+pub struct TabLease<B: PoolBackend = Browser> {
+    pub tab: Arc<B::Tab>,
+    open_tabs: Arc<AtomicUsize>,
+}
+
+impl<B: PoolBackend> Drop for TabLease<B> {
+    fn drop(&mut self) {
+        B::close_tab(&self.tab);
+        self.open_tabs.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+impl<B: PoolBackend> PooledBrowser<B> {
+    pub fn new(browser: B) -> Self {
+        Self {
+            browser,
+            created_at: Instant::now(),
+            uses: AtomicU32::new(0),
+            open_tabs: Arc::new(AtomicUsize::new(0)),
+            proxy: None,
+            _auth_ext: None,
+        }
+    }
+
+    /// Hold the proxy lease and auth extension the browser was launched with
+    /// for as long as the browser lives
+    pub fn with_launch_guards(mut self, proxy: Option<ProxyLease>, auth_ext: Option<ProxyAuthExtension>) -> Self {
+        self.proxy = proxy;
+        self._auth_ext = auth_ext;
+        self
+    }
+
+    /// Chrome's pid, if it is still running
+    pub fn process_id(&self) -> Option<u32> {
+        self.browser.process_id()
+    }
+
+    pub fn open_tabs(&self) -> usize {
+        self.open_tabs.load(Ordering::SeqCst)
+    }
+
+    /// Reserve a tab slot, returning false if the browser is at `max_tabs`
+    fn reserve_tab(&self, max_tabs: usize) -> bool {
+        self.open_tabs
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| (n < max_tabs).then_some(n + 1))
+            .is_ok()
+    }
+
+    /// Open a new tab if this browser is under the policy's tab cap.
+    /// Returns `Ok(None)` when the cap is hit so the caller can try another browser.
+    pub fn open_tab(&self, policy: &RecyclePolicy) -> anyhow::Result<Option<TabLease<B>>> {
+        if !self.reserve_tab(policy.max_tabs) {
+            return Ok(None);
+        }
+        match self.browser.new_tab() {
+            Ok(tab) => Ok(Some(TabLease { tab, open_tabs: self.open_tabs.clone() })),
+            Err(e) => {
+                self.open_tabs.fetch_sub(1, Ordering::SeqCst);
+                Err(e)
+            }
+        }
+    }
+
+    /// Record one more job handed to this browser, returning the new count
+    pub fn record_use(&self) -> u32 {
+        self.uses.fetch_add(1, Ordering::Relaxed) + 1
+    }
+
+    pub fn uses(&self) -> u32 {
+        self.uses.load(Ordering::Relaxed)
+    }
+
+    pub fn age(&self) -> Duration {
+        self.created_at.elapsed()
+    }
+
+    /// False once the proxy the browser was launched with has been marked unhealthy
+    /// (Chrome can't switch proxies, so the browser has to go)
+    pub fn proxy_healthy(&self) -> bool {
+        self.proxy.as_ref().is_none_or(|p| p.healthy.load(Ordering::Relaxed))
+    }
+
+    /// Should this browser be replaced before its next use?
+    pub fn needs_recycle(&self, policy: &RecyclePolicy) -> bool {
+        policy.is_expired(self.uses(), self.age()) || !self.proxy_healthy()
+    }
+}
+
+/// Index of the least-loaded browser that still has a free tab slot
+/// and is not due for recycling; `None` means a new browser is needed.
+pub fn pick_browser<B: PoolBackend>(browsers: &[Arc<PooledBrowser<B>>], policy: &RecyclePolicy) -> Option<usize> {
+    browsers
+        .iter()
+        .enumerate()
+        .filter(|(_, b)| b.open_tabs() < policy.max_tabs && !b.needs_recycle(policy))
+        .min_by_key(|(_, b)| b.open_tabs())
+        .map(|(i, _)| i)
+}
+
+/// Shared deep-extraction browsers (`BROWSER_POOL_SIZE`, default 2; 0 = launch per page)
+pub static BROWSER_POOL: Lazy<Option<BrowserPool>> = Lazy::new(|| {
+    let size: usize = env_parse("BROWSER_POOL_SIZE", 2);
+    (size > 0).then(|| BrowserPool::new(size, RecyclePolicy::from_env()))
+});
+
+/// Up to `size` warm browsers handing out at most `size * max_tabs` tabs at once.
+/// Browsers are retired per the [`RecyclePolicy`] (or when their proxy goes
+/// unhealthy) and replaced on demand; one that fails to open a tab is assumed
+/// crashed and relaunched.
+pub struct BrowserPool<B: PoolBackend = Browser> {
+    size: usize,
+    policy: RecyclePolicy,
+    browsers: Mutex<Vec<Arc<PooledBrowser<B>>>>,
+    /// Launches in flight, counted against `size`; only changed with `browsers` locked
+    launching: AtomicUsize,
+    tabs: Arc<Semaphore>,
+}
+
+/// What [`BrowserPool::acquire`] decided under the lock
+enum Slot<B: PoolBackend> {
+    Existing(Arc<PooledBrowser<B>>),
+    Launch,
+    /// Every browser is busy and the rest of the pool is still launching
+    Wait,
+}
+
+/// A tab from [`BrowserPool::acquire`]. Dropping it closes the tab and frees its slot;
+/// a retired browser shuts down once its last tab is dropped.
+pub struct PooledTab<B: PoolBackend = Browser> {
+    lease: TabLease<B>,
+    _browser: Arc<PooledBrowser<B>>,
+    _permit: OwnedSemaphorePermit,
+}
+
+impl<B: PoolBackend> PooledTab<B> {
+    pub fn tab(&self) -> &Arc<B::Tab> {
+        &self.lease.tab
+    }
+}
+
+impl<B: PoolBackend> BrowserPool<B> {
+    pub fn new(size: usize, policy: RecyclePolicy) -> Self {
+        Self {
+            size,
+            policy,
+            browsers: Mutex::new(Vec::with_capacity(size)),
+            launching: AtomicUsize::new(0),
+            tabs: Arc::new(Semaphore::new(size * policy.max_tabs)),
+        }
+    }
+
+    fn lock_browsers(&self) -> std::sync::MutexGuard<'_, Vec<Arc<PooledBrowser<B>>>> {
+        self.browsers.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Start a browser for the pool behind `proxy`. Blocking.
+    fn launch(proxy: Option<ProxyLease>) -> Result<Arc<PooledBrowser<B>>> {
+        // Chrome's proxy is per process, so each pooled browser keeps one until retired
+        let (browser, auth_ext) = B::launch(proxy.as_deref())?;
+        Ok(Arc::new(PooledBrowser::new(browser).with_launch_guards(proxy, auth_ext)))
+    }
+
+    /// Retire browsers with no open tab, releasing their proxy leases. Called while
+    /// someone waits for a proxy under PROXY_MAX_CONCURRENT, so idle warm browsers
+    /// don't hold every slot. Returns how many were retired.
+    pub fn retire_idle(&self) -> usize {
+        let idle: Vec<_> = {
+            let mut browsers = self.lock_browsers();
+            let (idle, busy) = std::mem::take(&mut *browsers).into_iter().partition(|b| b.open_tabs() == 0);
+            *browsers = busy;
+            idle
+        };
+        // Dropped here, outside the lock: shutting Chrome down can take a moment
+        idle.len()
+    }
+
+    /// Launch browsers until the pool is full. Blocking; call from a blocking context.
+    pub fn warm_up(&self) {
+        let proxies = &crate::proxy::PROXY_MANAGER;
+        while self.lock_browsers().len() < self.size {
+            let proxy = proxies.get_next_proxy();
+            if proxy.is_none() && proxies.has_proxies() {
+                // The rest launch on demand once a proxy has a free slot
+                println!("⚠️ [BrowserPool] No proxy under PROXY_MAX_CONCURRENT left for pre-launch");
+                break;
+            }
+            match Self::launch(proxy) {
+                Ok(browser) => self.lock_browsers().push(browser),
+                Err(e) => {
+                    eprintln!("⚠️ [BrowserPool] Failed to pre-launch browser: {}", e);
+                    break;
+                }
+            }
+        }
+        println!("🧰 [BrowserPool] {} browser(s) ready", self.lock_browsers().len());
+    }
+
+    /// Wait for a free tab slot, then open a tab on the least-loaded browser.
+    /// The lock only covers picking a browser; launches and tab opens block, so
+    /// they run on the blocking pool.
+    pub async fn acquire(&self) -> Result<PooledTab<B>> {
+        let permit = self.tabs.clone().acquire_owned().await?;
+        let policy = self.policy;
+        // A crashed browser is dropped and replaced once before giving up
+        let mut crashes = 0;
+        loop {
+            let slot = {
+                let mut browsers = self.lock_browsers();
+                // Retired browsers (expired, or behind a proxy gone unhealthy) stay
+                // alive through the Arc in their open tabs
+                browsers.retain(|b| !b.needs_recycle(&policy));
+                match pick_browser(&browsers, &policy) {
+                    Some(i) => Slot::Existing(browsers[i].clone()),
+                    None if browsers.len() + self.launching.load(Ordering::SeqCst) < self.size => {
+                        self.launching.fetch_add(1, Ordering::SeqCst);
+                        Slot::Launch
+                    }
+                    None => Slot::Wait,
+                }
+            };
+
+            let browser = match slot {
+                Slot::Existing(browser) => browser,
+                Slot::Launch => {
+                    let launched = async {
+                        let proxy = super::select_proxy(None, None).await?;
+                        tokio::task::spawn_blocking(move || Self::launch(proxy)).await?
+                    }
+                    .await;
+                    let mut browsers = self.lock_browsers();
+                    self.launching.fetch_sub(1, Ordering::SeqCst);
+                    let browser = launched?;
+                    browsers.push(browser.clone());
+                    browser
+                }
+                Slot::Wait => {
+                    tokio::time::sleep(Duration::from_millis(50)).await;
+                    continue;
+                }
+            };
+
+            let opener = browser.clone();
+            match tokio::task::spawn_blocking(move || opener.open_tab(&policy)).await? {
+                Ok(Some(lease)) => {
+                    browser.record_use();
+                    return Ok(PooledTab { lease, _browser: browser, _permit: permit });
+                }
+                // Another acquire took the last slot on this browser
+                Ok(None) => continue,
+                Err(e) => {
+                    eprintln!("💥 [BrowserPool] Browser failed to open a tab ({}), replacing it", e);
+                    self.lock_browsers().retain(|b| !Arc::ptr_eq(b, &browser));
+                    crashes += 1;
+                    if crashes >= 2 {
+                        anyhow::bail!("browser pool could not open a tab: {}", e);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Chrome pids owned by the pool, so the orphan reaper leaves them alone
+    pub fn process_ids(&self) -> Vec<u32> {
+        self.lock_browsers().iter().filter_map(|b| b.process_id()).collect()
+    }
+}
+
+impl BrowserPool {
+    /// [`acquire`](Self::acquire) a tab and make it look like `user_agent`
+    pub async fn acquire_stealth(&self, user_agent: &str, stealth: &StealthConfig) -> Result<PooledTab> {
+        let pooled = self.acquire().await?;
+        // The browser was launched with some other profile's UA
+        pooled.tab().set_user_agent(user_agent, None, None)?;
+        install_stealth(pooled.tab(), stealth)?;
+        Ok(pooled)
+    }
+}
+
+/// Keeps whichever browser backs an extraction tab alive while the tab is in use
+pub enum TabHandle {
+    /// A browser launched for this extraction, and the proxy it was launched with
+    Dedicated { browser: StealthBrowser, _proxy: Option<ProxyLease> },
+    Pooled(PooledTab),
+}
+
+impl TabHandle {
+    pub fn tab(&self) -> &Arc<Tab> {
+        match self {
+            TabHandle::Dedicated { browser, .. } => &browser.tab,
+            TabHandle::Pooled(pooled) => pooled.tab(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::AtomicBool;
+
+    /// Stand-in for Chrome; `crashed` makes `new_tab` fail
+    #[derive(Default)]
+    struct FakeBrowser {
+        crashed: AtomicBool,
+    }
+
+    impl PoolBackend for FakeBrowser {
+        type Tab = ();
+
+        fn launch(_proxy: Option<&Proxy>) -> Result<(Self, Option<ProxyAuthExtension>)> {
+            Ok((Self::default(), None))
+        }
+
+        fn new_tab(&self) -> Result<Arc<()>> {
+            if self.crashed.load(Ordering::SeqCst) {
+                anyhow::bail!("browser crashed");
+            }
+            Ok(Arc::new(()))
+        }
+
+        fn close_tab(_tab: &()) {}
+
+        fn process_id(&self) -> Option<u32> {
+            None
+        }
+    }
+
+    fn policy(max_uses: u32, max_tabs: usize) -> RecyclePolicy {
+        RecyclePolicy { max_uses, max_lifetime: Duration::from_secs(60), max_tabs }
+    }
+
+    #[test]
+    fn test_recycle_policy_limits() {
+        let policy = RecyclePolicy {
+            max_uses: 3,
+            max_lifetime: Duration::from_secs(60),
+            max_tabs: 4,
+        };
+        assert!(!policy.is_expired(2, Duration::from_secs(10)));
+        assert!(policy.is_expired(3, Duration::from_secs(10)));
+        assert!(policy.is_expired(0, Duration::from_secs(61)));
+    }
+
+    #[test]
+    fn test_pick_browser_least_loaded_with_room() {
+        let policy = policy(3, 2);
+        let browsers: Vec<Arc<PooledBrowser<FakeBrowser>>> =
+            (0..3).map(|_| Arc::new(PooledBrowser::new(FakeBrowser::default()))).collect();
+        // Loads: #0 full (2), #1 empty, #2 one tab
+        let mut tabs: Vec<_> = [0, 0, 2].iter().map(|&i| browsers[i].open_tab(&policy).unwrap().unwrap()).collect();
+        assert!(browsers[0].open_tab(&policy).unwrap().is_none());
+        assert_eq!(pick_browser(&browsers, &policy), Some(1));
+
+        // Used up: skipped even though it is the least loaded
+        for _ in 0..3 {
+            browsers[1].record_use();
+        }
+        assert_eq!(pick_browser(&browsers, &policy), Some(2));
+
+        tabs.push(browsers[2].open_tab(&policy).unwrap().unwrap());
+        assert_eq!(pick_browser(&browsers, &policy), None);
+    }
+
+    #[test]
+    fn test_unhealthy_proxy_retires_browser() {
+        let manager = crate::proxy::ProxyManager::new(Vec::new(), crate::proxy::RotationStrategy::RoundRobin, 1);
+        manager.add_proxy("1.1.1.1:8080", false).unwrap();
+        let browser = PooledBrowser::new(FakeBrowser::default()).with_launch_guards(manager.get_next_proxy(), None);
+        assert!(!browser.needs_recycle(&policy(10, 2)));
+
+        manager.mark_failure("1.1.1.1:8080");
+        assert!(!browser.proxy_healthy());
+        assert!(browser.needs_recycle(&policy(10, 2)));
+    }
+
+    #[tokio::test]
+    async fn test_acquire_replaces_crashed_browser() {
+        let pool = BrowserPool::<FakeBrowser>::new(1, policy(10, 2));
+        let _first = pool.acquire().await.unwrap();
+        let crashed = pool.lock_browsers()[0].clone();
+        crashed.browser.crashed.store(true, Ordering::SeqCst);
+
+        let _second = pool.acquire().await.unwrap();
+        let browsers = pool.lock_browsers();
+        assert_eq!(browsers.len(), 1);
+        assert!(!Arc::ptr_eq(&browsers[0], &crashed));
+    }
+
+    #[tokio::test]
+    async fn test_acquire_capacity() {
+        let pool = BrowserPool::<FakeBrowser>::new(2, policy(10, 1));
+        let first = pool.acquire().await.unwrap();
+        let second = pool.acquire().await.unwrap();
+        // One tab per browser: the second acquire launched the second browser
+        assert_eq!(pool.lock_browsers().len(), 2);
+
+        // size * max_tabs tabs are out, so the next acquire waits
+        assert!(tokio::time::timeout(Duration::from_millis(100), pool.acquire()).await.is_err());
+        drop(first);
+        let third = tokio::time::timeout(Duration::from_secs(1), pool.acquire()).await.unwrap().unwrap();
+        assert_eq!(pool.lock_browsers().len(), 2);
+
+        drop(second);
+        assert_eq!(pool.retire_idle(), 1);
+        assert_eq!(pool.lock_browsers().len(), 1);
+        drop(third);
+    }
+}
@@ -0,0 +1,199 @@
+//! Chrome process hygiene for long-running workers.
+//!
+//! Dropping a `headless_chrome::Browser` should kill its Chrome process, but on
+//! some error paths (panicked tabs, failed launches, hung renderers) processes
+//! survive and memory creeps up over days. After each job the worker looks for
+//! Chrome processes still descended from this process, gives them a grace
+//! period to exit, then SIGKILLs the stragglers. Browsers held by the
+//! [`BROWSER_POOL`](super::pool::BROWSER_POOL) (and their renderers) are
+//! meant to outlive jobs and are skipped. Linux only (reads `/proc`); a no-op
+//! elsewhere.
+
+use std::collections::{HashMap, HashSet};
+use std::time::Duration;
+
+/// Memory / process counts for this process and its Chrome descendants
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ProcessStats {
+    pub rss_kb: u64,
+    pub chrome_processes: usize,
+    pub chrome_rss_kb: u64,
+}
+
+/// One `/proc/<pid>/stat` entry: (pid, comm, ppid)
+pub fn parse_stat_line(line: &str) -> Option<(u32, String, u32)> {
+    // comm is parenthesised and may itself contain spaces or ')'
+    let open = line.find('(')?;
+    let close = line.rfind(')')?;
+    let pid = line[..open].trim().parse().ok()?;
+    let comm = line[open + 1..close].to_string();
+    let mut rest = line[close + 1..].split_whitespace();
+    let _state = rest.next()?;
+    let ppid = rest.next()?.parse().ok()?;
+    Some((pid, comm, ppid))
+}
+
+pub fn is_chrome_comm(comm: &str) -> bool {
+    let comm = comm.to_lowercase();
+    comm.contains("chrome") || comm.contains("chromium") || comm.contains("headless_shell")
+}
+
+/// Chrome processes anywhere below `root` in the process tree
+pub fn chrome_descendants(root: u32, procs: &[(u32, String, u32)]) -> Vec<u32> {
+    let mut children: HashMap<u32, Vec<u32>> = HashMap::new();
+    for (pid, _, ppid) in procs {
+        children.entry(*ppid).or_default().push(*pid);
+    }
+    let comm_of: HashMap<u32, &str> = procs.iter().map(|(pid, comm, _)| (*pid, comm.as_str())).collect();
+
+    let mut found = Vec::new();
+    let mut stack = vec![root];
+    while let Some(pid) = stack.pop() {
+        for &child in children.get(&pid).into_iter().flatten() {
+            if comm_of.get(&child).is_some_and(|c| is_chrome_comm(c)) {
+                found.push(child);
+            }
+            stack.push(child);
+        }
+    }
+    found.sort_unstable();
+    found
+}
+
+#[cfg(target_os = "linux")]
+fn list_processes() -> Vec<(u32, String, u32)> {
+    let Ok(entries) = std::fs::read_dir("/proc") else {
+        return Vec::new();
+    };
+    entries
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_name().to_string_lossy().chars().all(|c| c.is_ascii_digit()))
+        .filter_map(|e| std::fs::read_to_string(e.path().join("stat")).ok())
+        .filter_map(|line| parse_stat_line(&line))
+        .collect()
+}
+
+#[cfg(not(target_os = "linux"))]
+fn list_processes() -> Vec<(u32, String, u32)> {
+    Vec::new()
+}
+
+fn rss_kb(pid: u32) -> u64 {
+    std::fs::read_to_string(format!("/proc/{}/status", pid))
+        .ok()
+        .and_then(|status| {
+            status
+                .lines()
+                .find(|l| l.starts_with("VmRSS:"))
+                .and_then(|l| l.split_whitespace().nth(1))
+                .and_then(|kb| kb.parse().ok())
+        })
+        .unwrap_or(0)
+}
+
+/// Chrome processes currently descended from this process
+pub fn own_chrome_pids() -> Vec<u32> {
+    chrome_descendants(std::process::id(), &list_processes())
+}
+
+/// `pids` minus every process in the trees rooted at `keep`
+pub fn exclude_trees(pids: Vec<u32>, keep: &[u32], procs: &[(u32, String, u32)]) -> Vec<u32> {
+    let kept: HashSet<u32> = keep
+        .iter()
+        .flat_map(|&root| std::iter::once(root).chain(chrome_descendants(root, procs)))
+        .collect();
+    pids.into_iter().filter(|pid| !kept.contains(pid)).collect()
+}
+
+/// Own Chrome processes that don't belong to a pooled browser
+fn orphaned_chrome_pids() -> Vec<u32> {
+    let procs = list_processes();
+    let pooled = super::pool::BROWSER_POOL.as_ref().map(|p| p.process_ids()).unwrap_or_default();
+    exclude_trees(chrome_descendants(std::process::id(), &procs), &pooled, &procs)
+}
+
+pub fn process_stats() -> ProcessStats {
+    let chrome = own_chrome_pids();
+    ProcessStats {
+        rss_kb: rss_kb(std::process::id()),
+        chrome_processes: chrome.len(),
+        chrome_rss_kb: chrome.iter().map(|&pid| rss_kb(pid)).sum(),
+    }
+}
+
+/// Wait up to `grace` for leftover Chrome processes to exit on their own, then
+/// SIGKILL whatever remains. Call only when no browser outside the pool should
+/// be alive (between jobs). Returns the number of processes killed.
+pub async fn reap_orphaned_chrome(grace: Duration) -> usize {
+    if orphaned_chrome_pids().is_empty() {
+        return 0;
+    }
+    tokio::time::sleep(grace).await;
+    let survivors = orphaned_chrome_pids();
+    for &pid in &survivors {
+        kill(pid);
+    }
+    if !survivors.is_empty() {
+        println!("🧹 Killed {} orphaned Chrome process(es): {:?}", survivors.len(), survivors);
+    }
+    survivors.len()
+}
+
+#[cfg(unix)]
+fn kill(pid: u32) {
+    // SAFETY: plain syscalls on a pid we just found in our own process tree.
+    // WNOHANG reaps it if it was our direct child, so it doesn't linger as a zombie.
+    unsafe {
+        libc::kill(pid as libc::pid_t, libc::SIGKILL);
+        libc::waitpid(pid as libc::pid_t, std::ptr::null_mut(), libc::WNOHANG);
+    }
+}
+
+#[cfg(not(unix))]
+fn kill(_pid: u32) {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_stat_line() {
+        assert_eq!(
+            parse_stat_line("4242 (chrome) S 4200 4242 4242 0 -1 4194560"),
+            Some((4242, "chrome".to_string(), 4200))
+        );
+        assert_eq!(
+            parse_stat_line("77 (Web Content (x)) R 1 77 77"),
+            Some((77, "Web Content (x)".to_string(), 1))
+        );
+        assert_eq!(parse_stat_line("garbage"), None);
+    }
+
+    #[test]
+    fn test_chrome_descendants() {
+        let procs = vec![
+            (100, "rust-crawler".to_string(), 1),
+            (101, "chrome".to_string(), 100),
+            (102, "chrome".to_string(), 101),
+            (103, "cat".to_string(), 101),
+            (104, "chrome".to_string(), 103),
+            (200, "chrome".to_string(), 1), // someone else's browser
+        ];
+        assert_eq!(chrome_descendants(100, &procs), vec![101, 102, 104]);
+        assert!(chrome_descendants(999, &procs).is_empty());
+    }
+
+    #[test]
+    fn test_exclude_trees_keeps_pooled_browsers() {
+        let procs = vec![
+            (100, "rust-crawler".to_string(), 1),
+            (101, "chrome".to_string(), 100), // pooled
+            (102, "chrome".to_string(), 101), // its renderer
+            (103, "chrome".to_string(), 100), // leaked
+            (104, "chrome".to_string(), 103),
+        ];
+        let all = chrome_descendants(100, &procs);
+        assert_eq!(exclude_trees(all.clone(), &[101], &procs), vec![103, 104]);
+        assert_eq!(exclude_trees(all.clone(), &[], &procs), all);
+    }
+}
@@ -0,0 +1,328 @@
+//! robots.txt compliance for deep extraction (`respect_robots`).
+//!
+//! Rules are fetched once per origin and cached for `ROBOTS_CACHE_TTL_SECS`;
+//! concurrent misses for one origin share a single fetch. robots.txt goes out
+//! through the job's proxy (its sticky one if bound) like the page itself, so
+//! the crawler's own IP is never exposed.
+//! Matching follows RFC 9309: the group naming our token (`ROBOTS_USER_AGENT`,
+//! default `*`) wins over the `*` group, the longest matching path rule
+//! decides, and `Allow` wins a tie. A missing robots.txt (4xx) allows
+//! everything; an unreachable one (5xx / network error) disallows everything.
+
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::config::env_parse;
+use crate::proxy::Proxy;
+
+/// Extraction skipped because the site's robots.txt disallows the URL
+#[derive(Debug)]
+pub struct RobotsDisallowed {
+    pub url: String,
+}
+
+impl std::fmt::Display for RobotsDisallowed {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "disallowed by robots.txt: {}", self.url)
+    }
+}
+
+impl std::error::Error for RobotsDisallowed {}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Rule {
+    allow: bool,
+    pattern: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+struct Group {
+    agents: Vec<String>,
+    rules: Vec<Rule>,
+}
+
+/// Parsed robots.txt
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RobotsRules {
+    groups: Vec<Group>,
+}
+
+impl RobotsRules {
+    /// Everything allowed (no robots.txt)
+    pub fn allow_all() -> Self {
+        Self::default()
+    }
+
+    /// Everything disallowed (robots.txt unreachable)
+    pub fn disallow_all() -> Self {
+        Self {
+            groups: vec![Group {
+                agents: vec!["*".to_string()],
+                rules: vec![Rule { allow: false, pattern: "/".to_string() }],
+            }],
+        }
+    }
+
+    pub fn parse(body: &str) -> Self {
+        let mut groups: Vec<Group> = Vec::new();
+        // Consecutive user-agent lines share the group that follows them
+        let mut collecting_agents = false;
+        for line in body.lines() {
+            let line = line.split('#').next().unwrap_or("").trim();
+            let Some((key, value)) = line.split_once(':') else { continue };
+            let (key, value) = (key.trim().to_lowercase(), value.trim());
+            match key.as_str() {
+                "user-agent" => {
+                    if !collecting_agents {
+                        groups.push(Group::default());
+                    }
+                    collecting_agents = true;
+                    if let Some(group) = groups.last_mut() {
+                        group.agents.push(value.to_lowercase());
+                    }
+                }
+                "allow" | "disallow" => {
+                    collecting_agents = false;
+                    // Rules before any user-agent line belong to no group
+                    let Some(group) = groups.last_mut() else { continue };
+                    // An empty Disallow means "allow everything"
+                    if !value.is_empty() {
+                        group.rules.push(Rule { allow: key == "allow", pattern: value.to_string() });
+                    }
+                }
+                _ => {}
+            }
+        }
+        Self { groups }
+    }
+
+    /// Is `path` (path plus query) allowed for `user_agent`?
+    pub fn is_allowed(&self, user_agent: &str, path: &str) -> bool {
+        let agent = user_agent.to_lowercase();
+        let named: Vec<&Group> = self
+            .groups
+            .iter()
+            .filter(|g| agent != "*" && g.agents.iter().any(|a| a != "*" && agent.contains(a.as_str())))
+            .collect();
+        let groups = if named.is_empty() {
+            self.groups.iter().filter(|g| g.agents.iter().any(|a| a == "*")).collect()
+        } else {
+            named
+        };
+
+        groups
+            .iter()
+            .flat_map(|g| g.rules.iter())
+            .filter(|rule| path_matches(&rule.pattern, path))
+            .max_by_key(|rule| (rule.pattern.len(), rule.allow))
+            .map(|rule| rule.allow)
+            .unwrap_or(true)
+    }
+}
+
+/// robots.txt path pattern: `*` matches any run of characters, a trailing `$` anchors the end
+pub fn path_matches(pattern: &str, path: &str) -> bool {
+    let (pattern, anchored) = match pattern.strip_suffix('$') {
+        Some(p) => (p, true),
+        None => (pattern, false),
+    };
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or("");
+    let Some(mut rest) = path.strip_prefix(first) else {
+        return false;
+    };
+    let parts: Vec<&str> = parts.collect();
+    for (i, part) in parts.iter().enumerate() {
+        let last = i == parts.len() - 1;
+        if last && anchored {
+            return rest.ends_with(part);
+        }
+        match rest.find(part) {
+            Some(pos) => rest = &rest[pos + part.len()..],
+            None => return false,
+        }
+    }
+    !anchored || rest.is_empty()
+}
+
+/// One origin's parsed rules and when they were fetched; callers that miss
+/// together wait on the same fetch
+#[derive(Default)]
+struct CacheEntry {
+    rules: tokio::sync::OnceCell<(Instant, Arc<RobotsRules>)>,
+}
+
+/// Cache entries per origin (`scheme://host:port`)
+static ROBOTS_CACHE: Lazy<Mutex<HashMap<String, Arc<CacheEntry>>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// The token matched against robots.txt groups (`ROBOTS_USER_AGENT`, default `*`)
+pub fn robots_user_agent() -> String {
+    std::env::var("ROBOTS_USER_AGENT").unwrap_or_else(|_| "*".to_string())
+}
+
+async fn fetch_rules(origin: &str, proxy: Option<&Proxy>) -> RobotsRules {
+    let robots_url = format!("{}/robots.txt", origin);
+    let mut builder = reqwest::Client::builder().timeout(Duration::from_secs(env_parse("ROBOTS_FETCH_TIMEOUT_SECS", 10)));
+    if let Some(proxy) = proxy {
+        match proxy.to_reqwest_proxy() {
+            Ok(p) => builder = builder.proxy(p),
+            Err(e) => {
+                eprintln!("⚠️ Can't fetch {} through proxy {}: {}, treating site as disallowed", robots_url, proxy.id, e);
+                return RobotsRules::disallow_all();
+            }
+        }
+    }
+    let client = match builder.build() {
+        Ok(client) => client,
+        Err(_) => return RobotsRules::disallow_all(),
+    };
+    match client.get(&robots_url).send().await {
+        Ok(resp) if resp.status().is_success() => match resp.text().await {
+            Ok(body) => RobotsRules::parse(&body),
+            Err(e) => {
+                eprintln!("⚠️ Failed to read {}: {}", robots_url, e);
+                RobotsRules::disallow_all()
+            }
+        },
+        Ok(resp) if resp.status().is_client_error() => RobotsRules::allow_all(),
+        Ok(resp) => {
+            eprintln!("⚠️ {} answered HTTP {}, treating site as disallowed", robots_url, resp.status());
+            RobotsRules::disallow_all()
+        }
+        Err(e) => {
+            eprintln!("⚠️ Failed to fetch {}: {}, treating site as disallowed", robots_url, e);
+            RobotsRules::disallow_all()
+        }
+    }
+}
+
+/// Rules for `origin` from the cache, running `fetch` on a miss or after `ttl`.
+/// A failed fetch caches nothing, so the next caller tries again.
+async fn cached_rules<F, Fut>(origin: &str, ttl: Duration, fetch: F) -> anyhow::Result<Arc<RobotsRules>>
+where
+    F: FnOnce() -> Fut,
+    Fut: Future<Output = anyhow::Result<RobotsRules>>,
+{
+    let entry = {
+        let mut cache = ROBOTS_CACHE.lock().unwrap_or_else(|e| e.into_inner());
+        let entry = cache.entry(origin.to_string()).or_default();
+        if entry.rules.get().is_some_and(|(fetched_at, _)| fetched_at.elapsed() >= ttl) {
+            *entry = Arc::default();
+        }
+        entry.clone()
+    };
+    let (_, rules) = entry
+        .rules
+        .get_or_try_init(|| async { Ok::<_, anyhow::Error>((Instant::now(), Arc::new(fetch().await?))) })
+        .await?;
+    Ok(rules.clone())
+}
+
+/// Cached rules for `url`'s origin, fetching robots.txt through the job's proxy
+/// on a miss or after the TTL
+async fn rules_for(url: &reqwest::Url, session_key: Option<&str>) -> anyhow::Result<Arc<RobotsRules>> {
+    let origin = url.origin().ascii_serialization();
+    let ttl = Duration::from_secs(env_parse("ROBOTS_CACHE_TTL_SECS", 3600));
+    let robots_origin = origin.as_str();
+    cached_rules(&origin, ttl, move || async move {
+        let proxy = super::select_proxy(None, session_key).await?;
+        Ok(fetch_rules(robots_origin, proxy.as_deref()).await)
+    })
+    .await
+}
+
+/// Err([`RobotsDisallowed`]) if robots.txt forbids fetching `url`.
+/// Only http(s) URLs are checked. `session_key` picks the job's sticky proxy.
+pub async fn check_allowed(url: &str, session_key: Option<&str>) -> anyhow::Result<()> {
+    let Ok(parsed) = reqwest::Url::parse(url) else {
+        return Ok(());
+    };
+    if !matches!(parsed.scheme(), "http" | "https") {
+        return Ok(());
+    }
+    let path = match parsed.query() {
+        Some(query) => format!("{}?{}", parsed.path(), query),
+        None => parsed.path().to_string(),
+    };
+    if rules_for(&parsed, session_key).await?.is_allowed(&robots_user_agent(), &path) {
+        Ok(())
+    } else {
+        println!("🤖 robots.txt disallows {}, skipping extraction", url);
+        Err(RobotsDisallowed { url: url.to_string() }.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ROBOTS: &str = "
+        User-agent: *
+        Disallow: /private/
+        Allow: /private/public
+        Disallow: /*.pdf$
+
+        User-agent: rust-crawler
+        User-agent: other-bot
+        Disallow: /
+        Allow: /blog
+    ";
+
+    #[test]
+    fn test_robots_default_group() {
+        let rules = RobotsRules::parse(ROBOTS);
+        assert!(rules.is_allowed("*", "/"));
+        assert!(!rules.is_allowed("*", "/private/data"));
+        assert!(rules.is_allowed("*", "/private/public/page"));
+        assert!(!rules.is_allowed("*", "/docs/file.pdf"));
+        assert!(rules.is_allowed("*", "/docs/file.pdf?download=1"));
+    }
+
+    #[test]
+    fn test_robots_named_group_and_fallbacks() {
+        let rules = RobotsRules::parse(ROBOTS);
+        assert!(!rules.is_allowed("rust-crawler/1.0", "/about"));
+        assert!(rules.is_allowed("rust-crawler/1.0", "/blog/post"));
+        assert!(!rules.is_allowed("unlisted", "/private/x"));
+        assert!(rules.is_allowed("*", "/anything") && RobotsRules::allow_all().is_allowed("*", "/private"));
+        assert!(!RobotsRules::disallow_all().is_allowed("*", "/"));
+        assert!(RobotsRules::parse("User-agent: *\nDisallow:\n").is_allowed("*", "/x"));
+    }
+
+    #[test]
+    fn test_path_matches() {
+        assert!(path_matches("/a/", "/a/b"));
+        assert!(!path_matches("/a/", "/b/a/"));
+        assert!(path_matches("/*/edit", "/page/edit/1"));
+        assert!(path_matches("/x$", "/x"));
+        assert!(!path_matches("/x$", "/xy"));
+        assert!(path_matches("/*.php$", "/index.php"));
+        assert!(!path_matches("/*.php$", "/index.php5"));
+    }
+
+    #[tokio::test]
+    async fn test_cached_rules_coalesces_concurrent_misses() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        let fetches = &AtomicUsize::new(0);
+        let fetch = move || async move {
+            fetches.fetch_add(1, Ordering::SeqCst);
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            Ok(RobotsRules::parse(ROBOTS))
+        };
+        let ttl = Duration::from_secs(60);
+        let origin = "https://coalesce.test";
+        let (a, b, c) = tokio::join!(cached_rules(origin, ttl, fetch), cached_rules(origin, ttl, fetch), cached_rules(origin, ttl, fetch));
+        assert_eq!(fetches.load(Ordering::SeqCst), 1);
+        assert!(Arc::ptr_eq(&a.unwrap(), &b.unwrap()));
+        assert!(!c.unwrap().is_allowed("*", "/private/x"));
+
+        // Expired entries are fetched again; failures aren't cached
+        cached_rules(origin, Duration::ZERO, fetch).await.unwrap();
+        assert_eq!(fetches.load(Ordering::SeqCst), 2);
+        assert!(cached_rules("https://fails.test", ttl, || async { anyhow::bail!("no proxy") }).await.is_err());
+        assert!(cached_rules("https://fails.test", ttl, fetch).await.is_ok());
+    }
+}
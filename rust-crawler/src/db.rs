@@ -1,4 +1,4 @@
-use sqlx::{postgres::PgPool, Row};
+use sqlx::{postgres::PgPool, PgConnection, Postgres, QueryBuilder, Row};
 use anyhow::Result;
 
 pub async fn init_db(pool: &PgPool) -> Result<()> {
@@ -56,5 +56,359 @@ pub async fn init_db(pool: &PgPool) -> Result<()> {
         .execute(pool)
         .await;
 
+    // Per-result child rows (one per SERP result)
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS task_results (
+            id BIGSERIAL PRIMARY KEY,
+            task_id VARCHAR NOT NULL,
+            position INTEGER NOT NULL,
+            title TEXT,
+            link TEXT NOT NULL,
+            snippet TEXT,
+            created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+        );
+        "#,
+    )
+    .execute(pool)
+    .await?;
+    let _ = sqlx::query("CREATE INDEX IF NOT EXISTS idx_task_results_task_id ON task_results (task_id);")
+        .execute(pool)
+        .await;
+
+    // Result Signature (Ed25519, hex)
+    for column in ["signature TEXT", "signed_at TEXT", "signature_key_id TEXT"] {
+        let _ = sqlx::query(&format!("ALTER TABLE tasks ADD COLUMN IF NOT EXISTS {};", column))
+            .execute(pool)
+            .await;
+    }
+
+    // Deep-extracted article as Markdown (extracted_text stays plain text);
+    // created before the TOAST settings below so it gets them on first boot
+    let _ = sqlx::query("ALTER TABLE tasks ADD COLUMN IF NOT EXISTS extracted_markdown TEXT;")
+        .execute(pool)
+        .await;
+
+    // Large text columns: make sure they are TOAST-compressed out of line
+    // (EXTENDED is the TEXT default, but tables created by older tooling may
+    // have been switched to EXTERNAL/MAIN). Only affects newly written rows.
+    for column in ["results_json", "extracted_text", "extracted_markdown", "first_page_html"] {
+        let _ = sqlx::query(&format!("ALTER TABLE tasks ALTER COLUMN {} SET STORAGE EXTENDED;", column))
+            .execute(pool)
+            .await;
+    }
+    // Optional compression method (Postgres 14+): pglz (default) or lz4
+    if let Ok(method) = std::env::var("DB_TOAST_COMPRESSION") {
+        let method = method.to_lowercase();
+        if method == "pglz" || method == "lz4" {
+            for column in ["results_json", "extracted_text", "extracted_markdown", "first_page_html"] {
+                if let Err(e) = sqlx::query(&format!("ALTER TABLE tasks ALTER COLUMN {} SET COMPRESSION {};", column, method))
+                    .execute(pool)
+                    .await
+                {
+                    eprintln!("⚠️ Could not set {} compression on tasks.{}: {}", method, column, e);
+                }
+            }
+        } else {
+            eprintln!("⚠️ Ignoring DB_TOAST_COMPRESSION={} (expected pglz or lz4)", method);
+        }
+    }
+
+    // Related-search children point at the task that spawned them
+    let _ = sqlx::query("ALTER TABLE tasks ADD COLUMN IF NOT EXISTS parent_task_id VARCHAR;")
+        .execute(pool)
+        .await;
+    let _ = sqlx::query("CREATE INDEX IF NOT EXISTS idx_tasks_parent_task_id ON tasks (parent_task_id);")
+        .execute(pool)
+        .await;
+
+    // Client metadata passthrough
+    let _ = sqlx::query("ALTER TABLE tasks ADD COLUMN IF NOT EXISTS metadata JSONB;")
+        .execute(pool)
+        .await;
+
+    // Jobs submitted together under a client batch_id
+    let _ = sqlx::query("ALTER TABLE tasks ADD COLUMN IF NOT EXISTS batch_id VARCHAR;")
+        .execute(pool)
+        .await;
+    let _ = sqlx::query("CREATE INDEX IF NOT EXISTS idx_tasks_batch_id ON tasks (batch_id);")
+        .execute(pool)
+        .await;
+
+    // Redirect hops of the deep-extracted page
+    let _ = sqlx::query("ALTER TABLE tasks ADD COLUMN IF NOT EXISTS redirect_chain JSONB;")
+        .execute(pool)
+        .await;
+
+    // Full (untrimmed) results JSON (MinIO key)
+    let _ = sqlx::query("ALTER TABLE tasks ADD COLUMN IF NOT EXISTS results_key TEXT;")
+        .execute(pool)
+        .await;
+
+    // Per-page stored objects [{html_key, screenshot_key}] in page order; unlike
+    // results_json.pages, never trimmed by RESULT_MAX_BYTES
+    let _ = sqlx::query("ALTER TABLE tasks ADD COLUMN IF NOT EXISTS page_keys JSONB;")
+        .execute(pool)
+        .await;
+
+    // Page thumbnail (MinIO key)
+    let _ = sqlx::query("ALTER TABLE tasks ADD COLUMN IF NOT EXISTS thumbnail_key TEXT;")
+        .execute(pool)
+        .await;
+
+    // Why the last run failed (set alongside status 'failed', or 'pending' while a retry waits)
+    let _ = sqlx::query("ALTER TABLE tasks ADD COLUMN IF NOT EXISTS error_message TEXT;")
+        .execute(pool)
+        .await;
+    // Failure category: challenge_detected, page_too_small, no_results_found, extract_failed
+    let _ = sqlx::query("ALTER TABLE tasks ADD COLUMN IF NOT EXISTS failure_reason TEXT;")
+        .execute(pool)
+        .await;
+
+    // Full-text index over the extracted page text (GET /search). A generated column
+    // keeps it in step with every INSERT/UPDATE without a trigger (Postgres 12+).
+    if let Err(e) = sqlx::query(
+        "ALTER TABLE tasks ADD COLUMN IF NOT EXISTS search_vector tsvector \
+         GENERATED ALWAYS AS (to_tsvector('english', coalesce(extracted_text, ''))) STORED;",
+    )
+    .execute(pool)
+    .await
+    {
+        eprintln!("⚠️ Could not add tasks.search_vector: {}", e);
+    }
+    let _ = sqlx::query("CREATE INDEX IF NOT EXISTS idx_tasks_search_vector ON tasks USING GIN (search_vector);")
+        .execute(pool)
+        .await;
+
+    // Rank-tracking baselines: one reference task per keyword + engine
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS keyword_baselines (
+            keyword VARCHAR NOT NULL,
+            engine VARCHAR NOT NULL,
+            task_id VARCHAR NOT NULL,
+            set_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+            PRIMARY KEY (keyword, engine)
+        );
+        "#,
+    )
+    .execute(pool)
+    .await?;
+
+    // Proxies added at runtime (POST /proxies), reloaded into PROXY_MANAGER at startup
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS proxies (
+            id VARCHAR PRIMARY KEY,
+            protocol VARCHAR NOT NULL DEFAULT 'http',
+            host VARCHAR NOT NULL,
+            port INTEGER NOT NULL,
+            username TEXT,
+            password TEXT,
+            enabled BOOLEAN NOT NULL DEFAULT TRUE,
+            created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+        );
+        "#,
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Insert or update a persisted proxy (same id = same host:port)
+pub async fn save_proxy(pool: &PgPool, proxy: &crate::proxy::Proxy) -> Result<()> {
+    sqlx::query(
+        r#"
+        INSERT INTO proxies (id, protocol, host, port, username, password, enabled)
+        VALUES ($1, $2, $3, $4, $5, $6, TRUE)
+        ON CONFLICT (id) DO UPDATE SET
+            protocol = EXCLUDED.protocol, username = EXCLUDED.username, password = EXCLUDED.password
+        "#,
+    )
+    .bind(&proxy.id)
+    .bind(proxy.protocol.as_str())
+    .bind(&proxy.host)
+    .bind(proxy.port as i32)
+    .bind(&proxy.username)
+    .bind(&proxy.password)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+pub async fn delete_proxy(pool: &PgPool, proxy_id: &str) -> Result<()> {
+    sqlx::query("DELETE FROM proxies WHERE id = $1").bind(proxy_id).execute(pool).await?;
+    Ok(())
+}
+
+/// Persist a manual re-enable or an automatic disable/recovery; no-op for
+/// proxies that only come from PROXY_LIST
+pub async fn set_proxy_enabled(pool: &PgPool, proxy_id: &str, enabled: bool) -> Result<()> {
+    sqlx::query("UPDATE proxies SET enabled = $1 WHERE id = $2")
+        .bind(enabled)
+        .bind(proxy_id)
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
+/// Every persisted proxy; rows with an unknown protocol or port are skipped.
+/// Disabled rows come back unhealthy.
+pub async fn load_proxies(pool: &PgPool) -> Result<Vec<crate::proxy::Proxy>> {
+    let rows = sqlx::query(
+        "SELECT id, protocol, host, port, username, password, enabled FROM proxies ORDER BY created_at",
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let mut proxies = Vec::with_capacity(rows.len());
+    for row in rows {
+        let id: String = row.try_get("id")?;
+        let protocol = match crate::proxy::ProxyProtocol::from_scheme(&row.try_get::<String, _>("protocol")?) {
+            Ok(p) => p,
+            Err(e) => {
+                eprintln!("⚠️ Skipping stored proxy {}: {}", id, e);
+                continue;
+            }
+        };
+        let Ok(port) = u16::try_from(row.try_get::<i32, _>("port")?) else {
+            eprintln!("⚠️ Skipping stored proxy {}: invalid port", id);
+            continue;
+        };
+        let proxy = crate::proxy::Proxy::new(protocol, row.try_get("host")?, port, row.try_get("username")?, row.try_get("password")?);
+        if !row.try_get::<bool, _>("enabled")? {
+            proxy.healthy.store(false, std::sync::atomic::Ordering::Relaxed);
+            proxy.unhealthy_since.store(chrono::Utc::now().timestamp(), std::sync::atomic::Ordering::Relaxed);
+        }
+        proxies.push(proxy);
+    }
+    Ok(proxies)
+}
+
+/// Create the task row for a queued `job` with the given status, so it can be
+/// looked up before the worker finishes. No-op if the row already exists.
+pub async fn insert_task(pool: &PgPool, job: &crate::queue::CrawlJob, status: &str) -> Result<()> {
+    sqlx::query(
+        r#"
+        INSERT INTO tasks (id, keyword, engine, status, parent_task_id, metadata, batch_id)
+        VALUES ($1, $2, $3, $4, $5, $6, $7)
+        ON CONFLICT (id) DO NOTHING
+        "#,
+    )
+    .bind(&job.id)
+    .bind(&job.keyword)
+    .bind(&job.engine)
+    .bind(status)
+    .bind(&job.parent_task_id)
+    .bind(&job.metadata)
+    .bind(&job.batch_id)
+    .execute(pool)
+    .await?;
     Ok(())
 }
+
+/// Move a task to `status`, replacing its error message and failure category.
+/// Returns false if there is no such row.
+pub async fn update_task_status(
+    pool: &PgPool,
+    task_id: &str,
+    status: &str,
+    error_message: Option<&str>,
+    failure_reason: Option<&str>,
+) -> Result<bool> {
+    let result = sqlx::query("UPDATE tasks SET status = $1, error_message = $2, failure_reason = $3 WHERE id = $4")
+        .bind(status)
+        .bind(error_message)
+        .bind(failure_reason)
+        .bind(task_id)
+        .execute(pool)
+        .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+/// Columns recording a task's stored objects: (results_json, page_keys, thumbnail_key, results_key)
+pub type TaskObjectColumns = (Option<String>, Option<serde_json::Value>, Option<String>, Option<String>);
+
+/// Delete a task with its result rows and any baseline pointing at it.
+/// Returns the deleted row's [`TaskObjectColumns`], or None if it didn't exist.
+pub async fn delete_task(pool: &PgPool, task_id: &str) -> Result<Option<TaskObjectColumns>> {
+    let mut tx = pool.begin().await?;
+    let deleted: Option<TaskObjectColumns> =
+        sqlx::query_as("DELETE FROM tasks WHERE id = $1 RETURNING results_json, page_keys, thumbnail_key, results_key")
+            .bind(task_id)
+            .fetch_optional(&mut *tx)
+            .await?;
+    if deleted.is_some() {
+        sqlx::query("DELETE FROM task_results WHERE task_id = $1").bind(task_id).execute(&mut *tx).await?;
+        sqlx::query("DELETE FROM keyword_baselines WHERE task_id = $1").bind(task_id).execute(&mut *tx).await?;
+    }
+    tx.commit().await?;
+    Ok(deleted)
+}
+
+/// Make `task_id` the baseline for its keyword + engine, replacing any previous one.
+/// Returns (keyword, engine, previous baseline), or None if the task doesn't exist.
+pub async fn set_baseline(pool: &PgPool, task_id: &str) -> Result<Option<(String, String, Option<String>)>> {
+    let task: Option<(String, String)> = sqlx::query_as("SELECT keyword, engine FROM tasks WHERE id = $1")
+        .bind(task_id)
+        .fetch_optional(pool)
+        .await?;
+    let Some((keyword, engine)) = task else {
+        return Ok(None);
+    };
+
+    let previous = baseline_task_id(pool, &keyword, &engine).await?;
+    sqlx::query(
+        r#"
+        INSERT INTO keyword_baselines (keyword, engine, task_id, set_at)
+        VALUES ($1, $2, $3, CURRENT_TIMESTAMP)
+        ON CONFLICT (keyword, engine) DO UPDATE SET task_id = EXCLUDED.task_id, set_at = EXCLUDED.set_at
+        "#,
+    )
+    .bind(&keyword)
+    .bind(&engine)
+    .bind(task_id)
+    .execute(pool)
+    .await?;
+
+    Ok(Some((keyword, engine, previous)))
+}
+
+/// Baseline task for a keyword + engine, if one has been set
+pub async fn baseline_task_id(pool: &PgPool, keyword: &str, engine: &str) -> Result<Option<String>> {
+    let task_id = sqlx::query_scalar("SELECT task_id FROM keyword_baselines WHERE keyword = $1 AND engine = $2")
+        .bind(keyword)
+        .bind(engine)
+        .fetch_optional(pool)
+        .await?;
+    Ok(task_id)
+}
+
+/// Insert a task's SERP results as child rows using multi-row INSERTs.
+/// Rows are chunked by DB_BATCH_SIZE (default 500) to stay well under
+/// Postgres' bind-parameter limit.
+pub async fn insert_task_results(
+    conn: &mut PgConnection,
+    task_id: &str,
+    results: &[crate::crawler::SearchResult],
+) -> Result<u64> {
+    let batch_size: usize = crate::config::env_parse("DB_BATCH_SIZE", 500usize).max(1);
+    let mut inserted = 0;
+
+    for (chunk_idx, chunk) in results.chunks(batch_size).enumerate() {
+        let offset = chunk_idx * batch_size;
+        let mut builder: QueryBuilder<Postgres> =
+            QueryBuilder::new("INSERT INTO task_results (task_id, position, title, link, snippet) ");
+        builder.push_values(chunk.iter().enumerate(), |mut row, (i, result)| {
+            row.push_bind(task_id)
+                .push_bind((offset + i) as i32)
+                .push_bind(&result.title)
+                .push_bind(&result.link)
+                .push_bind(&result.snippet);
+        });
+        inserted += builder.build().execute(&mut *conn).await?.rows_affected();
+    }
+
+    Ok(inserted)
+}
@@ -0,0 +1,216 @@
+//! Parquet export of crawl results for analytics tooling (DuckDB, Spark, pandas).
+//!
+//! One row per SERP result, flattened with its task's keyword/engine/batch and
+//! page-level fields, written to object storage under
+//! `exports/parquet/date=YYYY-MM-DD/results.parquet` or
+//! `exports/parquet/batch=<batch_id>/results.parquet` (Hive-style partitions).
+
+use anyhow::Result;
+use arrow::array::{ArrayRef, Int32Array, StringArray, TimestampMillisecondArray};
+use arrow::datatypes::{DataType, Field, Schema, TimeUnit};
+use arrow::record_batch::RecordBatch;
+use chrono::{NaiveDate, NaiveDateTime};
+use parquet::arrow::ArrowWriter;
+use parquet::basic::Compression;
+use parquet::file::properties::WriterProperties;
+use sqlx::PgPool;
+use std::sync::Arc;
+
+use crate::storage::StorageManager;
+
+/// Which results to export
+#[derive(Debug, Clone, PartialEq)]
+pub enum ExportScope {
+    /// Tasks created on this UTC day
+    Day(NaiveDate),
+    Batch(String),
+}
+
+impl ExportScope {
+    /// Object key of the export file
+    pub fn object_key(&self) -> String {
+        match self {
+            ExportScope::Day(date) => format!("exports/parquet/date={}/results.parquet", date.format("%Y-%m-%d")),
+            ExportScope::Batch(batch_id) => {
+                let safe: String = batch_id
+                    .chars()
+                    .map(|c| if c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.') { c } else { '_' })
+                    .collect();
+                format!("exports/parquet/batch={}/results.parquet", safe)
+            }
+        }
+    }
+}
+
+/// Flat export row: one SERP result plus its task's fields
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct ExportRow {
+    pub task_id: String,
+    pub keyword: String,
+    pub engine: String,
+    pub batch_id: Option<String>,
+    pub crawled_at: Option<NaiveDateTime>,
+    pub position: i32,
+    pub url: String,
+    pub title: Option<String>,
+    pub snippet: Option<String>,
+    pub category: Option<String>,
+    pub sentiment: Option<String>,
+    /// Word count of the task's deep-extracted page
+    pub page_word_count: Option<i32>,
+}
+
+/// Outcome of one export run
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExportSummary {
+    pub key: String,
+    pub rows: usize,
+    pub bytes: usize,
+}
+
+const EXPORT_SELECT: &str = r#"
+    SELECT t.id AS task_id, t.keyword, t.engine, t.batch_id, t.created_at AS crawled_at,
+           r.position, r.link AS url, r.title, r.snippet, t.category, t.sentiment,
+           array_length(regexp_split_to_array(NULLIF(btrim(t.extracted_text), ''), '\s+'), 1) AS page_word_count
+    FROM task_results r JOIN tasks t ON t.id = r.task_id
+"#;
+
+pub async fn fetch_rows(pool: &PgPool, scope: &ExportScope) -> Result<Vec<ExportRow>> {
+    let rows = match scope {
+        ExportScope::Day(date) => {
+            let start = date.and_hms_opt(0, 0, 0).unwrap();
+            sqlx::query_as::<_, ExportRow>(&format!(
+                "{} WHERE t.created_at >= $1 AND t.created_at < $2 ORDER BY t.created_at, r.position",
+                EXPORT_SELECT
+            ))
+            .bind(start)
+            .bind(start + chrono::Duration::days(1))
+            .fetch_all(pool)
+            .await?
+        }
+        ExportScope::Batch(batch_id) => {
+            sqlx::query_as::<_, ExportRow>(&format!(
+                "{} WHERE t.batch_id = $1 ORDER BY t.created_at, r.position",
+                EXPORT_SELECT
+            ))
+            .bind(batch_id)
+            .fetch_all(pool)
+            .await?
+        }
+    };
+    Ok(rows)
+}
+
+pub fn export_schema() -> Arc<Schema> {
+    Arc::new(Schema::new(vec![
+        Field::new("task_id", DataType::Utf8, false),
+        Field::new("keyword", DataType::Utf8, false),
+        Field::new("engine", DataType::Utf8, false),
+        Field::new("batch_id", DataType::Utf8, true),
+        Field::new("crawled_at", DataType::Timestamp(TimeUnit::Millisecond, Some("UTC".into())), true),
+        Field::new("position", DataType::Int32, false),
+        Field::new("url", DataType::Utf8, false),
+        Field::new("title", DataType::Utf8, true),
+        Field::new("snippet", DataType::Utf8, true),
+        Field::new("category", DataType::Utf8, true),
+        Field::new("sentiment", DataType::Utf8, true),
+        Field::new("page_word_count", DataType::Int32, true),
+    ]))
+}
+
+pub fn build_record_batch(rows: &[ExportRow]) -> Result<RecordBatch> {
+    let strings = |f: fn(&ExportRow) -> Option<&str>| -> ArrayRef {
+        Arc::new(rows.iter().map(f).collect::<StringArray>())
+    };
+    let columns: Vec<ArrayRef> = vec![
+        strings(|r| Some(r.task_id.as_str())),
+        strings(|r| Some(r.keyword.as_str())),
+        strings(|r| Some(r.engine.as_str())),
+        strings(|r| r.batch_id.as_deref()),
+        Arc::new(
+            rows.iter()
+                .map(|r| r.crawled_at.map(|t| t.and_utc().timestamp_millis()))
+                .collect::<TimestampMillisecondArray>()
+                .with_timezone("UTC"),
+        ),
+        Arc::new(rows.iter().map(|r| Some(r.position)).collect::<Int32Array>()),
+        strings(|r| Some(r.url.as_str())),
+        strings(|r| r.title.as_deref()),
+        strings(|r| r.snippet.as_deref()),
+        strings(|r| r.category.as_deref()),
+        strings(|r| r.sentiment.as_deref()),
+        Arc::new(rows.iter().map(|r| r.page_word_count).collect::<Int32Array>()),
+    ];
+    Ok(RecordBatch::try_new(export_schema(), columns)?)
+}
+
+/// Snappy-compressed Parquet file containing `rows`
+pub fn to_parquet_bytes(rows: &[ExportRow]) -> Result<Vec<u8>> {
+    let batch = build_record_batch(rows)?;
+    let props = WriterProperties::builder().set_compression(Compression::SNAPPY).build();
+    let mut buf = Vec::new();
+    let mut writer = ArrowWriter::try_new(&mut buf, export_schema(), Some(props))?;
+    writer.write(&batch)?;
+    writer.close()?;
+    Ok(buf)
+}
+
+/// Query, encode and upload one export. Empty scopes still produce a (0-row) file
+/// so downstream jobs can tell "nothing crawled" from "export didn't run".
+pub async fn export_parquet(pool: &PgPool, storage: &StorageManager, scope: &ExportScope) -> Result<ExportSummary> {
+    let rows = fetch_rows(pool, scope).await?;
+    let bytes = to_parquet_bytes(&rows)?;
+    let key = scope.object_key();
+    storage.store_bytes(&key, bytes.clone(), "application/vnd.apache.parquet").await?;
+    println!("📦 Exported {} rows ({} bytes) to {}", rows.len(), bytes.len(), key);
+    Ok(ExportSummary { key, rows: rows.len(), bytes: bytes.len() })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use parquet::file::reader::{FileReader, SerializedFileReader};
+    use std::io::Write;
+
+    fn row(position: i32, batch_id: Option<&str>) -> ExportRow {
+        ExportRow {
+            task_id: "t1".to_string(),
+            keyword: "rust".to_string(),
+            engine: "bing".to_string(),
+            batch_id: batch_id.map(str::to_string),
+            crawled_at: NaiveDate::from_ymd_opt(2024, 6, 1).unwrap().and_hms_opt(12, 0, 0),
+            position,
+            url: format!("https://example.com/{}", position),
+            title: Some("Example".to_string()),
+            snippet: None,
+            category: None,
+            sentiment: Some("Neutral (0.50)".to_string()),
+            page_word_count: Some(420),
+        }
+    }
+
+    #[test]
+    fn test_parquet_roundtrip_row_count() {
+        let rows = vec![row(0, Some("b1")), row(1, None), row(2, None)];
+        let bytes = to_parquet_bytes(&rows).unwrap();
+        assert_eq!(&bytes[..4], b"PAR1");
+
+        let mut file = tempfile::tempfile().unwrap();
+        file.write_all(&bytes).unwrap();
+        let reader = SerializedFileReader::new(file).unwrap();
+        assert_eq!(reader.metadata().file_metadata().num_rows(), 3);
+        assert_eq!(reader.metadata().file_metadata().schema_descr().num_columns(), export_schema().fields().len());
+
+        assert!(to_parquet_bytes(&[]).is_ok());
+    }
+
+    #[test]
+    fn test_export_object_keys() {
+        let day = ExportScope::Day(NaiveDate::from_ymd_opt(2024, 6, 1).unwrap());
+        assert_eq!(day.object_key(), "exports/parquet/date=2024-06-01/results.parquet");
+        assert_eq!(
+            ExportScope::Batch("q3/../launch".to_string()).object_key(),
+            "exports/parquet/batch=q3_.._launch/results.parquet"
+        );
+    }
+}
@@ -1,7 +1,9 @@
 pub mod api;
 pub mod auth;
+pub mod config;
 pub mod crawler;
 pub mod db;
+pub mod export;
 pub mod ml;
 pub mod notifications;
 pub mod payments;
@@ -9,6 +11,8 @@ pub mod profiles;
 pub mod proxy;
 pub mod queue;
 pub mod scheduler;
+pub mod signing;
 pub mod stealth;
 pub mod storage;
+pub mod webhook;
 pub mod worker;
@@ -1,5 +1,5 @@
 
-use rust_crawler::{api, auth, crawler, db, ml, notifications, payments, profiles, proxy, queue, scheduler, stealth, storage, worker};
+use rust_crawler::{api, auth, config, crawler, db, ml, notifications, payments, profiles, proxy, queue, scheduler, signing, stealth, storage, worker};
 use axum::{
     routing::{get, post, delete},
     Router,
@@ -21,31 +21,78 @@ use tower_http::services::ServeDir;
 #[openapi(
     paths(
         api::trigger_crawl,
+        api::trigger_crawl_get,
         api::get_crawl_status,
         api::list_tasks,
+        api::search_tasks,
         api::list_proxies,
         api::add_proxy,
         api::remove_proxy,
         api::enable_proxy,
-        api::proxy_stats
+        api::reset_proxy_stats,
+        api::reset_all_proxy_stats,
+        api::proxy_stats,
+        api::set_proxy_strategy,
+        api::dashboard_feed,
+        api::batch_unique_results,
+        api::quota_stats,
+        api::pause_worker,
+        api::resume_worker,
+        api::worker_status,
+        api::verify_task,
+        api::set_baseline,
+        api::delete_task,
+        api::get_task_artifacts,
+        api::export_task_csv,
+        api::task_events,
+        api::upload_crawl_jobs,
+        api::export_parquet
     ),
     components(
         schemas(
             api::CrawlRequest, 
             api::CrawlResponse, 
+            api::FieldError,
+            api::ValidationErrorResponse,
             api::TaskResult, 
             api::TaskSummary,
+            api::TaskPage,
             api::AddProxyRequest,
             api::AddProxyResponse,
             api::RemoveProxyResponse,
+            api::ResetProxyStatsResponse,
+            api::DashboardFeed,
+            api::QueueStats,
+            api::ActivityStats,
+            api::VerifyResponse,
+            api::UniqueResult,
+            api::BatchUniqueResults,
+            api::WorkerStatusResponse,
+            api::QuotaStats,
+            api::BaselineResponse,
+            api::DeleteTaskResponse,
+            api::TaskArtifact,
+            api::TaskArtifactsResponse,
+            api::CrawlUploadForm,
+            api::UploadRowStatus,
+            api::CrawlUploadResponse,
+            api::ExportParquetRequest,
+            api::ExportParquetResponse,
+            crate::queue::TaskStatusEvent,
+            crate::queue::JobPriority,
             crate::proxy::ProxyInfo,
+            crate::stealth::StealthConfig,
             crate::proxy::ProxyStats,
+            crate::proxy::RotationStrategy,
+            api::SetProxyStrategyRequest,
+            api::SetProxyStrategyResponse,
             crate::proxy::ProxyProtocol
         )
     ),
     tags(
         (name = "crawler", description = "Crawler Management API"),
         (name = "proxy", description = "Proxy Management API"),
+        (name = "admin", description = "Operational Controls"),
         (name = "profiles", description = "User Profiles API"),
         (name = "payments", description = "Payment Processing API"),
         (name = "notifications", description = "Notifications API")
@@ -72,7 +119,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 .statement_cache_capacity(0);
             
             match PgPoolOptions::new()
-                .max_connections(5)
+                .max_connections(config::env_parse("DB_MAX_CONNECTIONS", 5))
                 .after_connect(|conn, _meta| Box::pin(async move {
                     use sqlx::Executor;
                     conn.execute("DEALLOCATE ALL").await.map(|_| ())
@@ -97,15 +144,60 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         }
     };
 
+    if let Err(e) = db::init_db(&pool).await {
+        eprintln!("⚠️ Failed to migrate tasks table: {}", e);
+    }
     let _ = profiles::init_profiles_table(&pool).await;
     let _ = payments::init_payments_table(&pool).await;
     let _ = notifications::init_notifications_table(&pool).await;
     println!("✅ All database tables initialized!");
 
-    let storage = storage::StorageManager::new().await.expect("Failed to init MinIO");
+    // Proxies added via the API (PROXY_LIST entries with the same host:port are replaced)
+    match db::load_proxies(&pool).await {
+        Ok(stored) => {
+            let count = stored.len();
+            for proxy in stored {
+                let id = proxy.id.clone();
+                if let Err(e) = proxy::PROXY_MANAGER.insert_proxy(proxy, true) {
+                    eprintln!("⚠️ Skipping stored proxy {}: {}", id, e);
+                }
+            }
+            if count > 0 {
+                println!("📡 Loaded {} stored proxies", count);
+            }
+        }
+        Err(e) => eprintln!("⚠️ Failed to load stored proxies: {}", e),
+    }
+
+    // Persist automatic disables/recoveries so a restart doesn't revive a dead proxy
+    let mut proxy_health = proxy::PROXY_MANAGER.subscribe_health();
+    let health_pool = pool.clone();
+    tokio::spawn(async move {
+        while let Some((proxy_id, healthy)) = proxy_health.recv().await {
+            if let Err(e) = db::set_proxy_enabled(&health_pool, &proxy_id, healthy).await {
+                eprintln!("⚠️ Failed to persist proxy {} state: {}", proxy_id, e);
+            }
+        }
+    });
+
+    // STORAGE_REQUIRED=false lets the app run without MinIO (HTML is then kept in Postgres only)
+    let storage_required = config::env_flag("STORAGE_REQUIRED", true);
+    let storage = match storage::StorageManager::new().await {
+        Ok(s) => Some(s),
+        Err(e) if !storage_required => {
+            eprintln!("⚠️ MinIO unavailable ({}). Continuing with object storage disabled.", e);
+            None
+        }
+        Err(e) => panic!("Failed to init MinIO: {}", e),
+    };
     let queue = queue::QueueManager::new().await.expect("Failed to init Redis");
 
-    let state = Arc::new(api::AppState { pool, storage, queue });
+    let state = Arc::new(api::AppState {
+        pool,
+        storage,
+        queue,
+        worker_paused: Arc::new(std::sync::atomic::AtomicBool::new(false)),
+    });
 
     // Start Background Worker
     let worker_state = state.clone();
@@ -125,14 +217,34 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         .merge(SwaggerUi::new("/rust-crawler-swagger").url("/api-docs/openapi.json", ApiDoc::openapi()))
         // Crawler endpoints
         .route("/crawl", post(api::trigger_crawl))
+        .route("/crawl", get(api::trigger_crawl_get))
+        .route("/crawl/upload", post(api::upload_crawl_jobs))
         .route("/crawl/:task_id", get(api::get_crawl_status))
         .route("/tasks", get(api::list_tasks))
+        .route("/search", get(api::search_tasks))
+        .route("/tasks/:task_id", axum::routing::delete(api::delete_task))
+        .route("/tasks/:task_id/artifacts", get(api::get_task_artifacts))
+        .route("/tasks/:task_id/export.csv", get(api::export_task_csv))
+        .route("/tasks/:task_id/verify", get(api::verify_task))
+        .route("/tasks/:task_id/set-baseline", post(api::set_baseline))
+        .route("/dashboard/feed", get(api::dashboard_feed))
+        .route("/events/tasks", get(api::task_events))
+        .route("/export/parquet", post(api::export_parquet))
+        .route("/stats/quota", get(api::quota_stats))
+        .route("/batch/:batch_id/unique-results", get(api::batch_unique_results))
         // Proxy management endpoints
         .route("/proxies", get(api::list_proxies))
         .route("/proxies", post(api::add_proxy))
         .route("/proxies/:proxy_id", axum::routing::delete(api::remove_proxy))
         .route("/proxies/:proxy_id/enable", post(api::enable_proxy))
+        .route("/proxies/:proxy_id/reset-stats", post(api::reset_proxy_stats))
+        .route("/proxies/reset-stats", post(api::reset_all_proxy_stats))
         .route("/proxies/stats", get(api::proxy_stats))
+        .route("/proxies/strategy", post(api::set_proxy_strategy))
+        // Worker control
+        .route("/admin/worker/pause", post(api::pause_worker))
+        .route("/admin/worker/resume", post(api::resume_worker))
+        .route("/admin/worker/status", get(api::worker_status))
         // Auth endpoints
         .route("/auth/status", get(auth::auth_status))
         // Profile endpoints
@@ -92,38 +92,9 @@ pub fn analyze_sentiment(text: &str) -> Option<String> {
     Some(format!("{} ({:.2})", label, score))
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn test_positive_sentiment() {
-        let text = "This product is amazing and wonderful. I love it so much. Best purchase ever!";
-        let result = analyze_sentiment(text);
-        assert!(result.is_some());
-        assert!(result.unwrap().starts_with("Positive"));
-    }
-
-    #[test]
-    fn test_negative_sentiment() {
-        let text = "This is terrible and horrible. I hate it. Worst experience ever, total failure.";
-        let result = analyze_sentiment(text);
-        assert!(result.is_some());
-        assert!(result.unwrap().starts_with("Negative"));
-    }
-
-    #[test]
-    fn test_neutral_sentiment() {
-        let text = "The item arrived on time. It works as described in the listing.";
-        let result = analyze_sentiment(text);
-        assert!(result.is_some());
-        assert!(result.unwrap().starts_with("Neutral"));
-    }
-}
-
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub struct Entity {
     pub text: String,
     pub label: String,
@@ -195,3 +166,191 @@ pub async fn classify_content_remote(text: &str) -> Option<String> {
         Err(_) => None
     }
 }
+
+// ============================================================================
+// Local entity mentions (regex + gazetteer, no sidecar)
+// ============================================================================
+
+use regex::Regex;
+
+/// Kind of entity found by `extract_entity_mentions`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EntityKind {
+    Organization,
+    Location,
+    Date,
+}
+
+impl EntityKind {
+    /// The sidecar's (spaCy) label for this kind
+    pub fn ner_label(self) -> &'static str {
+        match self {
+            EntityKind::Organization => "ORG",
+            EntityKind::Location => "GPE",
+            EntityKind::Date => "DATE",
+        }
+    }
+}
+
+/// One entity mentioned in a page's main text
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct EntityMention {
+    pub text: String,
+    pub kind: EntityKind,
+}
+
+static KNOWN_ORGANIZATIONS: &[&str] = &[
+    "Google", "Microsoft", "Apple", "Amazon", "Meta", "Facebook", "OpenAI", "IBM", "Intel",
+    "Nvidia", "Tesla", "Netflix", "Samsung", "Sony", "Oracle", "Adobe", "Twitter", "NASA",
+    "United Nations", "European Union", "World Health Organization", "World Bank", "NATO",
+    "Reuters", "BBC", "Mozilla", "Wikipedia", "GitHub",
+];
+
+static KNOWN_LOCATIONS: &[&str] = &[
+    "United States", "United Kingdom", "Canada", "Mexico", "Brazil", "Argentina", "France",
+    "Germany", "Spain", "Italy", "Netherlands", "Belgium", "Switzerland", "Sweden", "Norway",
+    "Poland", "Ukraine", "Russia", "China", "Japan", "India", "Indonesia", "Singapore",
+    "Thailand", "Vietnam", "Myanmar", "Australia", "New Zealand", "South Africa", "Nigeria",
+    "Egypt", "Kenya", "Turkey", "Israel", "Saudi Arabia", "Europe", "Asia", "Africa",
+    "North America", "South America", "New York", "London", "Paris", "Berlin", "Tokyo",
+    "Beijing", "Shanghai", "Mumbai", "Delhi", "Sydney", "Toronto", "San Francisco",
+    "Los Angeles", "Chicago", "Seattle", "Silicon Valley", "Hong Kong", "Dubai", "Moscow",
+    "Madrid", "Rome", "Amsterdam", "Bangkok", "Yangon", "Washington",
+];
+
+static GAZETTEER_REGEX: Lazy<Vec<(Regex, EntityKind)>> = Lazy::new(|| {
+    let alternation = |names: &[&str]| {
+        let mut names: Vec<&str> = names.to_vec();
+        names.sort_by_key(|n| std::cmp::Reverse(n.len())); // prefer "New York" over "York"
+        format!(r"\b(?:{})\b", names.iter().map(|n| regex::escape(n)).collect::<Vec<_>>().join("|"))
+    };
+    vec![
+        (Regex::new(&alternation(KNOWN_ORGANIZATIONS)).unwrap(), EntityKind::Organization),
+        (Regex::new(&alternation(KNOWN_LOCATIONS)).unwrap(), EntityKind::Location),
+    ]
+});
+
+/// Capitalized phrases carrying an organization suffix or prefix
+/// ("Acme Widgets Inc.", "University of Oxford", "Mozilla Foundation")
+static ORG_PATTERN_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(concat!(
+        r"\b(?:(?:[A-Z][\w&'-]*\s+){1,4}(?:Inc\.?|Corp\.?|Corporation|Ltd\.?|LLC|GmbH|PLC|Company|Group|Foundation|",
+        r"Institute|Association|University|College|Bank|Agency|Ministry|Council|Laboratories|Labs)",
+        r"|(?:University|Ministry|Bank|Department|Institute) of(?:\s+[A-Z][\w'-]*){1,3})\b\.?"
+    ))
+    .unwrap()
+});
+
+static DATE_REGEX: Lazy<Regex> = Lazy::new(|| {
+    let month = r"(?:Jan(?:uary)?|Feb(?:ruary)?|Mar(?:ch)?|Apr(?:il)?|May|June?|July?|Aug(?:ust)?|Sep(?:t(?:ember)?)?|Oct(?:ober)?|Nov(?:ember)?|Dec(?:ember)?)";
+    Regex::new(&format!(
+        r"\b(?:\d{{4}}-\d{{2}}-\d{{2}}|{m}\.?\s+\d{{1,2}}(?:st|nd|rd|th)?,?\s+\d{{4}}|\d{{1,2}}(?:st|nd|rd|th)?\s+{m}\.?,?\s+\d{{4}}|{m}\s+\d{{4}}|\d{{1,2}}/\d{{1,2}}/\d{{4}})\b",
+        m = month
+    ))
+    .unwrap()
+});
+
+/// Organizations, locations and dates mentioned in `text`, deduplicated in order of
+/// first appearance and capped at `max`. Heuristic (gazetteer + patterns, English-centric):
+/// good for knowledge-graph seeding, not a replacement for the sidecar's model-based NER.
+pub fn extract_entity_mentions(text: &str, max: usize) -> Vec<EntityMention> {
+    let mut found: Vec<(usize, EntityMention)> = Vec::new();
+    let mut push = |start: usize, raw: &str, kind: EntityKind| {
+        let text = raw.split_whitespace().collect::<Vec<_>>().join(" ");
+        let text = text.trim_end_matches('.').to_string();
+        // "Google" inside an already-found "Google Foundation" etc. is not a separate mention
+        if found.iter().any(|(_, e)| e.kind == kind && (e.text == text || (kind == EntityKind::Organization && e.text.contains(&text)))) {
+            return;
+        }
+        found.push((start, EntityMention { text, kind }));
+    };
+
+    for m in ORG_PATTERN_REGEX.find_iter(text) {
+        push(m.start(), m.as_str(), EntityKind::Organization);
+    }
+    for (regex, kind) in GAZETTEER_REGEX.iter() {
+        for m in regex.find_iter(text) {
+            push(m.start(), m.as_str(), *kind);
+        }
+    }
+    for m in DATE_REGEX.find_iter(text) {
+        push(m.start(), m.as_str(), EntityKind::Date);
+    }
+
+    found.sort_by_key(|(start, _)| *start);
+    found.into_iter().map(|(_, e)| e).take(max).collect()
+}
+
+/// Sidecar entities followed by the local mentions it missed, deduplicated by
+/// (case-insensitive text, label); local kinds are mapped to the sidecar's labels
+pub fn merge_entities(remote: Vec<Entity>, local: &[EntityMention]) -> Vec<Entity> {
+    let mut seen = HashSet::new();
+    let local = local.iter().map(|m| Entity { text: m.text.clone(), label: m.kind.ner_label().to_string() });
+    remote
+        .into_iter()
+        .chain(local)
+        .filter(|e| seen.insert((e.text.to_lowercase(), e.label.clone())))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_positive_sentiment() {
+        let text = "This product is amazing and wonderful. I love it so much. Best purchase ever!";
+        let result = analyze_sentiment(text);
+        assert!(result.is_some());
+        assert!(result.unwrap().starts_with("Positive"));
+    }
+
+    #[test]
+    fn test_negative_sentiment() {
+        let text = "This is terrible and horrible. I hate it. Worst experience ever, total failure.";
+        let result = analyze_sentiment(text);
+        assert!(result.is_some());
+        assert!(result.unwrap().starts_with("Negative"));
+    }
+
+    #[test]
+    fn test_neutral_sentiment() {
+        let text = "The item arrived on time. It works as described in the listing.";
+        let result = analyze_sentiment(text);
+        assert!(result.is_some());
+        assert!(result.unwrap().starts_with("Neutral"));
+    }
+
+    #[test]
+    fn test_extract_entity_mentions() {
+        let text = "On March 3, 2024 the University of Oxford and Acme Widgets Inc. opened a lab in New York. \
+                    Google joined on 2024-04-01, and Acme Widgets Inc. later expanded to Germany in May 2025.";
+        let entities = extract_entity_mentions(text, 50);
+        let of = |kind| entities.iter().filter(|e| e.kind == kind).map(|e| e.text.as_str()).collect::<Vec<_>>();
+
+        assert_eq!(of(EntityKind::Organization), vec!["University of Oxford", "Acme Widgets Inc", "Google"]);
+        assert_eq!(of(EntityKind::Location), vec!["New York", "Germany"]);
+        assert_eq!(of(EntityKind::Date), vec!["March 3, 2024", "2024-04-01", "May 2025"]);
+        assert_eq!(entities[0].kind, EntityKind::Date);
+        assert_eq!(extract_entity_mentions(text, 2).len(), 2);
+    }
+
+    #[test]
+    fn test_merge_entities() {
+        let remote = vec![
+            Entity { text: "Google".to_string(), label: "ORG".to_string() },
+            Entity { text: "Ada Lovelace".to_string(), label: "PERSON".to_string() },
+            Entity { text: "google".to_string(), label: "ORG".to_string() },
+        ];
+        let local = vec![
+            EntityMention { text: "Google".to_string(), kind: EntityKind::Organization },
+            EntityMention { text: "Germany".to_string(), kind: EntityKind::Location },
+            EntityMention { text: "May 2025".to_string(), kind: EntityKind::Date },
+        ];
+        let merged = merge_entities(remote, &local);
+        let pairs: Vec<(&str, &str)> = merged.iter().map(|e| (e.text.as_str(), e.label.as_str())).collect();
+        assert_eq!(pairs, vec![("Google", "ORG"), ("Ada Lovelace", "PERSON"), ("Germany", "GPE"), ("May 2025", "DATE")]);
+        assert_eq!(merge_entities(Vec::new(), &local).len(), 3);
+    }
+}
@@ -10,6 +10,8 @@ use sqlx::{PgPool, FromRow};
 use uuid::Uuid;
 use utoipa::ToSchema;
 use std::sync::Arc;
+use axum::async_trait;
+use once_cell::sync::Lazy;
 use crate::api::AppState;
 
 #[derive(Debug, Serialize, Deserialize, Clone, ToSchema, FromRow)]
@@ -82,6 +84,144 @@ async fn send_email_via_resend(to: &str, subject: &str, body: &str) -> Result<St
     }
 }
 
+// ============================================================================
+// Job Result Notifiers (Slack / Email)
+// ============================================================================
+
+/// Outcome of a finished crawl job, handed to the configured notifier
+#[derive(Debug, Clone, Serialize)]
+pub struct JobEvent {
+    pub task_id: String,
+    pub keyword: String,
+    pub engine: String,
+    /// "completed" or "failed"
+    pub status: String,
+    pub result_count: usize,
+    pub error: Option<String>,
+}
+
+impl JobEvent {
+    /// Link to the task status endpoint (PUBLIC_BASE_URL, defaults to localhost)
+    pub fn task_link(&self) -> String {
+        let base = std::env::var("PUBLIC_BASE_URL").unwrap_or_else(|_| "http://localhost:3000".to_string());
+        format!("{}/crawl/{}", base.trim_end_matches('/'), self.task_id)
+    }
+
+    pub fn is_failure(&self) -> bool {
+        self.status == "failed"
+    }
+
+    fn subject(&self) -> String {
+        format!("Crawl {} for '{}'", self.status, self.keyword)
+    }
+
+    fn body(&self) -> String {
+        let mut body = format!(
+            "Keyword: {}\nEngine: {}\nStatus: {}\nResults: {}\nTask: {}",
+            self.keyword, self.engine, self.status, self.result_count, self.task_link()
+        );
+        if let Some(ref e) = self.error {
+            body.push_str(&format!("\nError: {}", e));
+        }
+        body
+    }
+}
+
+/// Sink for job completion/failure events. The default implementation does nothing.
+#[async_trait]
+pub trait JobNotifier: Send + Sync {
+    async fn notify(&self, _event: &JobEvent) {}
+}
+
+/// Used when no notification channel is configured
+pub struct NoopNotifier;
+
+#[async_trait]
+impl JobNotifier for NoopNotifier {}
+
+/// Posts a message to a Slack incoming webhook
+pub struct SlackNotifier {
+    webhook_url: String,
+}
+
+#[async_trait]
+impl JobNotifier for SlackNotifier {
+    async fn notify(&self, event: &JobEvent) {
+        let icon = if event.is_failure() { "❌" } else { "✅" };
+        let payload = serde_json::json!({
+            "text": format!("{} *{}*\n{}", icon, event.subject(), event.body())
+        });
+
+        match reqwest::Client::new().post(&self.webhook_url).json(&payload).send().await {
+            Ok(resp) if resp.status().is_success() => {}
+            Ok(resp) => eprintln!("⚠️ [Notify] Slack webhook returned {}", resp.status()),
+            Err(e) => eprintln!("⚠️ [Notify] Slack webhook failed: {}", e),
+        }
+    }
+}
+
+/// Emails the job outcome via Resend
+pub struct EmailNotifier {
+    to: String,
+}
+
+#[async_trait]
+impl JobNotifier for EmailNotifier {
+    async fn notify(&self, event: &JobEvent) {
+        if let Err(e) = send_email_via_resend(&self.to, &event.subject(), &event.body()).await {
+            eprintln!("⚠️ [Notify] Email skipped: {}", e);
+        }
+    }
+}
+
+/// Fans an event out to every configured channel, optionally only for failures
+pub struct MultiNotifier {
+    notifiers: Vec<Box<dyn JobNotifier>>,
+    failures_only: bool,
+}
+
+#[async_trait]
+impl JobNotifier for MultiNotifier {
+    async fn notify(&self, event: &JobEvent) {
+        if self.failures_only && !event.is_failure() {
+            return;
+        }
+        for notifier in &self.notifiers {
+            notifier.notify(event).await;
+        }
+    }
+}
+
+/// Global job notifier, configured from the environment:
+/// - `SLACK_WEBHOOK_URL`: Slack incoming webhook
+/// - `NOTIFY_EMAIL_TO`: recipient address (sent through Resend, needs `RESEND_API_KEY`)
+/// - `NOTIFY_ON`: `all` (default) or `failure`
+pub static JOB_NOTIFIER: Lazy<Box<dyn JobNotifier>> = Lazy::new(|| {
+    let mut notifiers: Vec<Box<dyn JobNotifier>> = Vec::new();
+
+    if let Ok(webhook_url) = std::env::var("SLACK_WEBHOOK_URL") {
+        if !webhook_url.trim().is_empty() {
+            notifiers.push(Box::new(SlackNotifier { webhook_url }));
+        }
+    }
+    if let Ok(to) = std::env::var("NOTIFY_EMAIL_TO") {
+        if !to.trim().is_empty() {
+            notifiers.push(Box::new(EmailNotifier { to }));
+        }
+    }
+
+    if notifiers.is_empty() {
+        return Box::new(NoopNotifier);
+    }
+
+    let failures_only = std::env::var("NOTIFY_ON")
+        .map(|v| v.eq_ignore_ascii_case("failure"))
+        .unwrap_or(false);
+    println!("🔔 Job notifications enabled ({} channel(s), failures only: {})", notifiers.len(), failures_only);
+
+    Box::new(MultiNotifier { notifiers, failures_only })
+});
+
 use crate::auth::AuthUser;
 
 pub async fn send_notification(
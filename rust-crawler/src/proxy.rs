@@ -4,12 +4,16 @@
 //! - Authenticated proxies (user:pass@host:port)
 //! - Multiple rotation strategies
 //! - Health tracking with automatic failure recovery
+//! - Per-proxy concurrency cap (PROXY_MAX_CONCURRENT)
+//! - Per-engine cooldowns (a proxy blocked by Google can still serve Bing)
+//! - Concurrent health-check probes with bounded parallelism
 //! - Runtime management
 
 use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
 use std::sync::atomic::{AtomicBool, AtomicI64, AtomicU32, AtomicU64, Ordering};
-use std::sync::{Arc, RwLock};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, RwLock};
 use std::time::{SystemTime, UNIX_EPOCH};
 use utoipa::ToSchema;
 
@@ -21,21 +25,32 @@ pub static PROXY_MANAGER: Lazy<ProxyManager> = Lazy::new(|| {
         .ok()
         .and_then(|s| s.parse().ok())
         .unwrap_or(3);
+    // 0 = unlimited simultaneous uses per proxy
+    let max_concurrent: u32 = crate::config::env_parse("PROXY_MAX_CONCURRENT", 0);
 
-    let strategy = match strategy_str.to_lowercase().as_str() {
-        "leastused" => RotationStrategy::LeastUsed,
-        "random" => RotationStrategy::Random,
-        "weighted" => RotationStrategy::Weighted,
-        _ => RotationStrategy::RoundRobin,
-    };
+    let strategy = RotationStrategy::parse(&strategy_str).unwrap_or(RotationStrategy::RoundRobin);
 
-    let proxies: Vec<Arc<Proxy>> = proxies_str
+    let mut proxies: Vec<Arc<Proxy>> = Vec::new();
+    for proxy in proxies_str
         .split(',')
         .map(|s| s.trim())
         .filter(|s| !s.is_empty())
-        .filter_map(|s| Proxy::parse(s).ok())
-        .map(Arc::new)
-        .collect();
+        .filter_map(|s| match Proxy::parse(s).and_then(|p| p.check_chrome_support().map(|_| p)) {
+            Ok(p) => Some(p),
+            Err(e) => {
+                println!("⚠️ Skipping PROXY_LIST entry: {}", e);
+                None
+            }
+        })
+    {
+        // Duplicate entries would be over-selected and skew stats; last one wins
+        if let Some(pos) = proxies.iter().position(|p| p.id == proxy.id) {
+            println!("⚠️ Duplicate proxy {} in PROXY_LIST, keeping the last entry", proxy.id);
+            proxies[pos] = Arc::new(proxy);
+        } else {
+            proxies.push(Arc::new(proxy));
+        }
+    }
 
     if proxies.is_empty() {
         println!("📡 No proxies configured. Using direct connection.");
@@ -43,9 +58,16 @@ pub static PROXY_MANAGER: Lazy<ProxyManager> = Lazy::new(|| {
         println!("📡 Loaded {} proxies with {:?} rotation strategy.", proxies.len(), strategy);
     }
 
-    ProxyManager::new(proxies, strategy, max_fails)
+    ProxyManager::new(proxies, strategy, max_fails).with_max_concurrent(max_concurrent)
 });
 
+fn unix_now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0)
+}
+
 /// Proxy protocol types
 #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "lowercase")]
@@ -61,8 +83,29 @@ impl Default for ProxyProtocol {
     }
 }
 
+impl ProxyProtocol {
+    /// URL scheme, also the value stored in the `proxies` table
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ProxyProtocol::Socks5 => "socks5",
+            ProxyProtocol::Https => "https",
+            ProxyProtocol::Http => "http",
+        }
+    }
+
+    pub fn from_scheme(scheme: &str) -> Result<Self, String> {
+        match scheme.to_ascii_lowercase().as_str() {
+            "socks5" => Ok(ProxyProtocol::Socks5),
+            "https" => Ok(ProxyProtocol::Https),
+            "http" => Ok(ProxyProtocol::Http),
+            other => Err(format!("Unsupported proxy protocol: {} (expected http, https or socks5)", other)),
+        }
+    }
+}
+
 /// Rotation strategy for proxy selection
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
 pub enum RotationStrategy {
     /// Simple round-robin rotation
     RoundRobin,
@@ -70,8 +113,41 @@ pub enum RotationStrategy {
     LeastUsed,
     /// Random selection from healthy proxies
     Random,
-    /// Higher success rate = higher priority
-    Weighted,
+    /// Random selection with probability proportional to success rate
+    WeightedBySuccessRate,
+    /// Lowest health-check latency; round-robin until a health check has measured one
+    LeastLatency,
+}
+
+impl RotationStrategy {
+    /// Parse a PROXY_ROTATION value (case, `_` and `-` are ignored)
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.to_lowercase().replace(['_', '-'], "").as_str() {
+            "roundrobin" => Some(RotationStrategy::RoundRobin),
+            "leastused" => Some(RotationStrategy::LeastUsed),
+            "random" => Some(RotationStrategy::Random),
+            "weighted" | "weightedbysuccessrate" => Some(RotationStrategy::WeightedBySuccessRate),
+            "leastlatency" => Some(RotationStrategy::LeastLatency),
+            _ => None,
+        }
+    }
+}
+
+/// Index picked from `weights` by a roll `r` in [0, 1): each index wins with
+/// probability weight / total. All-zero weights fall back to a uniform pick.
+fn pick_weighted(weights: &[f64], r: f64) -> usize {
+    let total: f64 = weights.iter().sum();
+    if total <= 0.0 {
+        return ((r * weights.len() as f64) as usize).min(weights.len().saturating_sub(1));
+    }
+    let mut target = r * total;
+    for (i, w) in weights.iter().enumerate() {
+        if target < *w {
+            return i;
+        }
+        target -= w;
+    }
+    weights.len() - 1
 }
 
 /// Individual proxy configuration with stats
@@ -98,6 +174,14 @@ pub struct Proxy {
     pub success_count: AtomicU64,
     /// Total requests made
     pub total_requests: AtomicU64,
+    /// Leases currently handed out (see [`ProxyLease`])
+    pub in_flight: AtomicU32,
+    /// When the proxy was auto-disabled (unix seconds, 0 = healthy)
+    pub unhealthy_since: AtomicI64,
+    /// Round-trip time of the last passing health check (ms, 0 = never measured)
+    pub latency_ms: AtomicU64,
+    /// Engine -> unix seconds until which this proxy is blocked on that engine
+    pub engine_blocks: Mutex<HashMap<String, i64>>,
 }
 
 impl Proxy {
@@ -108,18 +192,14 @@ impl Proxy {
     pub fn parse(s: &str) -> Result<Self, String> {
         let mut s = s.trim();
         
-        // Extract protocol if present
-        let protocol = if s.starts_with("socks5://") {
-            s = &s[9..];
-            ProxyProtocol::Socks5
-        } else if s.starts_with("https://") {
-            s = &s[8..];
-            ProxyProtocol::Https
-        } else if s.starts_with("http://") {
-            s = &s[7..];
-            ProxyProtocol::Http
-        } else {
-            ProxyProtocol::Http
+        // Extract protocol if present (scheme is case-insensitive)
+        let protocol = match s.find("://") {
+            Some(pos) => {
+                let protocol = ProxyProtocol::from_scheme(&s[..pos])?;
+                s = &s[pos + 3..];
+                protocol
+            }
+            None => ProxyProtocol::Http,
         };
 
         // Check for auth (user:pass@)
@@ -156,11 +236,13 @@ impl Proxy {
             return Err(format!("Missing port in proxy address: {}", host_port));
         };
 
-        // Generate unique ID
-        let id = format!("{}:{}", host, port);
+        Ok(Self::new(protocol, host, port, username, password))
+    }
 
-        Ok(Self {
-            id,
+    /// A proxy with fresh stats; its id is `host:port`
+    pub fn new(protocol: ProxyProtocol, host: String, port: u16, username: Option<String>, password: Option<String>) -> Self {
+        Self {
+            id: format!("{}:{}", host, port),
             host,
             port,
             username,
@@ -171,17 +253,25 @@ impl Proxy {
             last_used: AtomicI64::new(0),
             success_count: AtomicU64::new(0),
             total_requests: AtomicU64::new(0),
-        })
+            in_flight: AtomicU32::new(0),
+            unhealthy_since: AtomicI64::new(0),
+            latency_ms: AtomicU64::new(0),
+            engine_blocks: Mutex::new(HashMap::new()),
+        }
     }
 
     /// Get the Chrome proxy argument (--proxy-server=...)
     pub fn to_chrome_arg(&self) -> String {
-        let protocol = match self.protocol {
-            ProxyProtocol::Socks5 => "socks5",
-            ProxyProtocol::Https => "https",
-            ProxyProtocol::Http => "http",
-        };
-        format!("{}://{}:{}", protocol, self.host, self.port)
+        format!("{}://{}:{}", self.protocol.as_str(), self.host, self.port)
+    }
+
+    /// reqwest proxy for direct probes (SOCKS5 needs reqwest's `socks` feature and errors here)
+    pub fn to_reqwest_proxy(&self) -> reqwest::Result<reqwest::Proxy> {
+        let proxy = reqwest::Proxy::all(self.to_chrome_arg())?;
+        Ok(match (&self.username, &self.password) {
+            (Some(user), Some(pass)) => proxy.basic_auth(user, pass),
+            _ => proxy,
+        })
     }
 
     /// Check if proxy requires authentication
@@ -189,6 +279,36 @@ impl Proxy {
         self.username.is_some() && self.password.is_some()
     }
 
+    /// Chrome has no way to authenticate to a SOCKS proxy (credentials in
+    /// --proxy-server are ignored and the auth extension only answers HTTP 407s),
+    /// so an authenticated SOCKS5 proxy would silently fail every request
+    pub fn check_chrome_support(&self) -> Result<(), String> {
+        if self.protocol == ProxyProtocol::Socks5 && self.requires_auth() {
+            return Err(format!(
+                "SOCKS5 proxy {} uses authentication, which Chrome does not support; use an unauthenticated SOCKS5 proxy or an HTTP proxy",
+                self.id
+            ));
+        }
+        Ok(())
+    }
+
+    /// Is this proxy cooling down for `engine` at `now` (unix seconds)?
+    pub fn is_blocked_for(&self, engine: &str, now: i64) -> bool {
+        self.blocked_until(engine).is_some_and(|until| until > now)
+    }
+
+    fn blocked_until(&self, engine: &str) -> Option<i64> {
+        self.engine_blocks.lock().ok().and_then(|b| b.get(engine).copied())
+    }
+
+    /// Active engine blocks (engine -> blocked-until unix seconds)
+    pub fn active_blocks(&self, now: i64) -> HashMap<String, i64> {
+        self.engine_blocks
+            .lock()
+            .map(|b| b.iter().filter(|(_, until)| **until > now).map(|(e, u)| (e.clone(), *u)).collect())
+            .unwrap_or_default()
+    }
+
     /// Get success rate (0.0 - 1.0)
     pub fn success_rate(&self) -> f64 {
         let total = self.total_requests.load(Ordering::Relaxed);
@@ -198,6 +318,11 @@ impl Proxy {
         let success = self.success_count.load(Ordering::Relaxed);
         success as f64 / total as f64
     }
+
+    /// Last measured health-check latency, if any
+    pub fn latency(&self) -> Option<u64> {
+        Some(self.latency_ms.load(Ordering::Relaxed)).filter(|ms| *ms > 0)
+    }
 }
 
 /// Serializable proxy info for API responses
@@ -216,6 +341,12 @@ pub struct ProxyInfo {
     pub success_count: u64,
     pub total_requests: u64,
     pub success_rate: f64,
+    /// Browsers currently using this proxy
+    pub in_flight: u32,
+    /// Engines this proxy is cooling down on (engine -> blocked-until unix seconds)
+    pub blocked_engines: HashMap<String, i64>,
+    /// Round-trip time of the last passing health check (None until one passes)
+    pub latency_ms: Option<u64>,
 }
 
 impl From<&Proxy> for ProxyInfo {
@@ -231,6 +362,9 @@ impl From<&Proxy> for ProxyInfo {
             success_count: p.success_count.load(Ordering::Relaxed),
             total_requests: p.total_requests.load(Ordering::Relaxed),
             success_rate: p.success_rate(),
+            in_flight: p.in_flight.load(Ordering::Relaxed),
+            blocked_engines: p.active_blocks(unix_now()),
+            latency_ms: p.latency(),
         }
     }
 }
@@ -243,14 +377,58 @@ pub struct ProxyStats {
     pub total_requests: u64,
     pub total_successes: u64,
     pub overall_success_rate: f64,
+    /// Mean health-check latency over proxies that have one
+    pub avg_latency_ms: Option<u64>,
+    /// Current selection strategy
+    pub strategy: RotationStrategy,
+}
+
+/// A proxy handed out by [`ProxyManager::get_next_proxy`].
+/// Counts towards the proxy's in-flight usage until dropped, so keep it
+/// alive for as long as the browser/connection using the proxy.
+pub struct ProxyLease {
+    proxy: Arc<Proxy>,
+}
+
+impl ProxyLease {
+    /// Take a slot on `proxy` unless it already has `max_concurrent` leases (0 = unlimited)
+    fn try_acquire(proxy: Arc<Proxy>, max_concurrent: u32) -> Option<Self> {
+        proxy
+            .in_flight
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| {
+                (max_concurrent == 0 || n < max_concurrent).then_some(n + 1)
+            })
+            .ok()?;
+        Some(Self { proxy })
+    }
+}
+
+impl std::ops::Deref for ProxyLease {
+    type Target = Proxy;
+
+    fn deref(&self) -> &Proxy {
+        &self.proxy
+    }
+}
+
+impl Drop for ProxyLease {
+    fn drop(&mut self) {
+        self.proxy.in_flight.fetch_sub(1, Ordering::SeqCst);
+    }
 }
 
 /// Proxy manager with rotation and health tracking
 pub struct ProxyManager {
     proxies: RwLock<Vec<Arc<Proxy>>>,
     current_index: AtomicU64,
-    strategy: RotationStrategy,
+    strategy: RwLock<RotationStrategy>,
     max_fail_count: u32,
+    /// Max simultaneous leases per proxy (0 = unlimited)
+    max_concurrent: u32,
+    /// Session key (e.g. job id) -> proxy id, see [`get_sticky_proxy`](Self::get_sticky_proxy)
+    sticky: Mutex<HashMap<String, String>>,
+    /// See [`subscribe_health`](Self::subscribe_health)
+    health_events: Mutex<Option<tokio::sync::mpsc::UnboundedSender<(String, bool)>>>,
 }
 
 impl ProxyManager {
@@ -259,13 +437,70 @@ impl ProxyManager {
         Self {
             proxies: RwLock::new(proxies),
             current_index: AtomicU64::new(0),
-            strategy,
+            strategy: RwLock::new(strategy),
             max_fail_count,
+            max_concurrent: 0,
+            sticky: Mutex::new(HashMap::new()),
+            health_events: Mutex::new(None),
         }
     }
 
-    /// Get the next proxy based on rotation strategy
-    pub fn get_next_proxy(&self) -> Option<Arc<Proxy>> {
+    /// Receive `(proxy_id, healthy)` whenever a proxy is disabled after repeated
+    /// failures or brought back by a success/health probe, so the change can be
+    /// persisted. Only the latest subscriber receives events.
+    pub fn subscribe_health(&self) -> tokio::sync::mpsc::UnboundedReceiver<(String, bool)> {
+        let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+        if let Ok(mut events) = self.health_events.lock() {
+            *events = Some(tx);
+        }
+        rx
+    }
+
+    fn notify_health(&self, proxy_id: &str, healthy: bool) {
+        if let Ok(events) = self.health_events.lock() {
+            if let Some(tx) = events.as_ref() {
+                let _ = tx.send((proxy_id.to_string(), healthy));
+            }
+        }
+    }
+
+    /// Cap simultaneous leases per proxy (0 = unlimited)
+    pub fn with_max_concurrent(mut self, max_concurrent: u32) -> Self {
+        self.max_concurrent = max_concurrent;
+        self
+    }
+
+    /// Current selection strategy
+    pub fn strategy(&self) -> RotationStrategy {
+        self.strategy.read().map(|s| *s).unwrap_or(RotationStrategy::RoundRobin)
+    }
+
+    /// Switch the selection strategy at runtime. Returns the previous one.
+    pub fn set_strategy(&self, strategy: RotationStrategy) -> RotationStrategy {
+        let previous = match self.strategy.write() {
+            Ok(mut current) => std::mem::replace(&mut *current, strategy),
+            Err(_) => return strategy,
+        };
+        println!("🔀 Proxy rotation strategy: {:?} -> {:?}", previous, strategy);
+        previous
+    }
+
+    fn has_capacity(&self, proxy: &Proxy) -> bool {
+        self.max_concurrent == 0 || proxy.in_flight.load(Ordering::SeqCst) < self.max_concurrent
+    }
+
+    /// Get the next proxy based on rotation strategy, skipping proxies already
+    /// at PROXY_MAX_CONCURRENT. Returns `None` when every candidate is saturated;
+    /// callers with proxies configured should wait for a lease to drop rather
+    /// than falling back to a direct connection.
+    pub fn get_next_proxy(&self) -> Option<ProxyLease> {
+        self.get_next_proxy_for(None)
+    }
+
+    /// Like [`get_next_proxy`](Self::get_next_proxy), but skips proxies cooling
+    /// down on `engine`. If every healthy proxy is blocked there, the one whose
+    /// block expires soonest is used.
+    pub fn get_next_proxy_for(&self, engine: Option<&str>) -> Option<ProxyLease> {
         let proxies = self.proxies.read().ok()?;
         if proxies.is_empty() {
             return None;
@@ -279,14 +514,41 @@ impl ProxyManager {
 
         if healthy.is_empty() {
             println!("⚠️ All proxies unhealthy! Trying first proxy anyway...");
-            return proxies.first().cloned();
+            return proxies.first().cloned().and_then(|p| ProxyLease::try_acquire(p, self.max_concurrent));
         }
 
-        let proxy = match self.strategy {
-            RotationStrategy::RoundRobin => {
-                let idx = self.current_index.fetch_add(1, Ordering::SeqCst) as usize % healthy.len();
-                healthy[idx].clone()
+        // Skip proxies blocked on this engine
+        let healthy = match engine {
+            Some(engine) => {
+                let now = unix_now();
+                let unblocked: Vec<_> = healthy.iter().copied().filter(|p| !p.is_blocked_for(engine, now)).collect();
+                if unblocked.is_empty() {
+                    println!("⚠️ All proxies blocked on {}, using the one that recovers first", engine);
+                    healthy
+                        .iter()
+                        .copied()
+                        .min_by_key(|p| p.blocked_until(engine).unwrap_or(0))
+                        .into_iter()
+                        .collect()
+                } else {
+                    unblocked
+                }
             }
+            None => healthy,
+        };
+
+        // Skip proxies already at the concurrency cap
+        let healthy: Vec<_> = healthy.iter().copied().filter(|p| self.has_capacity(p)).collect();
+        if healthy.is_empty() {
+            return None;
+        }
+
+        let round_robin = || {
+            let idx = self.current_index.fetch_add(1, Ordering::SeqCst) as usize % healthy.len();
+            healthy[idx].clone()
+        };
+        let proxy = match self.strategy() {
+            RotationStrategy::RoundRobin => round_robin(),
             RotationStrategy::LeastUsed => {
                 healthy
                     .iter()
@@ -298,29 +560,72 @@ impl ProxyManager {
                 use rand::seq::SliceRandom;
                 healthy.choose(&mut rand::thread_rng())?.clone().clone()
             }
-            RotationStrategy::Weighted => {
-                // Simple weighted selection: pick highest success rate
-                healthy
-                    .iter()
-                    .max_by(|a, b| {
-                        a.success_rate()
-                            .partial_cmp(&b.success_rate())
-                            .unwrap_or(std::cmp::Ordering::Equal)
-                    })
-                    .cloned()?
-                    .clone()
+            RotationStrategy::WeightedBySuccessRate => {
+                let weights: Vec<f64> = healthy.iter().map(|p| p.success_rate()).collect();
+                healthy[pick_weighted(&weights, rand::random::<f64>())].clone()
+            }
+            RotationStrategy::LeastLatency => {
+                // Unmeasured proxies rank last
+                match healthy.iter().filter_map(|p| p.latency().map(|ms| (ms, *p))).min_by_key(|(ms, _)| *ms) {
+                    Some((_, proxy)) => proxy.clone(),
+                    None => round_robin(),
+                }
             }
         };
 
+        // A concurrent caller may have taken the last slot since the filter above
+        let lease = ProxyLease::try_acquire(proxy, self.max_concurrent)?;
+
         // Update last used timestamp
         let now = SystemTime::now()
             .duration_since(UNIX_EPOCH)
             .unwrap()
             .as_secs() as i64;
-        proxy.last_used.store(now, Ordering::Relaxed);
-        proxy.total_requests.fetch_add(1, Ordering::Relaxed);
+        lease.last_used.store(now, Ordering::Relaxed);
+        lease.total_requests.fetch_add(1, Ordering::Relaxed);
+
+        Some(lease)
+    }
 
-        Some(proxy)
+    /// Proxy bound to `session_key`, so every browser of a multi-page crawl keeps
+    /// the same exit IP (and its cookies stay valid). The first call picks one with
+    /// the normal strategy; later calls return it until it goes unhealthy, then a
+    /// new one is bound. Call [`release_sticky`](Self::release_sticky) when done.
+    pub fn get_sticky_proxy(&self, session_key: &str) -> Option<ProxyLease> {
+        self.get_sticky_proxy_for(session_key, None)
+    }
+
+    /// Like [`get_sticky_proxy`](Self::get_sticky_proxy), also rebinding when the
+    /// bound proxy is cooling down on `engine`. A bound proxy at PROXY_MAX_CONCURRENT
+    /// yields `None` (wait and retry) instead of moving the session to another IP.
+    pub fn get_sticky_proxy_for(&self, session_key: &str, engine: Option<&str>) -> Option<ProxyLease> {
+        let bound_id = self.sticky.lock().ok()?.get(session_key).cloned();
+        if let Some(id) = bound_id {
+            let bound = self.proxies.read().ok()?.iter().find(|p| p.id == id).cloned();
+            let usable = bound.filter(|p| {
+                p.healthy.load(Ordering::Relaxed) && !engine.is_some_and(|e| p.is_blocked_for(e, unix_now()))
+            });
+            if let Some(proxy) = usable {
+                let lease = ProxyLease::try_acquire(proxy, self.max_concurrent)?;
+                lease.last_used.store(unix_now(), Ordering::Relaxed);
+                lease.total_requests.fetch_add(1, Ordering::Relaxed);
+                return Some(lease);
+            }
+            println!("🔗 Sticky proxy {} for {} is unusable, rebinding", id, session_key);
+        }
+
+        let lease = self.get_next_proxy_for(engine)?;
+        if let Ok(mut sticky) = self.sticky.lock() {
+            sticky.insert(session_key.to_string(), lease.id.clone());
+        }
+        Some(lease)
+    }
+
+    /// Forget the proxy bound to `session_key`
+    pub fn release_sticky(&self, session_key: &str) {
+        if let Ok(mut sticky) = self.sticky.lock() {
+            sticky.remove(session_key);
+        }
     }
 
     /// Mark a proxy request as successful
@@ -329,7 +634,10 @@ impl ProxyManager {
             if let Some(proxy) = proxies.iter().find(|p| p.id == proxy_id) {
                 proxy.success_count.fetch_add(1, Ordering::Relaxed);
                 proxy.fail_count.store(0, Ordering::Relaxed);
-                proxy.healthy.store(true, Ordering::Relaxed);
+                if !proxy.healthy.swap(true, Ordering::Relaxed) {
+                    self.notify_health(proxy_id, true);
+                }
+                proxy.unhealthy_since.store(0, Ordering::Relaxed);
             }
         }
     }
@@ -341,27 +649,71 @@ impl ProxyManager {
                 let fails = proxy.fail_count.fetch_add(1, Ordering::Relaxed) + 1;
                 if fails >= self.max_fail_count {
                     println!("🚫 Proxy {} disabled after {} consecutive failures", proxy_id, fails);
-                    proxy.healthy.store(false, Ordering::Relaxed);
+                    if proxy.healthy.swap(false, Ordering::Relaxed) {
+                        proxy.unhealthy_since.store(unix_now(), Ordering::Relaxed);
+                        self.notify_health(proxy_id, false);
+                    }
                 }
             }
         }
     }
 
-    /// Add a new proxy at runtime
-    pub fn add_proxy(&self, proxy_str: &str) -> Result<ProxyInfo, String> {
-        let proxy = Arc::new(Proxy::parse(proxy_str)?);
-        let info = ProxyInfo::from(proxy.as_ref());
-        
-        if let Ok(mut proxies) = self.proxies.write() {
-            // Check for duplicate
-            if proxies.iter().any(|p| p.id == proxy.id) {
-                return Err(format!("Proxy {} already exists", proxy.id));
+    /// Stop selecting a proxy for `engine` for `cooldown_secs` (challenge/rate
+    /// limit recovery). The proxy stays available for other engines.
+    pub fn block_for_engine(&self, proxy_id: &str, engine: &str, cooldown_secs: i64) {
+        if let Ok(proxies) = self.proxies.read() {
+            if let Some(proxy) = proxies.iter().find(|p| p.id == proxy_id) {
+                let until = unix_now() + cooldown_secs;
+                if let Ok(mut blocks) = proxy.engine_blocks.lock() {
+                    blocks.retain(|_, u| *u > unix_now());
+                    blocks.insert(engine.to_string(), until);
+                }
+                println!("🧊 Proxy {} blocked on {} for {}s", proxy_id, engine, cooldown_secs);
             }
-            println!("➕ Added proxy: {}", proxy.id);
-            proxies.push(proxy);
         }
-        
-        Ok(info)
+    }
+
+    /// Add a new proxy at runtime.
+    /// If a proxy with the same id (host:port) exists, it is rejected unless
+    /// `replace` is set, in which case its credentials/protocol are updated
+    /// while its health stats are kept. Returns the info and whether it replaced one.
+    pub fn add_proxy(&self, proxy_str: &str, replace: bool) -> Result<(ProxyInfo, bool), String> {
+        self.insert_proxy(Proxy::parse(proxy_str)?, replace)
+    }
+
+    /// [`add_proxy`](Self::add_proxy) for an already-built proxy
+    pub fn insert_proxy(&self, proxy: Proxy, replace: bool) -> Result<(ProxyInfo, bool), String> {
+        proxy.check_chrome_support()?;
+
+        let mut proxies = self.proxies.write().map_err(|_| "Proxy list lock poisoned".to_string())?;
+        if let Some(pos) = proxies.iter().position(|p| p.id == proxy.id) {
+            if !replace {
+                return Err(format!("Proxy {} already exists (use replace=true to update it)", proxy.id));
+            }
+            let existing = &proxies[pos];
+            proxy.healthy.store(existing.healthy.load(Ordering::Relaxed), Ordering::Relaxed);
+            proxy.fail_count.store(existing.fail_count.load(Ordering::Relaxed), Ordering::Relaxed);
+            proxy.last_used.store(existing.last_used.load(Ordering::Relaxed), Ordering::Relaxed);
+            proxy.success_count.store(existing.success_count.load(Ordering::Relaxed), Ordering::Relaxed);
+            proxy.total_requests.store(existing.total_requests.load(Ordering::Relaxed), Ordering::Relaxed);
+            proxy.unhealthy_since.store(existing.unhealthy_since.load(Ordering::Relaxed), Ordering::Relaxed);
+            proxy.latency_ms.store(existing.latency_ms.load(Ordering::Relaxed), Ordering::Relaxed);
+            if let (Ok(mut blocks), Ok(existing_blocks)) = (proxy.engine_blocks.lock(), existing.engine_blocks.lock()) {
+                *blocks = existing_blocks.clone();
+            }
+
+            let proxy = Arc::new(proxy);
+            let info = ProxyInfo::from(proxy.as_ref());
+            println!("♻️ Replaced proxy: {}", proxy.id);
+            proxies[pos] = proxy;
+            return Ok((info, true));
+        }
+
+        let proxy = Arc::new(proxy);
+        let info = ProxyInfo::from(proxy.as_ref());
+        println!("➕ Added proxy: {}", proxy.id);
+        proxies.push(proxy);
+        Ok((info, false))
     }
 
     /// Remove a proxy by ID
@@ -383,6 +735,7 @@ impl ProxyManager {
             if let Some(proxy) = proxies.iter().find(|p| p.id == proxy_id) {
                 proxy.healthy.store(true, Ordering::Relaxed);
                 proxy.fail_count.store(0, Ordering::Relaxed);
+                proxy.unhealthy_since.store(0, Ordering::Relaxed);
                 println!("✅ Re-enabled proxy: {}", proxy_id);
                 return Ok(());
             }
@@ -390,6 +743,52 @@ impl ProxyManager {
         Err(format!("Proxy {} not found", proxy_id))
     }
 
+    /// Zero the request/success/failure counters of one proxy (or all when
+    /// `proxy_id` is None) without touching its health flag or config.
+    /// Returns how many proxies were reset.
+    pub fn reset_stats(&self, proxy_id: Option<&str>) -> Result<usize, String> {
+        let proxies = self.proxies.read().map_err(|_| "Proxy list lock poisoned".to_string())?;
+        let mut reset = 0;
+        for proxy in proxies.iter().filter(|p| proxy_id.map_or(true, |id| p.id == id)) {
+            proxy.success_count.store(0, Ordering::Relaxed);
+            proxy.total_requests.store(0, Ordering::Relaxed);
+            proxy.fail_count.store(0, Ordering::Relaxed);
+            if let Ok(mut blocks) = proxy.engine_blocks.lock() {
+                blocks.clear();
+            }
+            reset += 1;
+        }
+        if let Some(id) = proxy_id {
+            if reset == 0 {
+                return Err(format!("Proxy {} not found", id));
+            }
+        }
+        println!("🧹 Reset stats for {} proxies", reset);
+        Ok(reset)
+    }
+
+    /// Remove proxies that have been disabled for at least `dead_after_secs`.
+    /// Returns the removed ids (their host:port can then be re-added).
+    pub fn prune_dead(&self, dead_after_secs: i64) -> Vec<String> {
+        let now = unix_now();
+        let Ok(mut proxies) = self.proxies.write() else {
+            return Vec::new();
+        };
+        let mut removed = Vec::new();
+        proxies.retain(|p| {
+            let since = p.unhealthy_since.load(Ordering::Relaxed);
+            let dead = !p.healthy.load(Ordering::Relaxed) && since > 0 && now - since >= dead_after_secs;
+            if dead {
+                removed.push(p.id.clone());
+            }
+            !dead
+        });
+        for id in &removed {
+            println!("🪦 Pruned dead proxy: {}", id);
+        }
+        removed
+    }
+
     /// List all proxies with their stats
     pub fn list_proxies(&self) -> Vec<ProxyInfo> {
         if let Ok(proxies) = self.proxies.read() {
@@ -402,15 +801,16 @@ impl ProxyManager {
     /// Get aggregate stats
     pub fn get_stats(&self) -> ProxyStats {
         let proxies = self.proxies.read().ok();
-        let (total, healthy, requests, successes) = proxies
+        let (total, healthy, requests, successes, latencies) = proxies
             .map(|ps| {
                 let total = ps.len();
                 let healthy = ps.iter().filter(|p| p.healthy.load(Ordering::Relaxed)).count();
                 let requests: u64 = ps.iter().map(|p| p.total_requests.load(Ordering::Relaxed)).sum();
                 let successes: u64 = ps.iter().map(|p| p.success_count.load(Ordering::Relaxed)).sum();
-                (total, healthy, requests, successes)
+                let latencies: Vec<u64> = ps.iter().filter_map(|p| p.latency()).collect();
+                (total, healthy, requests, successes, latencies)
             })
-            .unwrap_or((0, 0, 0, 0));
+            .unwrap_or((0, 0, 0, 0, Vec::new()));
 
         ProxyStats {
             total_proxies: total,
@@ -422,6 +822,8 @@ impl ProxyManager {
             } else {
                 1.0
             },
+            avg_latency_ms: (!latencies.is_empty()).then(|| latencies.iter().sum::<u64>() / latencies.len() as u64),
+            strategy: self.strategy(),
         }
     }
 
@@ -431,9 +833,104 @@ impl ProxyManager {
     }
 }
 
+/// Result of one health-check sweep
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct HealthCheckSummary {
+    pub checked: usize,
+    pub passed: usize,
+    pub failed: usize,
+    /// Proxies that could not be probed from here (e.g. SOCKS5 without reqwest support)
+    pub skipped: usize,
+    pub elapsed_ms: u64,
+}
+
+/// Fetch `url` through `proxy` and time it; Ok(None) means the proxy can't be probed directly
+async fn probe_proxy(proxy: &Proxy, url: &str, timeout: std::time::Duration) -> Result<Option<std::time::Duration>, String> {
+    let Ok(reqwest_proxy) = proxy.to_reqwest_proxy() else {
+        return Ok(None);
+    };
+    let client = reqwest::Client::builder()
+        .proxy(reqwest_proxy)
+        .timeout(timeout)
+        .build()
+        .map_err(|e| e.to_string())?;
+    let started = std::time::Instant::now();
+    let resp = client.get(url).send().await.map_err(|e| e.to_string())?;
+    if resp.status().is_success() || resp.status().is_redirection() {
+        Ok(Some(started.elapsed()))
+    } else {
+        Err(format!("HTTP {}", resp.status()))
+    }
+}
+
+impl ProxyManager {
+    /// Probe every proxy against `url`, at most `concurrency` at a time.
+    /// A passing probe restores a disabled proxy; a failing one counts as a
+    /// regular failure (PROXY_MAX_FAILS in a row disables it). Passing probes
+    /// record the proxy's latency; probes don't touch request/success counters.
+    pub async fn health_check(&self, url: &str, concurrency: usize, timeout: std::time::Duration) -> HealthCheckSummary {
+        use futures_util::StreamExt;
+
+        let started = std::time::Instant::now();
+        let proxies: Vec<Arc<Proxy>> = self.proxies.read().map(|p| p.clone()).unwrap_or_default();
+        let outcomes: Vec<(String, Result<Option<std::time::Duration>, String>)> = futures_util::stream::iter(proxies)
+            .map(|proxy| async move {
+                let outcome = probe_proxy(&proxy, url, timeout).await;
+                (proxy.id.clone(), outcome)
+            })
+            .buffer_unordered(concurrency.max(1))
+            .collect()
+            .await;
+
+        let mut summary = HealthCheckSummary { checked: outcomes.len(), ..Default::default() };
+        for (id, outcome) in outcomes {
+            match outcome {
+                Ok(Some(latency)) => {
+                    summary.passed += 1;
+                    if let Ok(proxies) = self.proxies.read() {
+                        if let Some(proxy) = proxies.iter().find(|p| p.id == id) {
+                            proxy.latency_ms.store((latency.as_millis() as u64).max(1), Ordering::Relaxed);
+                            proxy.fail_count.store(0, Ordering::Relaxed);
+                            if !proxy.healthy.swap(true, Ordering::Relaxed) {
+                                println!("💚 Proxy {} passed health check, re-enabled", id);
+                                self.notify_health(&id, true);
+                            }
+                            proxy.unhealthy_since.store(0, Ordering::Relaxed);
+                        }
+                    }
+                }
+                Ok(None) => summary.skipped += 1,
+                Err(e) => {
+                    summary.failed += 1;
+                    println!("💔 Proxy {} failed health check: {}", id, e);
+                    self.mark_failure(&id);
+                }
+            }
+        }
+        summary.elapsed_ms = started.elapsed().as_millis() as u64;
+        summary
+    }
+}
+
+/// Unpacked proxy-auth extension living in its own temp directory.
+/// The directory is removed when this guard is dropped, so keep it alive
+/// for as long as the browser that loaded it.
+pub struct ProxyAuthExtension {
+    dir: tempfile::TempDir,
+}
+
+impl ProxyAuthExtension {
+    /// Path to pass to `--load-extension=`
+    pub fn path(&self) -> String {
+        self.dir.path().to_string_lossy().to_string()
+    }
+}
+
 /// Generate Chrome extension for proxy authentication
-/// This creates a minimal Chrome extension that intercepts proxy auth requests
-pub fn generate_proxy_auth_extension(username: &str, password: &str) -> String {
+/// This creates a minimal Chrome extension that intercepts proxy auth requests.
+/// Each call gets a unique directory (under `PROXY_AUTH_EXT_DIR` or the system temp dir)
+/// so concurrent crawls with different credentials don't overwrite each other.
+pub fn generate_proxy_auth_extension(username: &str, password: &str) -> std::io::Result<ProxyAuthExtension> {
     let manifest = r#"{
   "version": "1.0.0",
   "manifest_version": 2,
@@ -459,14 +956,18 @@ pub fn generate_proxy_auth_extension(username: &str, password: &str) -> String {
         password.replace('\\', "\\\\").replace('"', "\\\"")
     );
 
-    // Return as base64 encoded CRX or directory path
-    // For simplicity, we'll write to a temp directory
-    let temp_dir = std::env::temp_dir().join("proxy_auth_ext");
-    let _ = std::fs::create_dir_all(&temp_dir);
-    let _ = std::fs::write(temp_dir.join("manifest.json"), manifest);
-    let _ = std::fs::write(temp_dir.join("background.js"), background);
-    
-    temp_dir.to_string_lossy().to_string()
+    let parent = std::env::var("PROXY_AUTH_EXT_DIR")
+        .map(std::path::PathBuf::from)
+        .unwrap_or_else(|_| std::env::temp_dir());
+    std::fs::create_dir_all(&parent)?;
+
+    let dir = tempfile::Builder::new()
+        .prefix("proxy_auth_ext_")
+        .tempdir_in(&parent)?;
+    std::fs::write(dir.path().join("manifest.json"), manifest)?;
+    std::fs::write(dir.path().join("background.js"), background)?;
+
+    Ok(ProxyAuthExtension { dir })
 }
 
 #[cfg(test)]
@@ -499,9 +1000,263 @@ mod tests {
         assert_eq!(proxy.port, 1080);
     }
 
+    #[test]
+    fn test_auth_extension_dirs_are_unique_and_cleaned_up() {
+        let a = generate_proxy_auth_extension("user_a", "pass_a").unwrap();
+        let b = generate_proxy_auth_extension("user_b", "pass_b").unwrap();
+        assert_ne!(a.path(), b.path());
+
+        let background = std::fs::read_to_string(format!("{}/background.js", a.path())).unwrap();
+        assert!(background.contains("user_a"));
+
+        let path = a.path();
+        drop(a);
+        assert!(!std::path::Path::new(&path).exists());
+    }
+
+    #[test]
+    fn test_add_duplicate_proxy() {
+        let manager = ProxyManager::new(Vec::new(), RotationStrategy::RoundRobin, 3);
+        assert!(manager.add_proxy("1.2.3.4:8080", false).is_ok());
+        assert!(manager.add_proxy("user:pass@1.2.3.4:8080", false).is_err());
+
+        let (info, replaced) = manager.add_proxy("user:pass@1.2.3.4:8080", true).unwrap();
+        assert!(replaced);
+        assert!(info.has_auth);
+        assert_eq!(manager.list_proxies().len(), 1);
+    }
+
+    #[test]
+    fn test_max_concurrent_skips_busy_proxies() {
+        let proxies = vec![
+            Arc::new(Proxy::parse("1.1.1.1:8080").unwrap()),
+            Arc::new(Proxy::parse("2.2.2.2:8080").unwrap()),
+        ];
+        let manager = ProxyManager::new(proxies, RotationStrategy::RoundRobin, 3).with_max_concurrent(1);
+
+        let first = manager.get_next_proxy().unwrap();
+        let second = manager.get_next_proxy().unwrap();
+        assert_ne!(first.id, second.id);
+        assert_eq!(first.in_flight.load(Ordering::SeqCst), 1);
+
+        // Both saturated: nothing until a lease is dropped
+        assert!(manager.get_next_proxy().is_none());
+
+        let first_id = first.id.clone();
+        drop(first);
+        assert_eq!(manager.get_next_proxy().unwrap().id, first_id);
+    }
+
+    #[test]
+    fn test_reset_stats() {
+        let manager = ProxyManager::new(Vec::new(), RotationStrategy::RoundRobin, 3);
+        manager.add_proxy("1.1.1.1:8080", false).unwrap();
+        manager.add_proxy("2.2.2.2:8080", false).unwrap();
+        drop(manager.get_next_proxy());
+        manager.mark_failure("1.1.1.1:8080");
+
+        assert_eq!(manager.reset_stats(Some("1.1.1.1:8080")), Ok(1));
+        let info = manager.list_proxies().into_iter().find(|p| p.id == "1.1.1.1:8080").unwrap();
+        assert_eq!((info.total_requests, info.fail_count, info.success_count), (0, 0, 0));
+
+        assert!(manager.reset_stats(Some("9.9.9.9:1")).is_err());
+        assert_eq!(manager.reset_stats(None), Ok(2));
+    }
+
+    #[tokio::test]
+    async fn test_health_check_summary() {
+        let manager = ProxyManager::new(Vec::new(), RotationStrategy::RoundRobin, 1);
+        // Nothing listens on port 1: both probes fail fast with connection refused
+        manager.add_proxy("127.0.0.1:1", false).unwrap();
+        manager.add_proxy("http://localhost:1", false).unwrap();
+        manager.add_proxy("socks5://127.0.0.1:1080", false).ok();
+
+        let summary = manager.health_check("http://example.com/", 2, std::time::Duration::from_secs(5)).await;
+        assert_eq!(summary.checked, manager.list_proxies().len());
+        assert_eq!(summary.passed, 0);
+        assert_eq!(summary.failed + summary.skipped, summary.checked);
+        assert!(summary.failed >= 2);
+        assert_eq!(manager.get_stats().healthy_proxies, summary.skipped);
+    }
+
+    #[test]
+    fn test_average_latency_ignores_unmeasured() {
+        let manager = ProxyManager::new(Vec::new(), RotationStrategy::RoundRobin, 3);
+        manager.add_proxy("1.1.1.1:8080", false).unwrap();
+        manager.add_proxy("2.2.2.2:8080", false).unwrap();
+        manager.add_proxy("3.3.3.3:8080", false).unwrap();
+        assert_eq!(manager.get_stats().avg_latency_ms, None);
+
+        if let Ok(proxies) = manager.proxies.read() {
+            proxies[0].latency_ms.store(100, Ordering::Relaxed);
+            proxies[1].latency_ms.store(300, Ordering::Relaxed);
+        }
+        assert_eq!(manager.get_stats().avg_latency_ms, Some(200));
+        let info = manager.list_proxies();
+        assert_eq!(info[0].latency_ms, Some(100));
+        assert_eq!(info[2].latency_ms, None);
+    }
+
+    #[test]
+    fn test_strategy_selection() {
+        assert_eq!(RotationStrategy::parse("weighted"), Some(RotationStrategy::WeightedBySuccessRate));
+        assert_eq!(RotationStrategy::parse("Least_Latency"), Some(RotationStrategy::LeastLatency));
+        assert_eq!(RotationStrategy::parse("fastest"), None);
+
+        // 1/4 of the mass on index 0, 3/4 on index 1
+        assert_eq!(pick_weighted(&[0.25, 0.75], 0.2), 0);
+        assert_eq!(pick_weighted(&[0.25, 0.75], 0.3), 1);
+        assert_eq!(pick_weighted(&[0.0, 1.0], 0.0), 1);
+        assert_eq!(pick_weighted(&[0.0, 0.0], 0.9), 1);
+
+        let manager = ProxyManager::new(Vec::new(), RotationStrategy::RoundRobin, 3);
+        manager.add_proxy("1.1.1.1:8080", false).unwrap();
+        manager.add_proxy("2.2.2.2:8080", false).unwrap();
+        manager.add_proxy("3.3.3.3:8080", false).unwrap();
+        assert_eq!(manager.set_strategy(RotationStrategy::LeastLatency), RotationStrategy::RoundRobin);
+        assert_eq!(manager.get_stats().strategy, RotationStrategy::LeastLatency);
+        if let Ok(proxies) = manager.proxies.read() {
+            proxies[0].latency_ms.store(400, Ordering::Relaxed);
+            proxies[1].latency_ms.store(90, Ordering::Relaxed);
+        }
+        for _ in 0..3 {
+            assert_eq!(manager.get_next_proxy().unwrap().id, "2.2.2.2:8080");
+        }
+    }
+
+    #[test]
+    fn test_sticky_proxy_same_key_same_proxy() {
+        let manager = ProxyManager::new(Vec::new(), RotationStrategy::RoundRobin, 1);
+        manager.add_proxy("1.1.1.1:8080", false).unwrap();
+        manager.add_proxy("2.2.2.2:8080", false).unwrap();
+        manager.add_proxy("3.3.3.3:8080", false).unwrap();
+
+        let bound = manager.get_sticky_proxy("job-a").unwrap().id.clone();
+        for _ in 0..5 {
+            // Unrelated rotation in between doesn't move the binding
+            drop(manager.get_next_proxy());
+            assert_eq!(manager.get_sticky_proxy("job-a").unwrap().id, bound);
+        }
+        assert_ne!(manager.get_sticky_proxy("job-b").unwrap().id, bound);
+
+        // Unhealthy: rebound to another proxy, which then sticks
+        manager.mark_failure(&bound);
+        let rebound = manager.get_sticky_proxy("job-a").unwrap().id.clone();
+        assert_ne!(rebound, bound);
+        assert_eq!(manager.get_sticky_proxy("job-a").unwrap().id, rebound);
+
+        manager.release_sticky("job-a");
+        assert!(manager.sticky.lock().unwrap().get("job-a").is_none());
+    }
+
+    #[test]
+    fn test_sticky_proxy_respects_max_concurrent() {
+        let manager = ProxyManager::new(Vec::new(), RotationStrategy::RoundRobin, 3).with_max_concurrent(1);
+        manager.add_proxy("1.1.1.1:8080", false).unwrap();
+        manager.add_proxy("2.2.2.2:8080", false).unwrap();
+
+        let held = manager.get_sticky_proxy("job-a").unwrap();
+        // Saturated binding waits rather than moving the session to another IP
+        assert!(manager.get_sticky_proxy("job-a").is_none());
+        let bound = held.id.clone();
+        drop(held);
+        assert_eq!(manager.get_sticky_proxy("job-a").unwrap().id, bound);
+    }
+
+    #[test]
+    fn test_prune_dead_proxies() {
+        let manager = ProxyManager::new(Vec::new(), RotationStrategy::RoundRobin, 1);
+        manager.add_proxy("1.1.1.1:8080", false).unwrap();
+        manager.add_proxy("2.2.2.2:8080", false).unwrap();
+
+        manager.mark_failure("1.1.1.1:8080");
+        assert!(manager.prune_dead(3600).is_empty());
+
+        // Pretend it has been down for two hours
+        if let Ok(proxies) = manager.proxies.read() {
+            proxies[0].unhealthy_since.fetch_sub(7200, Ordering::Relaxed);
+        }
+        assert_eq!(manager.prune_dead(3600), vec!["1.1.1.1:8080".to_string()]);
+        assert_eq!(manager.list_proxies().len(), 1);
+        assert!(manager.add_proxy("1.1.1.1:8080", false).is_ok());
+    }
+
+    #[test]
+    fn test_engine_block_only_affects_that_engine() {
+        let manager = ProxyManager::new(Vec::new(), RotationStrategy::RoundRobin, 3);
+        manager.add_proxy("1.1.1.1:8080", false).unwrap();
+        manager.add_proxy("2.2.2.2:8080", false).unwrap();
+        manager.block_for_engine("1.1.1.1:8080", "google", 600);
+
+        for _ in 0..4 {
+            assert_eq!(manager.get_next_proxy_for(Some("google")).unwrap().id, "2.2.2.2:8080");
+        }
+        let bing: Vec<String> = (0..2).map(|_| manager.get_next_proxy_for(Some("bing")).unwrap().id.clone()).collect();
+        assert!(bing.contains(&"1.1.1.1:8080".to_string()));
+
+        // Everything blocked: fall back to the proxy that recovers first
+        manager.block_for_engine("2.2.2.2:8080", "google", 1200);
+        assert_eq!(manager.get_next_proxy_for(Some("google")).unwrap().id, "1.1.1.1:8080");
+
+        let info = manager.list_proxies().into_iter().find(|p| p.id == "2.2.2.2:8080").unwrap();
+        assert!(info.blocked_engines.contains_key("google"));
+    }
+
     #[test]
     fn test_chrome_arg() {
         let proxy = Proxy::parse("http://proxy.example.com:8080").unwrap();
         assert_eq!(proxy.to_chrome_arg(), "http://proxy.example.com:8080");
     }
+
+    #[test]
+    fn test_parse_protocol_auth_combinations() {
+        // (input, protocol, chrome arg, has auth)
+        let cases = [
+            ("1.2.3.4:8080", ProxyProtocol::Http, "http://1.2.3.4:8080", false),
+            ("u:p@1.2.3.4:8080", ProxyProtocol::Http, "http://1.2.3.4:8080", true),
+            ("http://1.2.3.4:8080", ProxyProtocol::Http, "http://1.2.3.4:8080", false),
+            ("http://u:p@1.2.3.4:8080", ProxyProtocol::Http, "http://1.2.3.4:8080", true),
+            ("https://1.2.3.4:443", ProxyProtocol::Https, "https://1.2.3.4:443", false),
+            ("https://u:p@1.2.3.4:443", ProxyProtocol::Https, "https://1.2.3.4:443", true),
+            ("socks5://1.2.3.4:1080", ProxyProtocol::Socks5, "socks5://1.2.3.4:1080", false),
+            ("SOCKS5://1.2.3.4:1080", ProxyProtocol::Socks5, "socks5://1.2.3.4:1080", false),
+            ("socks5://u:p@1.2.3.4:1080", ProxyProtocol::Socks5, "socks5://1.2.3.4:1080", true),
+        ];
+        for (input, protocol, chrome_arg, has_auth) in cases {
+            let proxy = Proxy::parse(input).unwrap();
+            assert_eq!(proxy.protocol, protocol, "{}", input);
+            assert_eq!(proxy.to_chrome_arg(), chrome_arg, "{}", input);
+            assert_eq!(proxy.requires_auth(), has_auth, "{}", input);
+            assert_eq!(proxy.check_chrome_support().is_ok(), !(has_auth && protocol == ProxyProtocol::Socks5), "{}", input);
+        }
+
+        assert!(Proxy::parse("socks4://1.2.3.4:1080").is_err());
+        assert!(Proxy::parse("socks5://1.2.3.4").is_err());
+        assert!(Proxy::parse("socks5://user@1.2.3.4:1080").is_err());
+
+        let manager = ProxyManager::new(Vec::new(), RotationStrategy::RoundRobin, 3);
+        assert!(manager.add_proxy("socks5://1.2.3.4:1080", false).is_ok());
+        let err = manager.add_proxy("socks5://u:p@5.6.7.8:1080", false).unwrap_err();
+        assert!(err.contains("Chrome does not support"));
+        assert_eq!(manager.list_proxies().len(), 1);
+    }
+
+    #[test]
+    fn test_health_events_on_disable_and_recovery() {
+        let manager = ProxyManager::new(Vec::new(), RotationStrategy::RoundRobin, 2);
+        manager.add_proxy("1.1.1.1:8080", false).unwrap();
+        let mut events = manager.subscribe_health();
+
+        manager.mark_failure("1.1.1.1:8080");
+        assert!(events.try_recv().is_err());
+        manager.mark_failure("1.1.1.1:8080");
+        manager.mark_failure("1.1.1.1:8080");
+        assert_eq!(events.try_recv().unwrap(), ("1.1.1.1:8080".to_string(), false));
+        assert!(events.try_recv().is_err());
+
+        manager.mark_success("1.1.1.1:8080");
+        manager.mark_success("1.1.1.1:8080");
+        assert_eq!(events.try_recv().unwrap(), ("1.1.1.1:8080".to_string(), true));
+        assert!(events.try_recv().is_err());
+    }
 }
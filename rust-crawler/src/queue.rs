@@ -1,9 +1,14 @@
 use redis::{Client, AsyncCommands};
+use redis::aio::ConnectionManager;
 use anyhow::Result;
 use std::env;
 
+/// Holds one multiplexed Redis connection that reconnects on its own;
+/// clones share it, so callers never open a connection per operation.
 #[derive(Clone)]
 pub struct QueueManager {
+    conn: ConnectionManager,
+    /// Kept for pub/sub, which needs a dedicated connection per subscriber
     client: Client,
 }
 
@@ -17,6 +22,235 @@ pub struct CrawlJob {
     pub keyword: String,
     pub engine: String,
     pub selectors: Option<std::collections::HashMap<String, String>>,
+    #[serde(default)]
+    pub engine_options: Option<std::collections::HashMap<String, String>>,
+    #[serde(default)]
+    pub result_include_patterns: Vec<String>,
+    #[serde(default)]
+    pub result_exclude_patterns: Vec<String>,
+    #[serde(default)]
+    pub capture_thumbnail: bool,
+    /// Store a full-page PNG of every deep-extracted page
+    #[serde(default)]
+    pub screenshot: bool,
+    #[serde(default)]
+    pub warmup: bool,
+    /// Queue a child crawl for each related search once this job completes
+    #[serde(default)]
+    pub crawl_related: bool,
+    /// Set on jobs queued from another task's related searches
+    #[serde(default)]
+    pub parent_task_id: Option<String>,
+    #[serde(default)]
+    pub referer: Option<String>,
+    /// Client metadata, stored with the task untouched
+    #[serde(default)]
+    pub metadata: Option<serde_json::Value>,
+    /// Generic engine: SPA click-through steps before extraction
+    #[serde(default)]
+    pub click_sequence: Vec<String>,
+    /// Fairness group (campaign/batch); defaults to the submitting user
+    #[serde(default)]
+    pub tag: Option<String>,
+    /// Per-request search retry budget
+    #[serde(default)]
+    pub max_attempts: Option<u32>,
+    /// Deep-extract the first result (false = SERP only)
+    #[serde(default = "default_true")]
+    pub deep_crawl: bool,
+    /// Client-chosen batch the job was submitted with
+    #[serde(default)]
+    pub batch_id: Option<String>,
+    #[serde(default)]
+    pub priority: JobPriority,
+    /// Validated object-storage prefix the job's artifacts are written under
+    #[serde(default)]
+    pub storage_prefix: Option<String>,
+    /// Local entity-mention extraction on the deep-crawled page
+    #[serde(default)]
+    pub extract_entities: bool,
+    /// Fingerprint overrides for the stealth script (built-in profile when None)
+    #[serde(default)]
+    pub stealth: Option<crate::stealth::StealthConfig>,
+    /// How many top SERP results to deep-extract (when `deep_crawl`)
+    #[serde(default = "default_max_deep_extract")]
+    pub max_deep_extract: u32,
+    /// SERP pages to scrape (None = first page only)
+    #[serde(default)]
+    pub pages: Option<u32>,
+    /// Failed runs so far; retried with backoff until MAX_JOB_ATTEMPTS, then dead-lettered
+    #[serde(default)]
+    pub attempts: u32,
+    /// Run every browser of the job through one proxy (keyed by job id)
+    #[serde(default)]
+    pub sticky: bool,
+    /// Skip deep-extracting result URLs disallowed by the site's robots.txt
+    #[serde(default)]
+    pub respect_robots: bool,
+    /// Where to POST the final outcome (see `webhook`)
+    #[serde(default)]
+    pub callback_url: Option<String>,
+    /// Unix seconds when the job was queued (set by `push_job`), used for aging
+    #[serde(default)]
+    pub enqueued_at: i64,
+}
+
+/// Scheduling tier. Normal jobs use the main queue; high/low get their own lists.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum JobPriority {
+    Low,
+    #[default]
+    Normal,
+    High,
+}
+
+impl JobPriority {
+    const ALL: [JobPriority; 3] = [JobPriority::High, JobPriority::Normal, JobPriority::Low];
+
+    fn rank(self) -> i64 {
+        match self {
+            JobPriority::Low => 0,
+            JobPriority::Normal => 1,
+            JobPriority::High => 2,
+        }
+    }
+
+    fn queue_key(self) -> &'static str {
+        match self {
+            JobPriority::High => "crawl_queue:high",
+            JobPriority::Normal => MAIN_QUEUE,
+            JobPriority::Low => "crawl_queue:low",
+        }
+    }
+}
+
+/// Priority aging: a job gains one tier for every `aging_secs` it has waited
+/// (0 disables aging), so low-priority work can't starve under constant high load.
+pub fn effective_priority(priority: JobPriority, waited_secs: i64, aging_secs: i64) -> i64 {
+    let boost = if aging_secs > 0 { waited_secs.max(0) / aging_secs } else { 0 };
+    priority.rank() + boost
+}
+
+/// Pick the tier to serve next from the oldest job waiting in each one
+/// (`None` = empty tier). Highest effective priority wins; ties go to the
+/// higher base tier.
+pub fn pick_priority(oldest: &[(JobPriority, Option<i64>)], now: i64, aging_secs: i64) -> Option<JobPriority> {
+    oldest
+        .iter()
+        .filter_map(|(priority, enqueued_at)| {
+            enqueued_at.map(|at| (*priority, effective_priority(*priority, now - at, aging_secs)))
+        })
+        .max_by_key(|(priority, effective)| (*effective, priority.rank()))
+        .map(|(priority, _)| priority)
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_max_deep_extract() -> u32 {
+    1
+}
+
+const MAIN_QUEUE: &str = "crawl_queue";
+/// Rotating list of fairness keys that currently have queued jobs
+const FAIR_RING: &str = "crawl_queue:ring";
+/// Same keys as a set, for O(1) "already in the ring?" checks
+const FAIR_MEMBERS: &str = "crawl_queue:members";
+/// Failed jobs waiting out their retry backoff, scored by ready-at unix seconds
+const DELAYED_JOBS: &str = "crawl_queue:delayed";
+/// Jobs that exhausted MAX_JOB_ATTEMPTS (newest first)
+const DEAD_LETTER_QUEUE: &str = "crawl_queue:dead";
+
+/// Total runs a job gets before it is dead-lettered (MAX_JOB_ATTEMPTS, default 3)
+pub fn max_job_attempts() -> u32 {
+    crate::config::env_parse("MAX_JOB_ATTEMPTS", 3u32).max(1)
+}
+
+/// Delay before retry number `attempts` (1-based): JOB_RETRY_BACKOFF_SECS (default 30)
+/// doubled per attempt, capped at JOB_RETRY_BACKOFF_MAX_SECS (default 900)
+pub fn retry_backoff(attempts: u32) -> std::time::Duration {
+    let base: u64 = crate::config::env_parse("JOB_RETRY_BACKOFF_SECS", 30);
+    let max: u64 = crate::config::env_parse("JOB_RETRY_BACKOFF_MAX_SECS", 900);
+    retry_backoff_with(attempts, base, max)
+}
+
+fn retry_backoff_with(attempts: u32, base_secs: u64, max_secs: u64) -> std::time::Duration {
+    let factor = 1u64 << attempts.saturating_sub(1).min(16);
+    std::time::Duration::from_secs(base_secs.saturating_mul(factor).min(max_secs))
+}
+
+/// A job that used up its attempts, kept for inspection or manual replay
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeadLetter {
+    pub job: CrawlJob,
+    pub error: String,
+    /// Unix seconds
+    pub failed_at: i64,
+}
+
+impl CrawlJob {
+    /// Key jobs are grouped by under FAIR_QUEUEING
+    pub fn fairness_key(&self) -> String {
+        self.tag.clone().filter(|t| !t.is_empty()).unwrap_or_else(|| self.user_id.clone())
+    }
+}
+
+/// Task lifecycle change broadcast on the task events channel (queued, running, completed, failed)
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct TaskStatusEvent {
+    pub task_id: String,
+    pub keyword: String,
+    pub engine: String,
+    #[schema(example = "running")]
+    pub status: String,
+    /// Unix seconds
+    pub at: i64,
+}
+
+impl TaskStatusEvent {
+    pub fn new(job: &CrawlJob, status: &str) -> Self {
+        Self {
+            task_id: job.id.clone(),
+            keyword: job.keyword.clone(),
+            engine: job.engine.clone(),
+            status: status.to_string(),
+            at: chrono::Utc::now().timestamp(),
+        }
+    }
+}
+
+/// Redis pub/sub channel for task status changes (TASK_EVENTS_CHANNEL, default "task_events")
+fn task_events_channel() -> String {
+    env::var("TASK_EVENTS_CHANNEL").unwrap_or_else(|_| "task_events".to_string())
+}
+
+fn daily_quota_key(date: chrono::NaiveDate) -> String {
+    format!("crawl_quota:{}", date.format("%Y-%m-%d"))
+}
+
+/// DECR that never goes below zero and never creates the key (so its TTL is kept
+/// and an expired day isn't resurrected at -1)
+const RELEASE_QUOTA_SCRIPT: &str = r#"
+local used = tonumber(redis.call('GET', KEYS[1]) or '0')
+if used > 0 then
+    return redis.call('DECR', KEYS[1])
+end
+return 0
+"#;
+
+/// A unit of the daily quota taken by [`QueueManager::reserve_daily_crawl`]
+#[derive(Debug, Clone)]
+pub struct QuotaReservation {
+    /// Counter the unit came from, so a release after midnight UTC returns it to that day
+    key: String,
+    /// Crawls counted that day, this one included
+    pub used: u64,
+}
+
+fn fair_queue_key(fairness_key: &str) -> String {
+    format!("crawl_queue:fair:{}", fairness_key)
 }
 
 impl QueueManager {
@@ -25,23 +259,114 @@ impl QueueManager {
         let client = Client::open(redis_url)?;
         
         // Test connection
-        let mut conn = client.get_async_connection().await?;
+        let mut conn = ConnectionManager::new(client.clone()).await?;
         let _: String = redis::cmd("PING").query_async(&mut conn).await?;
         println!("✅ Redis Connected successfully");
 
-        Ok(Self { client })
+        Ok(Self { conn, client })
     }
 
-    pub async fn push_job(&self, job: CrawlJob) -> Result<()> {
-        let mut conn = self.client.get_async_connection().await?;
+    /// Broadcast a task status change to every API instance. Best-effort:
+    /// a failed publish only costs dashboards a live update.
+    pub async fn publish_task_event(&self, event: &TaskStatusEvent) {
+        let mut conn = self.conn.clone();
+        let payload = match serde_json::to_string(event) {
+            Ok(p) => p,
+            Err(_) => return,
+        };
+        if let Err(e) = conn.publish::<_, _, ()>(task_events_channel(), payload).await {
+            eprintln!("⚠️ [Queue] Failed to publish task event for {}: {}", event.task_id, e);
+        }
+    }
+
+    /// Stream of raw JSON task events, on its own pub/sub connection
+    pub async fn subscribe_task_events(&self) -> Result<impl futures_util::Stream<Item = String>> {
+        use futures_util::StreamExt;
+        let mut pubsub = self.client.get_async_pubsub().await?;
+        pubsub.subscribe(task_events_channel()).await?;
+        Ok(pubsub.into_on_message().filter_map(|msg| async move { msg.get_payload::<String>().ok() }))
+    }
+
+    /// With FAIR_QUEUEING=true each submitter/tag gets its own queue and
+    /// `pop_job` round-robins across them, so one large batch can't starve
+    /// other users. Otherwise everything goes through the single FIFO queue.
+    fn fair_queueing() -> bool {
+        crate::config::env_flag("FAIR_QUEUEING", false)
+    }
+
+    pub async fn push_job(&self, mut job: CrawlJob) -> Result<()> {
+        let mut conn = self.conn.clone();
+        if job.enqueued_at == 0 {
+            job.enqueued_at = chrono::Utc::now().timestamp();
+        }
         let job_json = serde_json::to_string(&job)?;
-        conn.lpush::<_, _, ()>("crawl_queue", job_json).await?;
+
+        if !Self::fair_queueing() {
+            conn.lpush::<_, _, ()>(job.priority.queue_key(), job_json).await?;
+            self.publish_task_event(&TaskStatusEvent::new(&job, "queued")).await;
+            return Ok(());
+        }
+
+        let key = job.fairness_key();
+        conn.lpush::<_, _, ()>(fair_queue_key(&key), job_json).await?;
+        let added: i64 = conn.sadd(FAIR_MEMBERS, &key).await?;
+        if added == 1 {
+            conn.lpush::<_, _, ()>(FAIR_RING, &key).await?;
+        }
+        self.publish_task_event(&TaskStatusEvent::new(&job, "queued")).await;
         Ok(())
     }
 
     pub async fn pop_job(&self) -> Result<Option<CrawlJob>> {
-        let mut conn = self.client.get_async_connection().await?;
-        let result: Option<String> = conn.rpop("crawl_queue", None).await?;
+        let mut conn = self.conn.clone();
+
+        if Self::fair_queueing() {
+            // Rotate the ring: each pop serves the next key in turn
+            let ring_len: usize = conn.llen(FAIR_RING).await?;
+            for _ in 0..ring_len {
+                let key: Option<String> = conn.rpoplpush(FAIR_RING, FAIR_RING).await?;
+                let Some(key) = key else { break };
+
+                let result: Option<String> = conn.rpop(fair_queue_key(&key), None).await?;
+                if let Some(json) = result {
+                    return Ok(Some(serde_json::from_str(&json)?));
+                }
+
+                // Drained: drop the key, unless a push raced in meanwhile
+                conn.lrem::<_, _, ()>(FAIR_RING, 0, &key).await?;
+                conn.srem::<_, _, ()>(FAIR_MEMBERS, &key).await?;
+                let pending: usize = conn.llen(fair_queue_key(&key)).await?;
+                if pending > 0 {
+                    conn.sadd::<_, _, ()>(FAIR_MEMBERS, &key).await?;
+                    conn.lpush::<_, _, ()>(FAIR_RING, &key).await?;
+                }
+            }
+        }
+
+        // Priority tiers (also drain jobs queued before FAIR_QUEUEING was enabled).
+        // Compare the oldest job of each tier, aged by PRIORITY_AGING_SECS (default 300).
+        let aging_secs = crate::config::env_parse("PRIORITY_AGING_SECS", 300i64);
+        let now = chrono::Utc::now().timestamp();
+        let mut oldest = Vec::with_capacity(JobPriority::ALL.len());
+        for priority in JobPriority::ALL {
+            let tail: Option<String> = conn.lindex(priority.queue_key(), -1).await?;
+            // Jobs queued before enqueued_at existed count as just queued
+            let enqueued_at = tail.map(|json| {
+                serde_json::from_str::<CrawlJob>(&json)
+                    .map(|job| if job.enqueued_at > 0 { job.enqueued_at } else { now })
+                    .unwrap_or(now)
+            });
+            oldest.push((priority, enqueued_at));
+        }
+
+        let Some(priority) = pick_priority(&oldest, now, aging_secs) else {
+            return Ok(None);
+        };
+        if priority != JobPriority::High && oldest[0].1.is_some() {
+            println!("⏫ [Queue] Aged {:?} job served ahead of waiting high-priority work", priority);
+        }
+
+        let result: Option<String> = conn.rpop(priority.queue_key(), None).await?;
         
         match result {
             Some(json) => {
@@ -51,4 +376,158 @@ impl QueueManager {
             None => Ok(None)
         }
     }
+
+    /// Park a failed `job` in the delayed set until `delay` has passed;
+    /// `promote_due_jobs` puts it back on the queue.
+    pub async fn schedule_retry(&self, mut job: CrawlJob, delay: std::time::Duration) -> Result<()> {
+        let mut conn = self.conn.clone();
+        // Re-stamped on promotion so the retry doesn't jump the queue via aging
+        job.enqueued_at = 0;
+        let ready_at = chrono::Utc::now().timestamp() + delay.as_secs() as i64;
+        conn.zadd::<_, _, _, ()>(DELAYED_JOBS, serde_json::to_string(&job)?, ready_at).await?;
+        self.publish_task_event(&TaskStatusEvent::new(&job, "retrying")).await;
+        Ok(())
+    }
+
+    /// Requeue delayed jobs whose backoff has elapsed. Returns how many were moved.
+    /// ZREM decides ownership, so concurrent workers never requeue a job twice.
+    pub async fn promote_due_jobs(&self) -> Result<usize> {
+        let mut conn = self.conn.clone();
+        let now = chrono::Utc::now().timestamp();
+        let due: Vec<String> = conn.zrangebyscore_limit(DELAYED_JOBS, "-inf", now, 0, 100).await?;
+        let mut promoted = 0;
+        for json in due {
+            let removed: i64 = conn.zrem(DELAYED_JOBS, &json).await?;
+            if removed == 0 {
+                continue;
+            }
+            match serde_json::from_str::<CrawlJob>(&json) {
+                Ok(job) => {
+                    self.push_job(job).await?;
+                    promoted += 1;
+                }
+                Err(e) => eprintln!("⚠️ [Queue] Dropping unreadable delayed job: {}", e),
+            }
+        }
+        Ok(promoted)
+    }
+
+    /// Record a job that exhausted its attempts. The list is trimmed to
+    /// DLQ_MAX_LEN (default 10000) entries.
+    pub async fn dead_letter(&self, job: CrawlJob, error: &str) -> Result<()> {
+        let mut conn = self.conn.clone();
+        let entry = DeadLetter { job, error: error.to_string(), failed_at: chrono::Utc::now().timestamp() };
+        conn.lpush::<_, _, ()>(DEAD_LETTER_QUEUE, serde_json::to_string(&entry)?).await?;
+        let max_len = crate::config::env_parse("DLQ_MAX_LEN", 10_000isize);
+        conn.ltrim::<_, ()>(DEAD_LETTER_QUEUE, 0, max_len - 1).await?;
+        Ok(())
+    }
+
+    /// Atomically count one crawl against today's (UTC) cluster-wide quota.
+    /// Returns Ok(Some(reservation)) if admitted, Ok(None) if `limit` is already
+    /// reached (the increment is rolled back). `limit` 0 means unlimited.
+    pub async fn reserve_daily_crawl(&self, limit: u64) -> Result<Option<QuotaReservation>> {
+        let mut conn = self.conn.clone();
+        let key = daily_quota_key(chrono::Utc::now().date_naive());
+        let used: u64 = conn.incr(&key, 1).await?;
+        if used == 1 {
+            // Keep yesterday's counter around briefly for inspection, then let it go
+            conn.expire::<_, ()>(&key, 2 * 86_400).await?;
+        }
+        if limit > 0 && used > limit {
+            conn.decr::<_, _, ()>(&key, 1).await?;
+            return Ok(None);
+        }
+        Ok(Some(QuotaReservation { key, used }))
+    }
+
+    /// Return a unit taken by [`reserve_daily_crawl`](Self::reserve_daily_crawl)
+    /// for a crawl that was never queued, to the day it was taken from
+    pub async fn release_daily_crawl(&self, reservation: &QuotaReservation) -> Result<()> {
+        let mut conn = self.conn.clone();
+        redis::Script::new(RELEASE_QUOTA_SCRIPT)
+            .key(&reservation.key)
+            .invoke_async::<_, i64>(&mut conn)
+            .await?;
+        Ok(())
+    }
+
+    /// Crawls counted so far today (UTC)
+    pub async fn daily_crawl_count(&self) -> Result<u64> {
+        let mut conn = self.conn.clone();
+        let used: Option<u64> = conn.get(daily_quota_key(chrono::Utc::now().date_naive())).await?;
+        Ok(used.unwrap_or(0))
+    }
+
+    /// Number of jobs waiting in the queue (all fair queues included)
+    pub async fn queue_len(&self) -> Result<usize> {
+        let mut conn = self.conn.clone();
+        let mut len: usize = 0;
+        for priority in JobPriority::ALL {
+            let tier_len: usize = conn.llen(priority.queue_key()).await?;
+            len += tier_len;
+        }
+        let keys: Vec<String> = conn.smembers(FAIR_MEMBERS).await?;
+        for key in keys {
+            let fair_len: usize = conn.llen(fair_queue_key(&key)).await?;
+            len += fair_len;
+        }
+        Ok(len)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_priority_aging_prevents_starvation() {
+        assert_eq!(effective_priority(JobPriority::Low, 0, 300), 0);
+        assert_eq!(effective_priority(JobPriority::Low, 650, 300), 2);
+        assert_eq!(effective_priority(JobPriority::Low, 650, 0), 0);
+
+        let now = 10_000;
+        // Fresh jobs: strict priority order
+        let fresh = [
+            (JobPriority::High, Some(now - 5)),
+            (JobPriority::Normal, Some(now - 10)),
+            (JobPriority::Low, Some(now - 20)),
+        ];
+        assert_eq!(pick_priority(&fresh, now, 300), Some(JobPriority::High));
+
+        // A low job that waited past two aging periods overtakes a fresh high job
+        let starving = [
+            (JobPriority::High, Some(now - 5)),
+            (JobPriority::Normal, None),
+            (JobPriority::Low, Some(now - 900)),
+        ];
+        assert_eq!(pick_priority(&starving, now, 300), Some(JobPriority::Low));
+        assert_eq!(pick_priority(&starving, now, 0), Some(JobPriority::High));
+
+        assert_eq!(pick_priority(&[(JobPriority::High, None)], now, 300), None);
+    }
+
+    #[test]
+    fn test_retry_backoff_doubles_and_caps() {
+        assert_eq!(retry_backoff_with(1, 30, 900).as_secs(), 30);
+        assert_eq!(retry_backoff_with(2, 30, 900).as_secs(), 60);
+        assert_eq!(retry_backoff_with(3, 30, 900).as_secs(), 120);
+        assert_eq!(retry_backoff_with(10, 30, 900).as_secs(), 900);
+        assert_eq!(retry_backoff_with(u32::MAX, 30, 900).as_secs(), 900);
+    }
+
+    #[test]
+    fn test_job_attempts_default_for_queued_json() {
+        let json = r#"{"id":"t1","user_id":"u1","keyword":"rust","engine":"bing","selectors":null}"#;
+        let job: CrawlJob = serde_json::from_str(json).unwrap();
+        assert_eq!(job.attempts, 0);
+        assert_eq!(job.max_deep_extract, 1);
+    }
+
+    #[test]
+    fn test_daily_quota_key_rolls_over_per_utc_day() {
+        let day = chrono::NaiveDate::from_ymd_opt(2024, 3, 9).unwrap();
+        assert_eq!(daily_quota_key(day), "crawl_quota:2024-03-09");
+        assert_ne!(daily_quota_key(day), daily_quota_key(day.succ_opt().unwrap()));
+    }
 }
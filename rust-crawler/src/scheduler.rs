@@ -31,16 +31,127 @@ pub async fn start_scheduler(state: Arc<AppState>) -> anyhow::Result<()> {
                     keyword: "daily trend analysis".to_string(),
                     engine: "bing".to_string(),
                     selectors: None,
+                    engine_options: None,
+                    result_include_patterns: vec![],
+                    result_exclude_patterns: vec![],
+                    capture_thumbnail: false,
+                    screenshot: false,
+                    warmup: crate::config::env_flag("SEARCH_WARMUP", false),
+                    crawl_related: false,
+                    parent_task_id: None,
+                    referer: None,
+                    metadata: None,
+                    click_sequence: vec![],
+                    tag: Some("scheduler".to_string()),
+                    max_attempts: None,
+                    deep_crawl: true,
+                    batch_id: None,
+                    priority: Default::default(),
+                    storage_prefix: None,
+                    extract_entities: false,
+                    stealth: None,
+                    max_deep_extract: 1,
+                    pages: None,
+                    attempts: 0,
+                    sticky: false,
+                    respect_robots: false,
+                    callback_url: None,
+                    enqueued_at: 0,
                 };
 
+                if let Err(e) = crate::db::insert_task(&state.pool, &job, "pending").await {
+                    eprintln!("⚠️ [Scheduler] Failed to record pending task {}: {}", job.id, e);
+                }
+                let task_id = job.id.clone();
                 match state.queue.push_job(job).await {
                     Ok(_) => println!("✅ [Scheduler] Daily job queued successfully."),
-                    Err(e) => eprintln!("❌ [Scheduler] Failed to queue daily job: {}", e),
+                    Err(e) => {
+                        eprintln!("❌ [Scheduler] Failed to queue daily job: {}", e);
+                        // Never queued, so don't leave the row pending forever
+                        let error = format!("Failed to queue job: {}", e);
+                        if let Err(e) = crate::db::update_task_status(&state.pool, &task_id, "failed", Some(&error), None).await {
+                            eprintln!("⚠️ [Scheduler] Failed to mark task {} failed: {}", task_id, e);
+                        }
+                    }
                 }
             })
         })?
     ).await?;
 
+    // 3. Prune proxies dead for longer than PROXY_DEAD_AFTER_HOURS (hourly, opt-in)
+    let dead_after_hours: u64 = crate::config::env_parse("PROXY_DEAD_AFTER_HOURS", 0);
+    if dead_after_hours > 0 {
+        let state_clone = state.clone();
+        sched.add(
+            Job::new_async("0 0 * * * *", move |_uuid, _l| {
+                let state = state_clone.clone();
+                Box::pin(async move {
+                    let removed = crate::proxy::PROXY_MANAGER.prune_dead((dead_after_hours * 3600) as i64);
+                    if !removed.is_empty() {
+                        println!("⏰ [Scheduler] Pruned {} dead proxies: {}", removed.len(), removed.join(", "));
+                    }
+                    // Otherwise they'd be reloaded on the next restart
+                    for id in &removed {
+                        if let Err(e) = crate::db::delete_proxy(&state.pool, id).await {
+                            eprintln!("⚠️ [Scheduler] Failed to delete stored proxy {}: {}", id, e);
+                        }
+                    }
+                })
+            })?
+        ).await?;
+    }
+
+    // 4. Probe every proxy concurrently every PROXY_HEALTH_INTERVAL_SECS (default 60, 0 = off;
+    // PROXY_HEALTHCHECK_INTERVAL_SECS is still honoured). Passing probes re-enable proxies
+    // disabled by transient failures and record latency. PROXY_HEALTHCHECK_CONCURRENCY caps
+    // parallel probes so large pools finish in seconds.
+    let healthcheck_interval: u64 = crate::config::env_parse(
+        "PROXY_HEALTH_INTERVAL_SECS",
+        crate::config::env_parse("PROXY_HEALTHCHECK_INTERVAL_SECS", 60),
+    );
+    if healthcheck_interval > 0 {
+        sched.add(
+            Job::new_repeated_async(std::time::Duration::from_secs(healthcheck_interval), |_uuid, _l| {
+                Box::pin(async move {
+                    if !crate::proxy::PROXY_MANAGER.has_proxies() {
+                        return;
+                    }
+                    let url = std::env::var("PROXY_HEALTHCHECK_URL")
+                        .unwrap_or_else(|_| "https://api.ipify.org?format=json".to_string());
+                    let concurrency = crate::config::env_parse("PROXY_HEALTHCHECK_CONCURRENCY", 32usize);
+                    let timeout = std::time::Duration::from_secs(crate::config::env_parse("PROXY_HEALTHCHECK_TIMEOUT_SECS", 10));
+                    let summary = crate::proxy::PROXY_MANAGER.health_check(&url, concurrency, timeout).await;
+                    println!(
+                        "⏰ [Scheduler] Proxy health check: {}/{} passed, {} failed, {} skipped in {}ms (avg latency {:?}ms)",
+                        summary.passed, summary.checked, summary.failed, summary.skipped, summary.elapsed_ms,
+                        crate::proxy::PROXY_MANAGER.get_stats().avg_latency_ms
+                    );
+                })
+            })?
+        ).await?;
+    }
+
+    // 5. Daily Parquet export of yesterday's results (00:15 UTC, opt-in via PARQUET_EXPORT_DAILY)
+    if crate::config::env_flag("PARQUET_EXPORT_DAILY", false) {
+        let state_clone = state.clone();
+        sched.add(
+            Job::new_async("0 15 0 * * *", move |_uuid, _l| {
+                let state = state_clone.clone();
+                Box::pin(async move {
+                    let Some(ref storage) = state.storage else {
+                        eprintln!("⚠️ [Scheduler] Parquet export skipped: no object storage");
+                        return;
+                    };
+                    let yesterday = chrono::Utc::now().date_naive() - chrono::Duration::days(1);
+                    let scope = crate::export::ExportScope::Day(yesterday);
+                    if let Err(e) = crate::export::export_parquet(&state.pool, storage, &scope).await {
+                        eprintln!("❌ [Scheduler] Parquet export for {} failed: {}", yesterday, e);
+                    }
+                })
+            })?
+        ).await?;
+    }
+
     // Start the scheduler
     sched.start().await?;
     println!("✅ Central Scheduler Started (Rust Native)");
@@ -0,0 +1,112 @@
+//! Ed25519 signing of stored crawl results for tamper-evidence.
+//!
+//! The signed message binds the task id, the SHA-256 of the stored HTML,
+//! the SHA-256 of the stored results JSON and the signing timestamp.
+//! Configure with `RESULT_SIGNING_KEY` (hex-encoded 32-byte Ed25519 seed).
+
+use once_cell::sync::Lazy;
+use ring::signature::{Ed25519KeyPair, KeyPair, UnparsedPublicKey, ED25519};
+
+/// Global signer, `None` when signing is not configured
+pub static RESULT_SIGNER: Lazy<Option<ResultSigner>> = Lazy::new(|| {
+    let seed_hex = std::env::var("RESULT_SIGNING_KEY").ok()?;
+    match ResultSigner::from_seed_hex(&seed_hex) {
+        Ok(signer) => {
+            println!("🔏 Result signing enabled (key {})", signer.key_id());
+            Some(signer)
+        }
+        Err(e) => {
+            eprintln!("⚠️ RESULT_SIGNING_KEY is invalid, signing disabled: {}", e);
+            None
+        }
+    }
+});
+
+/// Signature record stored alongside a task
+#[derive(Debug, Clone)]
+pub struct ResultSignature {
+    /// Hex-encoded Ed25519 signature
+    pub signature: String,
+    /// RFC 3339 timestamp included in the signed message
+    pub signed_at: String,
+    /// Hex-encoded public key that produced the signature
+    pub key_id: String,
+}
+
+pub struct ResultSigner {
+    key_pair: Ed25519KeyPair,
+}
+
+impl ResultSigner {
+    pub fn from_seed_hex(seed_hex: &str) -> Result<Self, String> {
+        let seed = hex::decode(seed_hex.trim()).map_err(|e| format!("not valid hex: {}", e))?;
+        if seed.len() != 32 {
+            return Err(format!("expected 32 bytes, got {}", seed.len()));
+        }
+        let key_pair = Ed25519KeyPair::from_seed_unchecked(&seed).map_err(|e| e.to_string())?;
+        Ok(Self { key_pair })
+    }
+
+    /// Public key reference stored with each signature
+    pub fn key_id(&self) -> String {
+        hex::encode(self.key_pair.public_key().as_ref())
+    }
+
+    pub fn sign(&self, task_id: &str, html: &str, results_json: &str) -> ResultSignature {
+        let signed_at = chrono::Utc::now().to_rfc3339();
+        let message = signing_message(task_id, html, results_json, &signed_at);
+        let signature = self.key_pair.sign(message.as_bytes());
+        ResultSignature {
+            signature: hex::encode(signature.as_ref()),
+            signed_at,
+            key_id: self.key_id(),
+        }
+    }
+}
+
+fn sha256_hex(data: &str) -> String {
+    hex::encode(ring::digest::digest(&ring::digest::SHA256, data.as_bytes()).as_ref())
+}
+
+/// Canonical message that gets signed
+pub fn signing_message(task_id: &str, html: &str, results_json: &str, signed_at: &str) -> String {
+    format!(
+        "crawl-result-v1\n{}\n{}\n{}\n{}",
+        task_id,
+        sha256_hex(html),
+        sha256_hex(results_json),
+        signed_at
+    )
+}
+
+/// Recompute the message from stored content and check the signature against the stored key
+pub fn verify(task_id: &str, html: &str, results_json: &str, sig: &ResultSignature) -> Result<(), String> {
+    let public_key = hex::decode(&sig.key_id).map_err(|e| format!("bad key id: {}", e))?;
+    let signature = hex::decode(&sig.signature).map_err(|e| format!("bad signature encoding: {}", e))?;
+    let message = signing_message(task_id, html, results_json, &sig.signed_at);
+
+    UnparsedPublicKey::new(&ED25519, &public_key)
+        .verify(message.as_bytes(), &signature)
+        .map_err(|_| "signature does not match content".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SEED: &str = "9d61b19deffd5a60ba844af492ec2cc44449c5697b326919703bac031cae7f60";
+
+    #[test]
+    fn test_sign_and_verify_roundtrip() {
+        let signer = ResultSigner::from_seed_hex(SEED).unwrap();
+        let sig = signer.sign("task-1", "<html></html>", "{\"results\":[]}");
+        assert!(verify("task-1", "<html></html>", "{\"results\":[]}", &sig).is_ok());
+        assert!(verify("task-1", "<html>tampered</html>", "{\"results\":[]}", &sig).is_err());
+    }
+
+    #[test]
+    fn test_rejects_bad_seed() {
+        assert!(ResultSigner::from_seed_hex("abcd").is_err());
+        assert!(ResultSigner::from_seed_hex("not-hex").is_err());
+    }
+}
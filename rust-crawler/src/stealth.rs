@@ -8,12 +8,62 @@
 
 use once_cell::sync::Lazy;
 use rand::seq::SliceRandom;
+use serde::{Deserialize, Serialize};
+
+/// Fingerprint values rendered into the stealth script, so they can be matched
+/// to the rotated User-Agent. Defaults reproduce the built-in profile.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(default)]
+pub struct StealthConfig {
+    /// `navigator.hardwareConcurrency`; randomized per page (4-10) when unset
+    #[schema(example = 8)]
+    pub hardware_concurrency: Option<u32>,
+    /// `navigator.platform`; Chrome's own value when unset
+    #[schema(example = "Win32")]
+    pub platform: Option<String>,
+    /// `UNMASKED_VENDOR_WEBGL`
+    #[schema(example = "Intel Inc.")]
+    pub webgl_vendor: String,
+    /// `UNMASKED_RENDERER_WEBGL`
+    #[schema(example = "Intel Iris OpenGL Engine")]
+    pub webgl_renderer: String,
+    /// Add noise to `canvas.toDataURL()`
+    pub spoof_canvas: bool,
+    /// Remove `RTCPeerConnection` so WebRTC can't leak the local IP
+    pub block_webrtc: bool,
+}
 
-/// Generate the main stealth injection script
-/// This script runs before any other script on the page (via Page.addScriptToEvaluateOnNewDocument)
+impl Default for StealthConfig {
+    fn default() -> Self {
+        Self {
+            hardware_concurrency: None,
+            platform: None,
+            webgl_vendor: "Intel Inc.".to_string(),
+            webgl_renderer: "Intel Iris OpenGL Engine".to_string(),
+            spoof_canvas: true,
+            block_webrtc: true,
+        }
+    }
+}
+
+/// Upper bound accepted for `StealthConfig::hardware_concurrency`
+pub const MAX_HARDWARE_CONCURRENCY: u32 = 128;
+
+/// Generate the main stealth injection script with the default profile
 pub fn get_stealth_script() -> String {
-    // We construct the script dynamically to allow for randomization per session
-    
+    render_stealth_script(&StealthConfig::default())
+}
+
+/// Generate the main stealth injection script for `config`.
+/// This script runs before any other script on the page (via Page.addScriptToEvaluateOnNewDocument)
+pub fn render_stealth_script(config: &StealthConfig) -> String {
+    let hardware_concurrency = match config.hardware_concurrency {
+        Some(cores) => cores.to_string(),
+        None => "4 + Math.floor(Math.random() * 4) * 2".to_string(), // 4, 6, 8, 10...
+    };
+    // JSON string literals are valid JS string literals (quotes and backslashes escaped)
+    let js_string = |s: &str| serde_json::to_string(s).unwrap_or_else(|_| "\"\"".to_string());
+
     let base_script = r#"
         // ============================================================================
         // 🛡️ ANTI-FINGERPRINTING & HARDENING (Tier 1)
@@ -24,11 +74,18 @@ pub fn get_stealth_script() -> String {
             get: () => undefined,
         });
 
-        // 2. Hardware Concurrency Spoofing (Randomize 4-16)
+        // 2. Hardware Concurrency Spoofing
         Object.defineProperty(navigator, 'hardwareConcurrency', {
-            get: () => 4 + Math.floor(Math.random() * 4) * 2, // 4, 6, 8, 10...
+            get: () => __HARDWARE_CONCURRENCY__,
         });
 
+        // 2b. Platform matching the User-Agent
+        if (__PLATFORM__ !== null) {
+            Object.defineProperty(navigator, 'platform', {
+                get: () => __PLATFORM__,
+            });
+        }
+
         // 3. Memory Spoofing (Randomize 4-32 GB)
         Object.defineProperty(navigator, 'deviceMemory', {
             get: () => 4 + Math.floor(Math.random() * 4) * 4, // 4, 8, 16, 24...
@@ -100,11 +157,13 @@ pub fn get_stealth_script() -> String {
         // Some sites check if WebRTC is completely missing to detect bots.
         // Better to mock it or leave it but ensure it doesn't leak local IP.
         // For now, we disable it as it's the safest 'nuclear' option against IP leaks.
-        ['RTCPeerConnection', 'webkitRTCPeerConnection', 'mozRTCPeerConnection', 'msRTCPeerConnection'].forEach(className => {
-             if (window[className]) {
-                 window[className] = undefined;
-             }
-        });
+        if (__BLOCK_WEBRTC__) {
+            ['RTCPeerConnection', 'webkitRTCPeerConnection', 'mozRTCPeerConnection', 'msRTCPeerConnection'].forEach(className => {
+                 if (window[className]) {
+                     window[className] = undefined;
+                 }
+            });
+        }
 
         // ============================================================================
         // 🎨 FINGERPRINT SPOOFING (Tier 2 - Canvas/WebGL/Audio)
@@ -114,7 +173,7 @@ pub fn get_stealth_script() -> String {
         const originalToDataURL = HTMLCanvasElement.prototype.toDataURL;
         HTMLCanvasElement.prototype.toDataURL = function(...args) {
             // Only inject noise if the canvas is large enough to be a fingerprint attempt
-            if (this.width > 16 && this.height > 16) {
+            if (__SPOOF_CANVAS__ && this.width > 16 && this.height > 16) {
                 const context = this.getContext('2d');
                 if (context) {
                     const imageData = context.getImageData(0, 0, this.width, this.height);
@@ -138,9 +197,9 @@ pub fn get_stealth_script() -> String {
         const getParameter = WebGLRenderingContext.prototype.getParameter;
         WebGLRenderingContext.prototype.getParameter = function(parameter) {
             // UNMASKED_VENDOR_WEBGL
-            if (parameter === 37445) return 'Intel Inc.';
+            if (parameter === 37445) return __WEBGL_VENDOR__;
             // UNMASKED_RENDERER_WEBGL
-            if (parameter === 37446) return 'Intel Iris OpenGL Engine';
+            if (parameter === 37446) return __WEBGL_RENDERER__;
             return getParameter.apply(this, [parameter]);
         };
 
@@ -211,7 +270,13 @@ pub fn get_stealth_script() -> String {
         console.log("🛡️ Stealth Injection Complete");
     "#;
 
-    base_script.to_string()
+    base_script
+        .replace("__HARDWARE_CONCURRENCY__", &hardware_concurrency)
+        .replace("__PLATFORM__", &config.platform.as_deref().map_or("null".to_string(), js_string))
+        .replace("__BLOCK_WEBRTC__", &config.block_webrtc.to_string())
+        .replace("__SPOOF_CANVAS__", &config.spoof_canvas.to_string())
+        .replace("__WEBGL_VENDOR__", &js_string(&config.webgl_vendor))
+        .replace("__WEBGL_RENDERER__", &js_string(&config.webgl_renderer))
 }
 
 /// JS to simulate realistic human mouse movement
@@ -292,6 +357,57 @@ mod tests {
         assert!(script.contains("HTMLCanvasElement.prototype.toDataURL"));
         println!("Stealth script generated successfully, length: {}", script.len());
     }
+
+    #[test]
+    fn test_stealth_config_rendering() {
+        let default_script = get_stealth_script();
+        assert!(default_script.contains("return \"Intel Iris OpenGL Engine\";"));
+        assert!(default_script.contains("get: () => 4 + Math.floor(Math.random() * 4) * 2,"));
+        assert!(default_script.contains("if (true) {"));
+        assert!(default_script.contains("if (null !== null) {"));
+        assert!(!default_script.contains("__"));
+
+        let config = StealthConfig {
+            hardware_concurrency: Some(12),
+            platform: Some("Win32".to_string()),
+            webgl_vendor: "Google Inc. (NVIDIA)".to_string(),
+            webgl_renderer: "ANGLE (NVIDIA \"RTX\" 3060)".to_string(),
+            spoof_canvas: false,
+            block_webrtc: false,
+        };
+        let script = render_stealth_script(&config);
+        assert!(script.contains("get: () => 12,"));
+        assert!(script.contains("get: () => \"Win32\","));
+        assert!(script.contains("return \"Google Inc. (NVIDIA)\";"));
+        assert!(script.contains(r#"return "ANGLE (NVIDIA \"RTX\" 3060)";"#));
+        assert!(script.contains("if (false) {"));
+        assert!(script.contains("if (false && this.width > 16"));
+    }
+
+    #[test]
+    fn test_typing_delay_bounds_and_pauses() {
+        use rand::SeedableRng;
+        let mut rng = rand::rngs::StdRng::seed_from_u64(7);
+
+        for distribution in [TypingDistribution::Uniform, TypingDistribution::Gaussian, TypingDistribution::LogNormal] {
+            let delay = TypingDelay { distribution, pause_chance: 0.0, ..TypingDelay::default() };
+            let samples: Vec<u64> = (0..2000).map(|_| delay.sample_ms(&mut rng)).collect();
+            assert!(samples.iter().all(|&ms| (40..=600).contains(&ms)), "{:?} out of bounds", distribution);
+            let mean = samples.iter().sum::<u64>() as f64 / samples.len() as f64;
+            assert!((110.0..170.0).contains(&mean), "{:?} mean {}", distribution, mean);
+        }
+
+        let always_pause = TypingDelay { pause_chance: 1.0, ..TypingDelay::default() };
+        assert!((0..100).all(|_| always_pause.sample_ms(&mut rng) >= 440));
+    }
+
+    #[test]
+    fn test_chrome_cipher_suites_resolve_in_order() {
+        let suites = resolve_cipher_suites(CHROME_CIPHER_SUITES);
+        assert_eq!(suites.len(), CHROME_CIPHER_SUITES.len());
+        assert_eq!(suites[0].suite(), rustls::CipherSuite::TLS13_AES_128_GCM_SHA256);
+        assert!(resolve_cipher_suites(&["TLS_RSA_WITH_RC4_128_MD5"]).is_empty());
+    }
 }
 
 // ============================================================================
@@ -454,3 +570,198 @@ pub async fn apply_stealth_settings(tab: &std::sync::Arc<Tab>, timezone_id: &str
 
     Ok(())
 }
+
+// ============================================================================
+// ⌨️ TYPING RHYTHM
+// ============================================================================
+
+/// Shape of the per-keystroke delay distribution
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TypingDistribution {
+    /// Flat random in [mean - stddev, mean + stddev]
+    Uniform,
+    Gaussian,
+    /// Right-skewed like real typing: mostly quick keys, occasional slow ones
+    LogNormal,
+}
+
+/// Inter-keystroke delay generator with occasional "thinking" pauses
+#[derive(Debug, Clone)]
+pub struct TypingDelay {
+    pub distribution: TypingDistribution,
+    pub mean_ms: f64,
+    pub stddev_ms: f64,
+    /// Floor/ceiling applied to every sampled keystroke delay
+    pub min_ms: f64,
+    pub max_ms: f64,
+    /// Probability (0-1) of adding a thinking pause after a keystroke
+    pub pause_chance: f64,
+    pub pause_min_ms: u64,
+    pub pause_max_ms: u64,
+}
+
+/// Configured via TYPING_DELAY_DIST (uniform|gaussian|lognormal), TYPING_DELAY_MEAN_MS,
+/// TYPING_DELAY_STDDEV_MS, TYPING_DELAY_MIN_MS, TYPING_DELAY_MAX_MS,
+/// TYPING_PAUSE_CHANCE, TYPING_PAUSE_MIN_MS and TYPING_PAUSE_MAX_MS
+pub static TYPING_DELAY: Lazy<TypingDelay> = Lazy::new(TypingDelay::from_env);
+
+impl Default for TypingDelay {
+    fn default() -> Self {
+        Self {
+            distribution: TypingDistribution::LogNormal,
+            mean_ms: 140.0,
+            stddev_ms: 60.0,
+            min_ms: 40.0,
+            max_ms: 600.0,
+            pause_chance: 0.05,
+            pause_min_ms: 400,
+            pause_max_ms: 1500,
+        }
+    }
+}
+
+impl TypingDelay {
+    pub fn from_env() -> Self {
+        use crate::config::env_parse;
+        let d = Self::default();
+        let distribution = match std::env::var("TYPING_DELAY_DIST").unwrap_or_default().to_lowercase().as_str() {
+            "uniform" => TypingDistribution::Uniform,
+            "gaussian" | "normal" => TypingDistribution::Gaussian,
+            _ => d.distribution,
+        };
+        let pause_min_ms = env_parse("TYPING_PAUSE_MIN_MS", d.pause_min_ms);
+        Self {
+            distribution,
+            mean_ms: env_parse("TYPING_DELAY_MEAN_MS", d.mean_ms),
+            stddev_ms: env_parse("TYPING_DELAY_STDDEV_MS", d.stddev_ms).max(0.0),
+            min_ms: env_parse("TYPING_DELAY_MIN_MS", d.min_ms),
+            max_ms: env_parse("TYPING_DELAY_MAX_MS", d.max_ms),
+            pause_chance: env_parse("TYPING_PAUSE_CHANCE", d.pause_chance).clamp(0.0, 1.0),
+            pause_min_ms,
+            pause_max_ms: env_parse("TYPING_PAUSE_MAX_MS", d.pause_max_ms).max(pause_min_ms),
+        }
+    }
+
+    /// Standard normal sample (Box-Muller)
+    fn standard_normal<R: Rng>(rng: &mut R) -> f64 {
+        let u1: f64 = 1.0 - rng.gen::<f64>(); // (0, 1], keeps ln() finite
+        let u2: f64 = rng.gen();
+        (-2.0 * u1.ln()).sqrt() * (2.0 * std::f64::consts::PI * u2).cos()
+    }
+
+    /// One keystroke delay in milliseconds (pause included when triggered)
+    pub fn sample_ms<R: Rng>(&self, rng: &mut R) -> u64 {
+        let raw = match self.distribution {
+            TypingDistribution::Uniform => {
+                if self.stddev_ms > 0.0 {
+                    rng.gen_range((self.mean_ms - self.stddev_ms)..(self.mean_ms + self.stddev_ms))
+                } else {
+                    self.mean_ms
+                }
+            }
+            TypingDistribution::Gaussian => self.mean_ms + self.stddev_ms * Self::standard_normal(rng),
+            TypingDistribution::LogNormal => {
+                // Parameters chosen so the distribution has the configured mean/stddev
+                let mean = self.mean_ms.max(1.0);
+                let sigma2 = (1.0 + (self.stddev_ms * self.stddev_ms) / (mean * mean)).ln();
+                let mu = mean.ln() - sigma2 / 2.0;
+                (mu + sigma2.sqrt() * Self::standard_normal(rng)).exp()
+            }
+        };
+        let mut delay = raw.clamp(self.min_ms, self.max_ms.max(self.min_ms)) as u64;
+
+        if self.pause_chance > 0.0 && rng.gen_bool(self.pause_chance) {
+            delay += rng.gen_range(self.pause_min_ms..=self.pause_max_ms);
+        }
+        delay
+    }
+
+    pub fn next_delay(&self) -> std::time::Duration {
+        std::time::Duration::from_millis(self.sample_ms(&mut rand::thread_rng()))
+    }
+}
+
+// ============================================================================
+// 🔐 TLS FINGERPRINT (reqwest fast path)
+// ============================================================================
+//
+// The reqwest fast path uses rustls, whose default ClientHello (cipher order,
+// no ALPN) produces a JA3 hash no browser has. We can reorder ciphers and
+// advertise ALPN like Chrome, which removes the most obvious mismatch, but we
+// can NOT fully mimic Chrome: rustls has no GREASE values, no TLS 1.2 CBC/RSA
+// suites, fixed extension order, no extension permutation and no
+// compress_certificate/application_settings extensions. Sites doing strict
+// JA3/JA4 matching will still tell the two apart; route those through Chrome.
+
+/// Chrome's cipher preference order, restricted to suites rustls implements
+pub const CHROME_CIPHER_SUITES: &[&str] = &[
+    "TLS13_AES_128_GCM_SHA256",
+    "TLS13_AES_256_GCM_SHA384",
+    "TLS13_CHACHA20_POLY1305_SHA256",
+    "TLS_ECDHE_ECDSA_WITH_AES_128_GCM_SHA256",
+    "TLS_ECDHE_RSA_WITH_AES_128_GCM_SHA256",
+    "TLS_ECDHE_ECDSA_WITH_AES_256_GCM_SHA384",
+    "TLS_ECDHE_RSA_WITH_AES_256_GCM_SHA384",
+    "TLS_ECDHE_ECDSA_WITH_CHACHA20_POLY1305_SHA256",
+    "TLS_ECDHE_RSA_WITH_CHACHA20_POLY1305_SHA256",
+];
+
+/// Resolve cipher suite names (rustls spelling) in the given order; unknown names are skipped
+pub fn resolve_cipher_suites(names: &[&str]) -> Vec<rustls::SupportedCipherSuite> {
+    names
+        .iter()
+        .filter_map(|name| {
+            let found = rustls::ALL_CIPHER_SUITES
+                .iter()
+                .find(|suite| format!("{:?}", suite.suite()).eq_ignore_ascii_case(name.trim()))
+                .copied();
+            if found.is_none() {
+                eprintln!("⚠️ Unknown TLS cipher suite '{}' ignored", name);
+            }
+            found
+        })
+        .collect()
+}
+
+/// rustls config for the fast path. Configured via TLS_CHROME_PROFILE (default true),
+/// TLS_CIPHER_SUITES (comma-separated, defaults to Chrome's order) and TLS_ALPN
+/// (default "h2,http/1.1"). Returns None when disabled so reqwest keeps its defaults.
+pub fn fast_path_tls_config() -> Option<rustls::ClientConfig> {
+    if !crate::config::env_flag("TLS_CHROME_PROFILE", true) {
+        return None;
+    }
+
+    let suite_names = std::env::var("TLS_CIPHER_SUITES").unwrap_or_else(|_| CHROME_CIPHER_SUITES.join(","));
+    let suites = resolve_cipher_suites(&suite_names.split(',').filter(|s| !s.trim().is_empty()).collect::<Vec<_>>());
+    if suites.is_empty() {
+        eprintln!("⚠️ TLS_CIPHER_SUITES matched no supported suites, using reqwest defaults");
+        return None;
+    }
+
+    let mut roots = rustls::RootCertStore::empty();
+    roots.add_trust_anchors(webpki_roots::TLS_SERVER_ROOTS.iter().map(|ta| {
+        rustls::OwnedTrustAnchor::from_subject_spki_name_constraints(ta.subject, ta.spki, ta.name_constraints)
+    }));
+
+    let builder = match rustls::ClientConfig::builder()
+        .with_cipher_suites(&suites)
+        .with_safe_default_kx_groups()
+        .with_protocol_versions(&[&rustls::version::TLS13, &rustls::version::TLS12])
+    {
+        Ok(builder) => builder,
+        Err(e) => {
+            eprintln!("⚠️ Invalid TLS profile ({}), using reqwest defaults", e);
+            return None;
+        }
+    };
+    let mut config = builder.with_root_certificates(roots).with_no_client_auth();
+
+    config.alpn_protocols = std::env::var("TLS_ALPN")
+        .unwrap_or_else(|_| "h2,http/1.1".to_string())
+        .split(',')
+        .map(|p| p.trim())
+        .filter(|p| !p.is_empty())
+        .map(|p| p.as_bytes().to_vec())
+        .collect();
+    Some(config)
+}
@@ -1,39 +1,80 @@
 use aws_sdk_s3::{Client, config::Region};
 use aws_config::meta::region::RegionProviderChain;
 use aws_sdk_s3::config::Credentials;
+use aws_sdk_s3::presigning::PresigningConfig;
 use aws_sdk_s3::primitives::ByteStream;
 use anyhow::Result;
+use serde::Serialize;
 use std::env;
+use std::time::Duration;
 
+/// One S3-compatible bucket that results are written to
 #[derive(Clone)]
-pub struct StorageManager {
+pub struct StorageBackend {
+    pub name: String,
     client: Client,
     bucket: String,
 }
 
-impl StorageManager {
-    pub async fn new() -> Result<Self> {
-        let endpoint = env::var("MINIO_ENDPOINT").unwrap_or_else(|_| "http://localhost:9000".to_string());
-        let access_key = env::var("MINIO_ROOT_USER").unwrap_or_else(|_| "minio_user".to_string());
-        let secret_key = env::var("MINIO_ROOT_PASSWORD").unwrap_or_else(|_| "minio_password".to_string());
-        let bucket = env::var("MINIO_BUCKET").unwrap_or_else(|_| "crawler-data".to_string());
-
-        let region_provider = RegionProviderChain::default_provider().or_else(Region::new("us-east-1"));
-        let config = aws_config::from_env()
-            .region(region_provider)
-            .endpoint_url(&endpoint)
-            .credentials_provider(Credentials::new(
-                access_key,
-                secret_key,
-                None,
-                None,
-                "static",
-            ))
-            .load()
-            .await;
+/// Outcome of a write to a single backend
+#[derive(Debug, Clone, Serialize)]
+pub struct BackendWriteStatus {
+    pub backend: String,
+    pub ok: bool,
+    pub error: Option<String>,
+}
+
+/// Writes go to every configured backend: the primary MinIO/S3 bucket
+/// (MINIO_* vars) plus any extra backends listed in STORAGE_EXTRA_BACKENDS.
+/// Each extra backend `<NAME>` reads STORAGE_<NAME>_ENDPOINT, _ACCESS_KEY,
+/// _SECRET_KEY, _BUCKET and _REGION.
+#[derive(Clone)]
+pub struct StorageManager {
+    backends: Vec<StorageBackend>,
+}
+
+impl StorageBackend {
+    async fn connect(
+        name: &str,
+        endpoint: Option<String>,
+        access_key: Option<String>,
+        secret_key: Option<String>,
+        bucket: String,
+        region: Option<String>,
+        max_attempts: u32,
+    ) -> Result<Self> {
+        // Static keys => MinIO (defaults to the local endpoint).
+        // No keys => default AWS credential chain (env, profile, IMDS/instance role, STS web identity),
+        // talking to real S3 unless an endpoint points somewhere else.
+        let static_credentials = match (access_key, secret_key) {
+            (Some(a), Some(s)) => Some(Credentials::new(a, s, None, None, "static")),
+            _ => None,
+        };
+        let endpoint = endpoint.or_else(|| {
+            static_credentials
+                .as_ref()
+                .map(|_| "http://localhost:9000".to_string())
+        });
 
+        let region_provider = RegionProviderChain::first_try(region.map(Region::new))
+            .or_default_provider()
+            .or_else(Region::new("us-east-1"));
+        let mut loader = aws_config::from_env().region(region_provider);
+        if let Some(ref endpoint) = endpoint {
+            loader = loader.endpoint_url(endpoint);
+        }
+        match static_credentials {
+            Some(credentials) => {
+                println!("🔑 Storage [{}]: using static credentials", name);
+                loader = loader.credentials_provider(credentials);
+            }
+            None => println!("🔑 Storage [{}]: no static keys, using default AWS credential chain (IAM/STS)", name),
+        }
+        let config = loader.load().await;
+
+        // Path-style addressing is only needed for MinIO/custom endpoints
         let client_config = aws_sdk_s3::config::Builder::from(&config)
-            .force_path_style(true)
+            .force_path_style(endpoint.is_some())
             .build();
         let client = Client::from_conf(client_config);
 
@@ -42,19 +83,19 @@ impl StorageManager {
         loop {
             match client.head_bucket().bucket(&bucket).send().await {
                 Ok(_) => {
-                    println!("✅ MinIO Bucket '{}' exists", bucket);
+                    println!("✅ [{}] Bucket '{}' exists", name, bucket);
                     break;
                 },
                 Err(e) => {
                     // Check if error is "NotFound" (404) or something else (DNS, Conn)
                     let is_not_found = e.into_service_error().is_not_found();
-                    
+
                     if is_not_found {
-                        println!("⚠️ MinIO Bucket '{}' not found, creating...", bucket);
+                        println!("⚠️ [{}] Bucket '{}' not found, creating...", name, bucket);
                         match client.create_bucket().bucket(&bucket).send().await {
                             Ok(_) => {
                                 println!("✅ Created bucket '{}'", bucket);
-                                break; 
+                                break;
                             },
                             Err(create_err) => {
                                 eprintln!("🔥 Failed to create bucket: {}", create_err);
@@ -64,29 +105,208 @@ impl StorageManager {
                     } else {
                         // DNS/Connection Error
                         attempts += 1;
-                        if attempts >= 30 {
-                            return Err(anyhow::anyhow!("Failed to connect to MinIO after 30 attempts"));
+                        if attempts >= max_attempts {
+                            return Err(anyhow::anyhow!("Failed to connect to storage backend '{}' after {} attempts", name, max_attempts));
                         }
-                        println!("⚠️ MinIO Connect failed (Attempt {}/30). Retrying in 2s...", attempts);
+                        println!("⚠️ [{}] Connect failed (Attempt {}/{}). Retrying in 2s...", name, attempts, max_attempts);
                         tokio::time::sleep(tokio::time::Duration::from_secs(2)).await;
                     }
                 }
             }
         }
 
-        Ok(Self { client, bucket })
+        Ok(Self { name: name.to_string(), client, bucket })
     }
 
-    pub async fn store_html(&self, key: &str, content: &str) -> Result<()> {
-        let body = ByteStream::from(content.as_bytes().to_vec());
+    async fn put(&self, key: &str, content: Vec<u8>, content_type: &str) -> Result<()> {
         self.client
             .put_object()
             .bucket(&self.bucket)
             .key(key)
-            .body(body)
-            .content_type("text/html")
+            .body(ByteStream::from(content))
+            .content_type(content_type)
             .send()
             .await?;
         Ok(())
     }
+
+    async fn delete(&self, key: &str) -> Result<()> {
+        self.client.delete_object().bucket(&self.bucket).key(key).send().await?;
+        Ok(())
+    }
+
+    /// Presigned GET for `key`. Signed against this client's endpoint and addressing
+    /// style, so a MinIO backend yields `{MINIO_ENDPOINT}/{bucket}/{key}?X-Amz-...`.
+    async fn presign_get(&self, key: &str, expires: Duration) -> Result<String> {
+        let config = PresigningConfig::expires_in(expires)?;
+        let request = self.client.get_object().bucket(&self.bucket).key(key).presigned(config).await?;
+        Ok(request.uri().to_string())
+    }
+}
+
+/// Check a per-request storage prefix against STORAGE_PREFIX_ALLOWLIST (comma-separated).
+/// The prefix must equal an allowlisted entry or sit beneath one ("tenants" allows
+/// "tenants/acme"); segments are limited to `[A-Za-z0-9._-]` and may not be `.`/`..`.
+/// Returns the prefix without leading/trailing slashes.
+pub fn validate_storage_prefix(prefix: &str, allowlist: &[String]) -> Result<String, String> {
+    let prefix = prefix.trim().trim_matches('/');
+    if prefix.is_empty() || prefix.len() > 256 {
+        return Err("must be 1-256 characters".to_string());
+    }
+    let valid_segment = |seg: &str| {
+        !seg.is_empty() && seg != "." && seg != ".." && seg.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-'))
+    };
+    if !prefix.split('/').all(valid_segment) {
+        return Err("may only contain [A-Za-z0-9._-] segments separated by '/'".to_string());
+    }
+    let allowed = allowlist.iter().map(|a| a.trim().trim_matches('/')).filter(|a| !a.is_empty()).any(|a| {
+        prefix == a || prefix.strip_prefix(a).is_some_and(|rest| rest.starts_with('/'))
+    });
+    if !allowed {
+        return Err("is not in STORAGE_PREFIX_ALLOWLIST".to_string());
+    }
+    Ok(prefix.to_string())
+}
+
+/// STORAGE_PREFIX_ALLOWLIST entries (empty = per-request prefixes disabled)
+pub fn storage_prefix_allowlist() -> Vec<String> {
+    env::var("STORAGE_PREFIX_ALLOWLIST")
+        .unwrap_or_default()
+        .split(',')
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .collect()
+}
+
+/// Object key for a job artifact, placed under the job's storage prefix when it has one
+pub fn object_key(prefix: Option<&str>, key: &str) -> String {
+    match prefix {
+        Some(prefix) => format!("{}/{}", prefix, key),
+        None => key.to_string(),
+    }
+}
+
+fn non_empty_env(key: &str) -> Option<String> {
+    env::var(key).ok().filter(|v| !v.is_empty())
+}
+
+impl StorageManager {
+    pub async fn new() -> Result<Self> {
+        // Primary backend: failure here is fatal (caller decides via STORAGE_REQUIRED)
+        let primary = StorageBackend::connect(
+            "minio",
+            non_empty_env("MINIO_ENDPOINT"),
+            non_empty_env("MINIO_ROOT_USER"),
+            non_empty_env("MINIO_ROOT_PASSWORD"),
+            env::var("MINIO_BUCKET").unwrap_or_else(|_| "crawler-data".to_string()),
+            None,
+            30,
+        )
+        .await?;
+        let mut backends = vec![primary];
+
+        // Extra backends (e.g. an S3 backup bucket): unreachable ones are skipped, not fatal
+        let extra = env::var("STORAGE_EXTRA_BACKENDS").unwrap_or_default();
+        for name in extra.split(',').map(|s| s.trim()).filter(|s| !s.is_empty()) {
+            let var = |suffix: &str| non_empty_env(&format!("STORAGE_{}_{}", name.to_uppercase(), suffix));
+            let Some(bucket) = var("BUCKET") else {
+                eprintln!("⚠️ Storage backend '{}' has no STORAGE_{}_BUCKET, skipping", name, name.to_uppercase());
+                continue;
+            };
+            match StorageBackend::connect(name, var("ENDPOINT"), var("ACCESS_KEY"), var("SECRET_KEY"), bucket, var("REGION"), 3).await {
+                Ok(backend) => backends.push(backend),
+                Err(e) => eprintln!("⚠️ Storage backend '{}' unavailable, skipping: {}", name, e),
+            }
+        }
+
+        println!("💾 Storage backends: {}", backends.iter().map(|b| b.name.as_str()).collect::<Vec<_>>().join(", "));
+        Ok(Self { backends })
+    }
+
+    pub fn backend_names(&self) -> Vec<String> {
+        self.backends.iter().map(|b| b.name.clone()).collect()
+    }
+
+    /// Write to every backend concurrently. A failing backend is logged and
+    /// reported in the returned statuses without affecting the others;
+    /// an error is returned only if no backend accepted the write.
+    async fn store_all(&self, key: &str, content: Vec<u8>, content_type: &str) -> Result<Vec<BackendWriteStatus>> {
+        let writes = self.backends.iter().map(|backend| {
+            let content = content.clone();
+            async move {
+                let result = backend.put(key, content, content_type).await;
+                if let Err(ref e) = result {
+                    eprintln!("⚠️ Storage [{}] write of {} failed: {}", backend.name, key, e);
+                }
+                BackendWriteStatus {
+                    backend: backend.name.clone(),
+                    ok: result.is_ok(),
+                    error: result.err().map(|e| e.to_string()),
+                }
+            }
+        });
+        let statuses = futures_util::future::join_all(writes).await;
+
+        if statuses.iter().any(|s| s.ok) {
+            Ok(statuses)
+        } else {
+            Err(anyhow::anyhow!("All storage backends failed for {}", key))
+        }
+    }
+
+    pub async fn store_html(&self, key: &str, content: &str) -> Result<Vec<BackendWriteStatus>> {
+        self.store_all(key, content.as_bytes().to_vec(), "text/html").await
+    }
+
+    pub async fn store_json(&self, key: &str, json: &str) -> Result<Vec<BackendWriteStatus>> {
+        self.store_all(key, json.as_bytes().to_vec(), "application/json").await
+    }
+
+    pub async fn store_bytes(&self, key: &str, content: Vec<u8>, content_type: &str) -> Result<Vec<BackendWriteStatus>> {
+        self.store_all(key, content, content_type).await
+    }
+
+    /// Time-limited download URL for `key` on the primary backend (signing is
+    /// local; the object is not checked for existence). S3 caps `expires` at 7 days.
+    pub async fn presign_get(&self, key: &str, expires: Duration) -> Result<String> {
+        let primary = self.backends.first().ok_or_else(|| anyhow::anyhow!("No storage backend configured"))?;
+        primary.presign_get(key, expires).await
+    }
+
+    /// Delete `key` from every backend. Missing objects count as deleted (S3
+    /// semantics); an error is returned only if no backend accepted the delete.
+    pub async fn delete_html(&self, key: &str) -> Result<()> {
+        let deletes = self.backends.iter().map(|backend| async move {
+            let result = backend.delete(key).await;
+            if let Err(ref e) = result {
+                eprintln!("⚠️ Storage [{}] delete of {} failed: {}", backend.name, key, e);
+            }
+            result.is_ok()
+        });
+        let results = futures_util::future::join_all(deletes).await;
+
+        if results.iter().any(|ok| *ok) {
+            Ok(())
+        } else {
+            Err(anyhow::anyhow!("All storage backends failed to delete {}", key))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_storage_prefix() {
+        let allow = vec!["tenants".to_string(), "acme-corp/".to_string()];
+        assert_eq!(validate_storage_prefix("/tenants/acme/", &allow), Ok("tenants/acme".to_string()));
+        assert_eq!(validate_storage_prefix("acme-corp", &allow), Ok("acme-corp".to_string()));
+        assert!(validate_storage_prefix("tenants/../secrets", &allow).is_err());
+        assert!(validate_storage_prefix("tenantsx/acme", &allow).is_err());
+        assert!(validate_storage_prefix("other", &allow).is_err());
+        assert!(validate_storage_prefix("tenants", &[]).is_err());
+        assert_eq!(object_key(Some("tenants/acme"), "bing/1.html"), "tenants/acme/bing/1.html");
+        assert_eq!(object_key(None, "bing/1.html"), "bing/1.html");
+    }
 }
@@ -0,0 +1,258 @@
+//! Per-request completion callbacks (`callback_url`).
+//!
+//! When a job reaches its final outcome the worker POSTs a JSON summary to the
+//! job's callback URL. With `WEBHOOK_SECRET` set, the raw body is signed with
+//! HMAC-SHA256 and sent as `X-Signature: sha256=<hex>`; receivers recompute it
+//! over the exact bytes they received. Non-2xx answers and network errors are
+//! retried with doubling backoff; delivery never affects the job itself.
+//!
+//! Callbacks may not target loopback, private or link-local addresses: literal
+//! hosts are rejected when the request is validated, hostnames are resolved and
+//! checked before every delivery (and the checked addresses pinned), and
+//! redirects are not followed. `WEBHOOK_ALLOW_PRIVATE=true` lifts this for
+//! local development.
+
+use ring::hmac;
+use serde::Serialize;
+use std::net::{IpAddr, SocketAddr};
+use std::time::Duration;
+
+use crate::config::{env_flag, env_parse};
+use crate::notifications::JobEvent;
+
+pub const SIGNATURE_HEADER: &str = "X-Signature";
+
+/// Body POSTed to a job's `callback_url`
+#[derive(Debug, Clone, Serialize)]
+pub struct CallbackPayload {
+    pub task_id: String,
+    /// "completed" or "failed"
+    pub status: String,
+    pub keyword: String,
+    pub engine: String,
+    pub summary: CallbackSummary,
+    /// Unix seconds when the callback was built
+    pub timestamp: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CallbackSummary {
+    pub result_count: usize,
+    pub error: Option<String>,
+    /// GET /crawl/{task_id} for the full result
+    pub task_url: String,
+}
+
+impl From<&JobEvent> for CallbackPayload {
+    fn from(event: &JobEvent) -> Self {
+        Self {
+            task_id: event.task_id.clone(),
+            status: event.status.clone(),
+            keyword: event.keyword.clone(),
+            engine: event.engine.clone(),
+            summary: CallbackSummary {
+                result_count: event.result_count,
+                error: event.error.clone(),
+                task_url: event.task_link(),
+            },
+            timestamp: chrono::Utc::now().timestamp(),
+        }
+    }
+}
+
+/// `sha256=<hex HMAC-SHA256 of body>`
+pub fn sign(secret: &str, body: &[u8]) -> String {
+    let key = hmac::Key::new(hmac::HMAC_SHA256, secret.as_bytes());
+    format!("sha256={}", hex::encode(hmac::sign(&key, body).as_ref()))
+}
+
+/// False for addresses a callback must not reach: loopback, private (RFC 1918,
+/// IPv6 ULA), link-local, carrier-grade NAT, unspecified, broadcast, multicast
+pub fn is_public_ip(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(v4) => {
+            let [a, b, ..] = v4.octets();
+            !(v4.is_loopback()
+                || v4.is_private()
+                || v4.is_link_local()
+                || v4.is_unspecified()
+                || v4.is_broadcast()
+                || v4.is_multicast()
+                || a == 0
+                || (a == 100 && (b & 0xc0) == 64))
+        }
+        IpAddr::V6(v6) => {
+            if let Some(v4) = v6.to_ipv4_mapped() {
+                return is_public_ip(IpAddr::V4(v4));
+            }
+            let first = v6.segments()[0];
+            !(v6.is_loopback()
+                || v6.is_unspecified()
+                || v6.is_multicast()
+                || (first & 0xfe00) == 0xfc00
+                || (first & 0xffc0) == 0xfe80)
+        }
+    }
+}
+
+fn allow_private() -> bool {
+    env_flag("WEBHOOK_ALLOW_PRIVATE", false)
+}
+
+/// `url`'s host without IPv6 brackets
+fn bare_host(url: &reqwest::Url) -> Option<&str> {
+    url.host_str().map(|h| h.trim_start_matches('[').trim_end_matches(']'))
+}
+
+/// Reject callback URLs whose host is `localhost` or a non-public IP literal.
+/// Hostnames are checked again after resolution in [`deliver`].
+pub fn check_callback_host(url: &reqwest::Url) -> Result<(), String> {
+    if allow_private() {
+        return Ok(());
+    }
+    let host = bare_host(url).ok_or_else(|| "must have a host".to_string())?;
+    let blocked = match host.parse::<IpAddr>() {
+        Ok(ip) => !is_public_ip(ip),
+        Err(_) => {
+            let host = host.trim_end_matches('.').to_ascii_lowercase();
+            host == "localhost" || host.ends_with(".localhost")
+        }
+    };
+    if blocked {
+        return Err(format!("host {} is loopback, private or link-local", host));
+    }
+    Ok(())
+}
+
+/// Resolve `url`'s host, failing if any address is non-public
+async fn resolve_public(url: &reqwest::Url) -> anyhow::Result<Vec<SocketAddr>> {
+    let host = bare_host(url).ok_or_else(|| anyhow::anyhow!("callback URL has no host"))?;
+    let port = url.port_or_known_default().unwrap_or(80);
+    let addrs: Vec<SocketAddr> = tokio::net::lookup_host((host, port)).await?.collect();
+    if let Some(addr) = addrs.iter().find(|a| !is_public_ip(a.ip())) {
+        anyhow::bail!("{} resolves to non-public address {}", host, addr.ip());
+    }
+    if addrs.is_empty() {
+        anyhow::bail!("{} did not resolve", host);
+    }
+    Ok(addrs)
+}
+
+/// Deliver `event` to `url` in the background. Retries WEBHOOK_MAX_RETRIES times
+/// (default 3) after the first attempt, waiting WEBHOOK_RETRY_BACKOFF_MS (default 1000)
+/// and doubling; each request times out after WEBHOOK_TIMEOUT_SECS (default 10).
+pub fn spawn_callback(url: String, event: &JobEvent) {
+    let payload = CallbackPayload::from(event);
+    tokio::spawn(async move {
+        if let Err(e) = deliver(&url, &payload).await {
+            eprintln!("⚠️ [Webhook] Callback for task {} to {} failed: {}", payload.task_id, url, e);
+        }
+    });
+}
+
+async fn deliver(url: &str, payload: &CallbackPayload) -> anyhow::Result<()> {
+    let body = serde_json::to_vec(payload)?;
+    let signature = std::env::var("WEBHOOK_SECRET")
+        .ok()
+        .filter(|s| !s.is_empty())
+        .map(|secret| sign(&secret, &body));
+    let mut client = reqwest::Client::builder().timeout(Duration::from_secs(env_parse("WEBHOOK_TIMEOUT_SECS", 10)));
+    if !allow_private() {
+        let parsed = reqwest::Url::parse(url)?;
+        check_callback_host(&parsed).map_err(|e| anyhow::anyhow!(e))?;
+        let addrs = resolve_public(&parsed).await?;
+        if let Some(host) = parsed.host_str().filter(|h| h.parse::<IpAddr>().is_err()) {
+            // Connect to the addresses just checked, not a fresh (possibly rebound) lookup
+            client = client.resolve_to_addrs(host, &addrs);
+        }
+        client = client.redirect(reqwest::redirect::Policy::none());
+    }
+    let client = client.build()?;
+    let max_retries: u32 = env_parse("WEBHOOK_MAX_RETRIES", 3);
+    let mut backoff = Duration::from_millis(env_parse("WEBHOOK_RETRY_BACKOFF_MS", 1000));
+
+    let mut attempt = 0;
+    loop {
+        let mut request = client
+            .post(url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .body(body.clone());
+        if let Some(ref signature) = signature {
+            request = request.header(SIGNATURE_HEADER, signature);
+        }
+        let error = match request.send().await {
+            Ok(resp) if resp.status().is_success() => {
+                println!("📬 [Webhook] Task {} {} -> {} ({})", payload.task_id, payload.status, url, resp.status());
+                return Ok(());
+            }
+            Ok(resp) => format!("HTTP {}", resp.status()),
+            Err(e) => e.to_string(),
+        };
+        if attempt >= max_retries {
+            anyhow::bail!("{} after {} attempt(s)", error, attempt + 1);
+        }
+        attempt += 1;
+        eprintln!("⚠️ [Webhook] {} answered {}, retry {}/{} in {}ms", url, error, attempt, max_retries, backoff.as_millis());
+        tokio::time::sleep(backoff).await;
+        backoff *= 2;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sign_matches_known_hmac() {
+        // RFC 4231 test case 2
+        assert_eq!(
+            sign("Jefe", b"what do ya want for nothing?"),
+            "sha256=5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+
+    #[test]
+    fn test_payload_from_event() {
+        let event = JobEvent {
+            task_id: "t1".to_string(),
+            keyword: "rust".to_string(),
+            engine: "bing".to_string(),
+            status: "completed".to_string(),
+            result_count: 10,
+            error: None,
+        };
+        let payload = serde_json::to_value(CallbackPayload::from(&event)).unwrap();
+        assert_eq!(payload["task_id"], "t1");
+        assert_eq!(payload["status"], "completed");
+        assert_eq!(payload["summary"]["result_count"], 10);
+        assert!(payload["summary"]["task_url"].as_str().unwrap().ends_with("/crawl/t1"));
+    }
+
+    #[test]
+    fn test_is_public_ip() {
+        for ip in ["127.0.0.1", "10.1.2.3", "172.16.0.1", "192.168.1.1", "169.254.169.254", "0.0.0.0", "100.64.0.1", "::1", "fe80::1", "fd00::1", "::ffff:127.0.0.1"] {
+            assert!(!is_public_ip(ip.parse().unwrap()), "{}", ip);
+        }
+        for ip in ["8.8.8.8", "100.128.0.1", "2606:4700::1111"] {
+            assert!(is_public_ip(ip.parse().unwrap()), "{}", ip);
+        }
+    }
+
+    #[test]
+    fn test_check_callback_host() {
+        let check = |u: &str| check_callback_host(&reqwest::Url::parse(u).unwrap());
+        assert!(check("https://hooks.example.com/done").is_ok());
+        assert!(check("https://8.8.8.8/done").is_ok());
+        assert!(check("http://localhost:8080/hook").is_err());
+        assert!(check("http://api.localhost/hook").is_err());
+        assert!(check("http://127.0.0.1/hook").is_err());
+        assert!(check("http://169.254.169.254/latest/meta-data").is_err());
+        assert!(check("http://[::1]:3000/hook").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_resolve_public_rejects_loopback() {
+        let url = reqwest::Url::parse("http://127.0.0.1:9/hook").unwrap();
+        assert!(resolve_public(&url).await.is_err());
+    }
+}
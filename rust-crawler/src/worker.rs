@@ -1,20 +1,60 @@
 use std::sync::Arc;
 use tokio::time::{sleep, Duration};
 use crate::api::AppState;
+use crate::config::{env_flag, env_parse};
 use crate::crawler;
-use crate::queue::CrawlJob;
+use crate::queue::{CrawlJob, QueueManager, TaskStatusEvent};
+use crate::notifications::{JobEvent, JOB_NOTIFIER};
+use crate::signing::RESULT_SIGNER;
 
 pub async fn start_worker(state: Arc<AppState>) {
     println!("👷 Worker started, polling Redis...");
 
+    // CHROME_REAP_ORPHANS (default on): kill Chrome processes that outlive their job
+    // after CHROME_REAP_GRACE_MS; PROCESS_STATS_LOG_SECS (default 300, 0 = off) logs RSS
+    let reap_orphans = env_flag("CHROME_REAP_ORPHANS", true);
+    let reap_grace = Duration::from_millis(env_parse("CHROME_REAP_GRACE_MS", 2000));
+    let stats_every = Duration::from_secs(env_parse("PROCESS_STATS_LOG_SECS", 300));
+    let mut last_stats = std::time::Instant::now();
+
+    // Launch the deep-extraction browser pool up front instead of on the first job
+    if let Some(pool) = crawler::pool::BROWSER_POOL.as_ref() {
+        let _ = tokio::task::spawn_blocking(move || pool.warm_up()).await;
+    }
+
     loop {
+        if !stats_every.is_zero() && last_stats.elapsed() >= stats_every {
+            let stats = crawler::reaper::process_stats();
+            println!(
+                "📈 [Worker] RSS {} MB, {} Chrome process(es) using {} MB",
+                stats.rss_kb / 1024, stats.chrome_processes, stats.chrome_rss_kb / 1024
+            );
+            last_stats = std::time::Instant::now();
+        }
+
+        // Paused via /admin/worker/pause: leave jobs in the queue
+        if state.worker_paused.load(std::sync::atomic::Ordering::Relaxed) {
+            sleep(Duration::from_secs(1)).await;
+            continue;
+        }
+
+        // Requeue failed jobs whose retry backoff has elapsed
+        match state.queue.promote_due_jobs().await {
+            Ok(0) => {}
+            Ok(n) => println!("🔁 [Worker] Requeued {} delayed retr{}", n, if n == 1 { "y" } else { "ies" }),
+            Err(e) => eprintln!("⚠️ [Worker] Failed to promote delayed jobs: {}", e),
+        }
+
         // Poll for 1 job
         match state.queue.pop_job().await {
             Ok(Some(job)) => {
                 println!("👷 [Worker] Picked up job: {} ({})", job.id, job.keyword);
                 if let Err(e) = process_job(state.clone(), job).await {
                     eprintln!("❌ [Worker] Job failed: {}", e);
-                    // TODO: Implement DLQ or Retry here
+                }
+                // Every non-pooled browser from the job has been dropped by now; anything left is leaked
+                if reap_orphans {
+                    crawler::reaper::reap_orphaned_chrome(reap_grace).await;
                 }
             },
             Ok(None) => {
@@ -30,47 +70,229 @@ pub async fn start_worker(state: Arc<AppState>) {
 }
 
 async fn process_job(state: Arc<AppState>, job: CrawlJob) -> anyhow::Result<()> {
+    let (task_id, keyword, engine) = (job.id.clone(), job.keyword.clone(), job.engine.clone());
+    let callback_url = job.callback_url.clone();
+    let queue = state.queue.clone();
+    let pool = state.pool.clone();
+    let mut status_event = TaskStatusEvent::new(&job, "running");
+    queue.publish_task_event(&status_event).await;
+    mark_running(&pool, &job).await;
+    let attempt = job.clone();
+    let outcome = run_job(state, job).await;
+    if attempt.sticky {
+        crate::proxy::PROXY_MANAGER.release_sticky(&attempt.id);
+    }
+
+    // Failures are retried with backoff; only the final outcome reaches webhooks
+    if let Err(ref e) = outcome {
+        let error = e.to_string();
+        let reason = crawler::failure_reason(e).map(|r| r.as_str());
+        let retrying = retry_or_dead_letter(&queue, attempt, e).await;
+        // A waiting retry is back to pending; the row keeps the last error either way
+        let status = if retrying { "pending" } else { "failed" };
+        if let Err(db_err) = crate::db::update_task_status(&pool, &task_id, status, Some(&error), reason).await {
+            eprintln!("⚠️ [Worker] Failed to mark task {} {}: {}", task_id, status, db_err);
+        }
+        if retrying {
+            return outcome.map(|_| ());
+        }
+    }
+
+    let event = match &outcome {
+        Ok(finished) => JobEvent {
+            task_id,
+            keyword,
+            engine,
+            status: if finished.error.is_some() { "failed" } else { "completed" }.to_string(),
+            result_count: finished.result_count,
+            error: finished.error.clone(),
+        },
+        Err(e) => JobEvent {
+            task_id,
+            keyword,
+            engine,
+            status: "failed".to_string(),
+            result_count: 0,
+            error: Some(e.to_string()),
+        },
+    };
+    status_event.status = event.status.clone();
+    status_event.at = chrono::Utc::now().timestamp();
+    queue.publish_task_event(&status_event).await;
+    JOB_NOTIFIER.notify(&event).await;
+    if let Some(url) = callback_url {
+        crate::webhook::spawn_callback(url, &event);
+    }
+
+    outcome.map(|_| ())
+}
+
+/// Flip the task row to running. Jobs queued without a pending row (scheduler,
+/// jobs queued before rows were created at submission) get one here.
+async fn mark_running(pool: &sqlx::PgPool, job: &CrawlJob) {
+    let result = match crate::db::update_task_status(pool, &job.id, "running", None, None).await {
+        Ok(true) => Ok(()),
+        Ok(false) => crate::db::insert_task(pool, job, "running").await,
+        Err(e) => Err(e),
+    };
+    if let Err(e) = result {
+        eprintln!("⚠️ [Worker] Failed to mark task {} running: {}", job.id, e);
+    }
+}
+
+/// Schedule another run of a failed `job` after an exponential backoff, or move it
+/// to the dead-letter queue once it has had MAX_JOB_ATTEMPTS runs. Returns true if
+/// a retry was scheduled.
+async fn retry_or_dead_letter(queue: &QueueManager, mut job: CrawlJob, error: &anyhow::Error) -> bool {
+    job.attempts += 1;
+    let max_attempts = crate::queue::max_job_attempts();
+    if job.attempts < max_attempts {
+        let delay = crate::queue::retry_backoff(job.attempts);
+        let (id, attempts) = (job.id.clone(), job.attempts);
+        match queue.schedule_retry(job, delay).await {
+            Ok(()) => {
+                println!("🔁 [Worker] Job {} retrying in {}s (attempt {}/{})", id, delay.as_secs(), attempts + 1, max_attempts);
+                return true;
+            }
+            Err(e) => {
+                eprintln!("⚠️ [Worker] Failed to schedule retry for {}: {}", id, e);
+                return false;
+            }
+        }
+    }
+
+    println!("🪦 [Worker] Job {} dead-lettered after {} attempts", job.id, job.attempts);
+    let id = job.id.clone();
+    if let Err(e) = queue.dead_letter(job, &error.to_string()).await {
+        eprintln!("⚠️ [Worker] Failed to dead-letter {}: {}", id, e);
+    }
+    false
+}
+
+/// A job that ran to the end and stored its row
+struct FinishedJob {
+    result_count: usize,
+    /// Set when the SERP was stored but every deep extraction failed; the row is
+    /// `failed` and the job is not retried (that would repeat the search)
+    error: Option<String>,
+}
+
+/// Runs a single job end-to-end and returns the number of SERP results found
+async fn run_job(state: Arc<AppState>, job: CrawlJob) -> anyhow::Result<FinishedJob> {
     println!("🚀 [Worker] Processing: {}", job.keyword);
     let pool = state.pool.clone();
     let engine_clone = job.engine.clone();
+    // Sticky jobs bind their proxy to the job id (released by process_job)
+    let session_key = job.sticky.then(|| job.id.clone());
+
+    let search_options = crawler::SearchOptions {
+        engine_options: job.engine_options.clone().unwrap_or_default(),
+        warmup: job.warmup,
+        max_attempts: job.max_attempts,
+        stealth: job.stealth.clone(),
+        pages: job.pages.unwrap_or(1),
+        session_key: session_key.clone(),
+    };
 
     // 1. Search (Google/Bing/Generic)
     let search_results = if job.engine == "google" {
-        crawler::search_google(&job.keyword).await
+        crawler::search_google(&job.keyword, &search_options).await
     } else if job.engine == "generic" {
-        crawler::generic_crawl(&job.keyword, job.selectors).await
+        crawler::generic_crawl(&job.keyword, job.selectors, &job.click_sequence, job.stealth.as_ref(), session_key.as_deref()).await
     } else {
-        crawler::search_bing(&job.keyword).await
+        crawler::search_bing(&job.keyword, &search_options).await
     };
 
-    let serp_data = match search_results {
-        Ok(data) => data,
-        Err(e) => {
-             // Log failure to DB?
-             return Err(e);
-        }
-    };
+    // Failures are recorded on the task row by process_job
+    let mut serp_data = search_results?;
+
+    // Post-filter SERP results by URL pattern (patterns were validated at submit time)
+    match crawler::ResultFilter::new(&job.result_include_patterns, &job.result_exclude_patterns) {
+        Ok(filter) => filter.apply(&mut serp_data),
+        Err(e) => eprintln!("⚠️ [Worker] Ignoring invalid result filter: {}", e),
+    }
 
-    // 2. Extract Content (Deep Crawl)
-    let first_result_data: Option<crawler::WebsiteData> = if let Some(first_result) = serp_data.results.first() {
-        println!("🔍 [Worker] Deep extracting: {}", first_result.link);
-        crawler::extract_website_data(&first_result.link).await.ok()
+    // 2. Extract Content (Deep Crawl) for the top max_deep_extract results, skipped for SERP-only jobs
+    let (mut pages, extract_errors) = if !job.deep_crawl {
+        println!("⏭️ [Worker] deep_crawl=false, storing SERP only");
+        (Vec::new(), DeepExtractErrors::default())
     } else {
-        None
+        let extract_options = crawler::ExtractOptions {
+            capture_thumbnail: job.capture_thumbnail,
+            capture_screenshot: job.screenshot,
+            referer: job.referer.clone(),
+            min_word_count: crate::config::env_parse("EXTRACT_MIN_WORD_COUNT", 0u32),
+            extract_entities: job.extract_entities,
+            stealth: job.stealth.clone(),
+            session_key: session_key.clone(),
+            respect_robots: job.respect_robots,
+            allow_file_urls: env_flag("ALLOW_FILE_URLS", false),
+            ..Default::default()
+        };
+        deep_extract_results(&serp_data.results, &extract_options, job.max_deep_extract as usize).await
     };
+    // Every extraction failed: keep the SERP, but mark the task failed and record why
+    // the page columns are empty (robots exclusions are a choice, not a failure)
+    let (error_message, failure_reason) = if !pages.is_empty() {
+        (None, None)
+    } else if !extract_errors.failed.is_empty() {
+        (Some(format!("Deep extract failed: {}", extract_errors.failed.join("; "))), Some("extract_failed"))
+    } else if !extract_errors.robots_disallowed.is_empty() {
+        (Some("Every result URL is disallowed by robots.txt".to_string()), Some("robots_disallowed"))
+    } else {
+        (None, None)
+    };
+    serp_data.robots_disallowed = extract_errors.robots_disallowed;
 
-    let results_json = serde_json::to_string(&serp_data).unwrap_or_default();
+    // 2a. Upload each page's PNG screenshot (screenshot=true); the key stays on the page
+    if let Some(storage) = &state.storage {
+        for (index, data) in pages.iter_mut() {
+            let Some(image) = data.screenshot.take() else { continue };
+            let key = crate::storage::object_key(job.storage_prefix.as_deref(), &crate::api::screenshot_object_key(&job.engine, &job.id, *index));
+            match storage.store_bytes(&key, image, "image/png").await {
+                Ok(_) => {
+                    println!("📸 [Worker] Screenshot saved to MinIO: {}", key);
+                    data.screenshot_key = Some(key);
+                }
+                Err(e) => eprintln!("⚠️ [Worker] Screenshot upload failed: {}", e),
+            }
+        }
+    }
+    // The task row's page columns come from the best-ranked page that extracted
+    let first_result_data: Option<crawler::WebsiteData> = pages.first().map(|(_, data)| data.clone());
+
+    // 3. Save each page's raw HTML to MinIO under {engine}/{job_id}/{index}.html
+    if let Some(storage) = &state.storage {
+        for (index, data) in pages.iter_mut().filter(|(_, d)| !d.html.is_empty()) {
+            let s3_key = crate::storage::object_key(job.storage_prefix.as_deref(), &format!("{}/{}/{}.html", job.engine, job.id, index));
+            match storage.store_html(&s3_key, &data.html).await {
+                Ok(statuses) => {
+                    let stored: Vec<_> = statuses.iter().filter(|s| s.ok).map(|s| s.backend.as_str()).collect();
+                    println!("💾 [Worker] HTML saved: {} -> {}/{} backends ({})", s3_key, stored.len(), statuses.len(), stored.join(", "));
+                    data.html_key = Some(s3_key);
+                }
+                Err(e) => eprintln!("⚠️ [Worker] HTML upload failed: {}", e),
+            }
+        }
+    }
+    serp_data.pages = pages.into_iter().map(|(_, data)| data).collect();
+    // Recorded outside results_json so the RESULT_MAX_BYTES cap can't lose track of stored objects
+    let page_keys: serde_json::Value = serp_data
+        .pages
+        .iter()
+        .map(|p| serde_json::json!({ "html_key": p.html_key, "screenshot_key": p.screenshot_key }))
+        .collect();
 
-    // 3. Save to MinIO (Raw HTML)
-    // Example: Store first page HTML if exists
-    if let Some(ref data) = first_result_data {
-        if !data.html.is_empty() {
-            let s3_key = format!("{}/{}.html", job.engine, job.id);
-            if let Err(e) = state.storage.store_html(&s3_key, &data.html).await {
-                eprintln!("⚠️ [Worker] MinIO upload failed: {}", e);
-            } else {
-                println!("💾 [Worker] HTML saved to MinIO: {}", s3_key);
+    // 3a. Save the page thumbnail to MinIO (only when requested)
+    let mut thumbnail_key: Option<String> = None;
+    if let (Some(image), Some(storage)) = (first_result_data.as_ref().and_then(|d| d.thumbnail.clone()), &state.storage) {
+        let key = crate::storage::object_key(job.storage_prefix.as_deref(), &format!("{}/{}-thumb.jpg", job.engine, job.id));
+        match storage.store_bytes(&key, image, "image/jpeg").await {
+            Ok(_) => {
+                println!("🖼️ [Worker] Thumbnail saved to MinIO: {}", key);
+                thumbnail_key = Some(key);
             }
+            Err(e) => eprintln!("⚠️ [Worker] Thumbnail upload failed: {}", e),
         }
     }
 
@@ -81,6 +303,11 @@ async fn process_job(state: Arc<AppState>, job: CrawlJob) -> anyhow::Result<()>
         // We call the Python Sidecar on localhost:8000
         let entities = crate::ml::extract_entities_remote(&data.main_text).await;
         let category = crate::ml::classify_content_remote(&data.main_text).await;
+        // Sidecar NER plus the local mentions (if requested) it didn't find
+        let entities = match entities {
+            None if data.entities.is_empty() => serde_json::Value::Null,
+            remote => serde_json::to_value(crate::ml::merge_entities(remote.unwrap_or_default(), &data.entities)).unwrap_or_default(),
+        };
 
         (
             data.main_text.clone(),
@@ -93,7 +320,7 @@ async fn process_job(state: Arc<AppState>, job: CrawlJob) -> anyhow::Result<()>
             serde_json::to_value(&data.outbound_links).unwrap_or_default(),
             serde_json::to_value(&data.images).unwrap_or_default(),
             data.sentiment.clone(),
-            serde_json::to_value(&entities).unwrap_or_default(), // New: Entities
+            entities, // New: Entities
             category, // New: Category
             serde_json::to_value(&data.marketing_data).unwrap_or_default(), // New: Marketing Data
         )
@@ -115,7 +342,57 @@ async fn process_job(state: Arc<AppState>, job: CrawlJob) -> anyhow::Result<()>
         )
     };
 
+    // Redirect hops of the deep-extracted page (STORE_REDIRECT_CHAIN, default true)
+    let redirect_chain = first_result_data
+        .as_ref()
+        .filter(|d| !d.redirect_chain.is_empty() && env_flag("STORE_REDIRECT_CHAIN", true))
+        .and_then(|d| serde_json::to_value(&d.redirect_chain).ok());
+
+    let extracted_markdown = first_result_data
+        .as_ref()
+        .map(|d| d.main_markdown.clone())
+        .filter(|m| !m.is_empty());
+
+    // Related searches feed child jobs even if the size cap drops them from the row
+    let related_searches = if job.crawl_related { serp_data.related_searches.clone() } else { Vec::new() };
+
+    // 3b. Full results JSON to MinIO, before the row cap below trims it
+    let mut results_key: Option<String> = None;
+    if let Some(storage) = &state.storage {
+        let key = crate::storage::object_key(job.storage_prefix.as_deref(), &crate::api::results_object_key(&job.engine, &job.id));
+        match serde_json::to_string(&serp_data) {
+            Ok(json) => match storage.store_json(&key, &json).await {
+                Ok(_) => {
+                    println!("💾 [Worker] Results JSON saved: {}", key);
+                    results_key = Some(key);
+                }
+                Err(e) => eprintln!("⚠️ [Worker] Results JSON upload failed: {}", e),
+            },
+            Err(e) => eprintln!("⚠️ [Worker] Failed to serialize results: {}", e),
+        }
+    }
+
+    // Keep row sizes bounded: drop low-priority fields until the result fits
+    let max_bytes = env_parse("RESULT_MAX_BYTES", 1_000_000usize);
+    let mut row = RowColumns {
+        extracted_text,
+        first_page_html: extracted_html,
+        extracted_markdown,
+        emails,
+        phones,
+        links,
+        images,
+        marketing,
+        entities,
+    };
+    serp_data.truncated_fields = enforce_result_cap(max_bytes, &mut serp_data, &mut row);
+    if !serp_data.truncated_fields.is_empty() {
+        println!("✂️ [Worker] Result over {} bytes, dropped: {:?}", max_bytes, serp_data.truncated_fields);
+    }
+    let results_json = serde_json::to_string(&serp_data).unwrap_or_default();
+
     // 4. Save to DB
+    let status = if failure_reason == Some("extract_failed") { "failed" } else { "completed" };
     // 4. Save to DB with Workaround for Supabase
     let mut conn = pool.acquire().await?;
     // Workaround: generic deallocate to prevent "prepared statement already exists"
@@ -127,32 +404,148 @@ async fn process_job(state: Arc<AppState>, job: CrawlJob) -> anyhow::Result<()>
             id, keyword, engine, status, results_json, 
             extracted_text, first_page_html, meta_description, meta_author, meta_date,
             emails, phone_numbers, outbound_links, images, sentiment,
-            entities, category, marketing_data
+            entities, category, marketing_data, thumbnail_key, parent_task_id, metadata, batch_id,
+            redirect_chain, error_message, failure_reason, extracted_markdown, results_key, page_keys
         ) 
-        VALUES ($1, $2, $3, 'completed', $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17)
+        VALUES ($1, $2, $3, $27, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19, $20, $21, $22, $23, $24, $25, $26, $28)
+        ON CONFLICT (id) DO UPDATE SET
+            status = EXCLUDED.status, results_json = EXCLUDED.results_json,
+            extracted_text = EXCLUDED.extracted_text, first_page_html = EXCLUDED.first_page_html,
+            meta_description = EXCLUDED.meta_description, meta_author = EXCLUDED.meta_author, meta_date = EXCLUDED.meta_date,
+            emails = EXCLUDED.emails, phone_numbers = EXCLUDED.phone_numbers, outbound_links = EXCLUDED.outbound_links,
+            images = EXCLUDED.images, sentiment = EXCLUDED.sentiment, entities = EXCLUDED.entities,
+            category = EXCLUDED.category, marketing_data = EXCLUDED.marketing_data, thumbnail_key = EXCLUDED.thumbnail_key,
+            redirect_chain = EXCLUDED.redirect_chain, error_message = EXCLUDED.error_message,
+            failure_reason = EXCLUDED.failure_reason, extracted_markdown = EXCLUDED.extracted_markdown,
+            results_key = EXCLUDED.results_key, page_keys = EXCLUDED.page_keys
         "#
     )
     .bind(&job.id)
     .bind(&job.keyword)
     .bind(&job.engine)
     .bind(&results_json)
-    .bind(&extracted_text)
-    .bind(&extracted_html)
+    .bind(&row.extracted_text)
+    .bind(&row.first_page_html)
     .bind(&md)
     .bind(&ma)
     .bind(&mdate)
-    .bind(&emails)
-    .bind(&phones)
-    .bind(&links)
-    .bind(&images)
+    .bind(&row.emails)
+    .bind(&row.phones)
+    .bind(&row.links)
+    .bind(&row.images)
     .bind(&sentiment)
-    .bind(&entities)
+    .bind(&row.entities)
     .bind(&category)
-    .bind(&marketing)
+    .bind(&row.marketing)
+    .bind(&thumbnail_key)
+    .bind(&job.parent_task_id)
+    .bind(&job.metadata)
+    .bind(&job.batch_id)
+    .bind(&redirect_chain)
+    .bind(&error_message)
+    .bind(failure_reason)
+    .bind(&row.extracted_markdown)
+    .bind(&results_key)
+    .bind(status)
+    .bind(&page_keys)
     .execute(&mut *conn)
     .await?;
 
-    println!("✅ [Worker] Job {} completed successfully!", job.id);
+    // 4a. Child rows for each SERP result (single multi-row INSERT per batch)
+    if !serp_data.results.is_empty() {
+        if let Err(e) = crate::db::insert_task_results(&mut *conn, &job.id, &serp_data.results).await {
+            eprintln!("⚠️ [Worker] Failed to store result rows: {}", e);
+        }
+    }
+
+    // 4b. Sign the stored HTML + results for tamper-evidence (if RESULT_SIGNING_KEY is set)
+    if let Some(signer) = RESULT_SIGNER.as_ref() {
+        let sig = signer.sign(&job.id, &row.first_page_html, &results_json);
+        if let Err(e) = sqlx::query("UPDATE tasks SET signature = $1, signed_at = $2, signature_key_id = $3 WHERE id = $4")
+            .bind(&sig.signature)
+            .bind(&sig.signed_at)
+            .bind(&sig.key_id)
+            .bind(&job.id)
+            .execute(&mut *conn)
+            .await
+        {
+            eprintln!("⚠️ [Worker] Failed to store result signature: {}", e);
+        }
+    }
+
+    println!("✅ [Worker] Job {} {}", job.id, if status == "failed" { "stored SERP, but every deep extraction failed" } else { "completed successfully!" });
+
+    // 4c. Expand related searches into child jobs (one level deep); each counts
+    // against DAILY_CRAWL_LIMIT like a submitted crawl
+    if job.crawl_related && job.parent_task_id.is_none() {
+        let max_children = env_parse("RELATED_CRAWL_MAX", 5usize);
+        let daily_limit = env_parse("DAILY_CRAWL_LIMIT", 0u64);
+        for related in related_searches.iter().filter(|s| !s.trim().is_empty()).take(max_children) {
+            let mut quota = None;
+            if daily_limit > 0 {
+                match state.queue.reserve_daily_crawl(daily_limit).await {
+                    Ok(Some(reservation)) => quota = Some(reservation),
+                    Ok(None) => {
+                        println!("🚫 [Worker] Daily crawl limit of {} reached, not queuing more related crawls for {}", daily_limit, job.id);
+                        break;
+                    }
+                    Err(e) => eprintln!("⚠️ [Worker] Quota check failed, queuing related crawl anyway: {}", e),
+                }
+            }
+            let child = CrawlJob {
+                id: uuid::Uuid::new_v4().to_string(),
+                user_id: job.user_id.clone(),
+                keyword: related.trim().to_string(),
+                engine: job.engine.clone(),
+                selectors: None,
+                engine_options: job.engine_options.clone(),
+                result_include_patterns: job.result_include_patterns.clone(),
+                result_exclude_patterns: job.result_exclude_patterns.clone(),
+                capture_thumbnail: job.capture_thumbnail,
+                screenshot: job.screenshot,
+                warmup: job.warmup,
+                crawl_related: false,
+                parent_task_id: Some(job.id.clone()),
+                referer: job.referer.clone(),
+                metadata: job.metadata.clone(),
+                click_sequence: vec![],
+                tag: job.tag.clone(),
+                max_attempts: job.max_attempts,
+                deep_crawl: job.deep_crawl,
+                batch_id: job.batch_id.clone(),
+                priority: job.priority,
+                storage_prefix: job.storage_prefix.clone(),
+                extract_entities: job.extract_entities,
+                stealth: job.stealth.clone(),
+                max_deep_extract: job.max_deep_extract,
+                pages: job.pages,
+                attempts: 0,
+                sticky: job.sticky,
+                respect_robots: job.respect_robots,
+                callback_url: None,
+                enqueued_at: 0,
+            };
+            let child_id = child.id.clone();
+            if let Err(e) = crate::db::insert_task(&pool, &child, "pending").await {
+                eprintln!("⚠️ [Worker] Failed to record pending related crawl {}: {}", child_id, e);
+            }
+            match state.queue.push_job(child).await {
+                Ok(_) => println!("🌱 [Worker] Queued related crawl {} ('{}') for {}", child_id, related.trim(), job.id),
+                Err(e) => {
+                    eprintln!("⚠️ [Worker] Failed to queue related crawl '{}': {}", related, e);
+                    let error = format!("Failed to queue job: {}", e);
+                    if let Err(e) = crate::db::update_task_status(&pool, &child_id, "failed", Some(&error), None).await {
+                        eprintln!("⚠️ [Worker] Failed to mark related crawl {} failed: {}", child_id, e);
+                    }
+                    if let Some(ref reservation) = quota {
+                        if let Err(e) = state.queue.release_daily_crawl(reservation).await {
+                            eprintln!("⚠️ [Worker] Failed to release quota for {}: {}", child_id, e);
+                        }
+                    }
+                }
+            }
+        }
+    }
 
     // 5. Send Notification
     // We manually insert into DB because the worker doesn't have the API state/auth/endpoints handy, 
@@ -171,5 +564,210 @@ async fn process_job(state: Arc<AppState>, job: CrawlJob) -> anyhow::Result<()>
     .execute(&pool) // using the pool clone
     .await;
 
-    Ok(())
+    Ok(FinishedJob {
+        result_count: serp_data.results.len(),
+        error: error_message.filter(|_| status == "failed"),
+    })
+}
+
+fn json_is_empty(value: &serde_json::Value) -> bool {
+    match value {
+        serde_json::Value::Null => true,
+        serde_json::Value::Array(a) => a.is_empty(),
+        serde_json::Value::Object(o) => o.is_empty(),
+        _ => false,
+    }
+}
+
+/// Why deep extraction produced no page for some results
+#[derive(Default)]
+struct DeepExtractErrors {
+    /// "#index: error" per failed extraction
+    failed: Vec<String>,
+    /// URLs skipped under `respect_robots`
+    robots_disallowed: Vec<String>,
+}
+
+/// Deep-extract the first `max` results, running at most DEEP_EXTRACT_CONCURRENCY
+/// (default 3) browsers at once. Failed pages are skipped; the rest come back as
+/// (SERP index, data) in rank order, alongside the skipped pages' errors.
+/// Only the top result captures a thumbnail.
+async fn deep_extract_results(
+    results: &[crawler::SearchResult],
+    options: &crawler::ExtractOptions,
+    max: usize,
+) -> (Vec<(usize, crawler::WebsiteData)>, DeepExtractErrors) {
+    let concurrency = env_parse("DEEP_EXTRACT_CONCURRENCY", 3usize).max(1);
+    let mut pending = results.iter().take(max).map(|r| r.link.clone()).enumerate();
+    let mut tasks = tokio::task::JoinSet::new();
+    let mut pages = Vec::new();
+    let mut errors = DeepExtractErrors::default();
+
+    loop {
+        while tasks.len() < concurrency {
+            let Some((index, url)) = pending.next() else { break };
+            let options = crawler::ExtractOptions { capture_thumbnail: options.capture_thumbnail && index == 0, ..options.clone() };
+            tasks.spawn(async move {
+                println!("🔍 [Worker] Deep extracting #{}: {}", index, url);
+                (index, crawler::extract_website_data_with_fallback(&url, &options).await)
+            });
+        }
+        let Some(joined) = tasks.join_next().await else { break };
+        match joined {
+            Ok((index, Ok(data))) => pages.push((index, data)),
+            Ok((index, Err(e))) => match e.downcast_ref::<crawler::robots::RobotsDisallowed>() {
+                Some(disallowed) => errors.robots_disallowed.push(disallowed.url.clone()),
+                None => {
+                    eprintln!("⚠️ [Worker] Deep extract #{} failed: {}", index, e);
+                    errors.failed.push(format!("#{}: {}", index, e));
+                }
+            },
+            Err(e) => {
+                eprintln!("⚠️ [Worker] Deep extract task aborted: {}", e);
+                errors.failed.push(format!("aborted: {}", e));
+            }
+        }
+    }
+
+    pages.sort_by_key(|(index, _)| *index);
+    (pages, errors)
+}
+
+/// The task row's bulky columns besides `results_json`, sized and trimmed together
+/// by [`enforce_result_cap`]
+struct RowColumns {
+    extracted_text: String,
+    first_page_html: String,
+    extracted_markdown: Option<String>,
+    emails: serde_json::Value,
+    phones: serde_json::Value,
+    links: serde_json::Value,
+    images: serde_json::Value,
+    marketing: serde_json::Value,
+    entities: serde_json::Value,
+}
+
+/// Bytes stored for a task: serialized `results_json` plus every other large column
+fn result_size(serp: &crawler::SerpData, row: &RowColumns) -> usize {
+    let json_columns = [&row.emails, &row.phones, &row.links, &row.images, &row.marketing, &row.entities];
+    serde_json::to_string(serp).map(|s| s.len()).unwrap_or(0)
+        + json_columns.iter().map(|v| v.to_string().len()).sum::<usize>()
+        + row.extracted_text.len()
+        + row.first_page_html.len()
+        + row.extracted_markdown.as_ref().map_or(0, |m| m.len())
+}
+
+/// Shorten `text` by up to `excess` bytes, on a char boundary. Returns whether it changed.
+fn truncate_by(text: &mut String, excess: usize) -> bool {
+    if excess == 0 || text.is_empty() {
+        return false;
+    }
+    let mut len = text.len().saturating_sub(excess);
+    while !text.is_char_boundary(len) {
+        len -= 1;
+    }
+    text.truncate(len);
+    true
+}
+
+/// Trim the lowest-priority data until the task fits in `max_bytes`: the raw page
+/// HTML first (the full copy is in object storage), then outbound links, images and
+/// the other JSON fields, and finally the page's Markdown and text, which are cut
+/// short rather than dropped. Returns the names of the trimmed fields.
+fn enforce_result_cap(max_bytes: usize, serp: &mut crawler::SerpData, row: &mut RowColumns) -> Vec<String> {
+    const DROP_ORDER: &[&str] = &[
+        "first_page_html",
+        "outbound_links",
+        "images",
+        "pages",
+        "filtered_results",
+        "related_searches",
+        "people_also_ask",
+        "marketing_data",
+        "entities",
+        "extracted_markdown",
+        "extracted_text",
+    ];
+
+    let mut truncated = Vec::new();
+    for field in DROP_ORDER {
+        let excess = result_size(serp, row).saturating_sub(max_bytes);
+        if excess == 0 {
+            break;
+        }
+        let dropped = match *field {
+            "first_page_html" => truncate_by(&mut row.first_page_html, excess),
+            "outbound_links" => !json_is_empty(&std::mem::replace(&mut row.links, serde_json::json!([]))),
+            "images" => !json_is_empty(&std::mem::replace(&mut row.images, serde_json::json!([]))),
+            "pages" => !std::mem::take(&mut serp.pages).is_empty(),
+            "filtered_results" => !std::mem::take(&mut serp.filtered_results).is_empty(),
+            "related_searches" => !std::mem::take(&mut serp.related_searches).is_empty(),
+            "people_also_ask" => !std::mem::take(&mut serp.people_also_ask).is_empty(),
+            "marketing_data" => !json_is_empty(&std::mem::replace(&mut row.marketing, serde_json::json!({}))),
+            "entities" => !json_is_empty(&std::mem::replace(&mut row.entities, serde_json::json!([]))),
+            "extracted_markdown" => row.extracted_markdown.as_mut().is_some_and(|m| truncate_by(m, excess)),
+            "extracted_text" => truncate_by(&mut row.extracted_text, excess),
+            _ => false,
+        };
+        if dropped {
+            truncated.push(field.to_string());
+        }
+    }
+    truncated
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn columns(text: &str, html: &str, links: usize) -> RowColumns {
+        RowColumns {
+            extracted_text: text.to_string(),
+            first_page_html: html.to_string(),
+            extracted_markdown: Some(text.to_string()),
+            emails: serde_json::json!([]),
+            phones: serde_json::json!([]),
+            links: serde_json::json!(vec!["https://example.com/some/outbound/link"; links]),
+            images: serde_json::json!(["https://example.com/a.png"]),
+            marketing: serde_json::json!({}),
+            entities: serde_json::json!([]),
+        }
+    }
+
+    #[test]
+    fn test_result_cap_drop_order() {
+        let mut serp = crawler::SerpData {
+            results: vec![crawler::SearchResult { title: "t".into(), link: "https://example.com".into(), snippet: "s".into() }],
+            pages: vec![crawler::WebsiteData { main_text: "p".repeat(20_000), ..Default::default() }],
+            ..Default::default()
+        };
+        let mut row = columns(&"t".repeat(5_000), &"<p>".repeat(1_000), 500);
+        assert!(result_size(&serp, &row) > 40_000);
+
+        let truncated = enforce_result_cap(12_000, &mut serp, &mut row);
+        assert_eq!(truncated, vec!["first_page_html", "outbound_links", "images", "pages"]);
+        assert!(result_size(&serp, &row) <= 12_000);
+        assert!(row.first_page_html.is_empty());
+        // Stopped before touching the page text
+        assert_eq!(row.extracted_text.len(), 5_000);
+        assert_eq!(serp.results.len(), 1);
+    }
+
+    #[test]
+    fn test_result_cap_cuts_text_last() {
+        let mut serp = crawler::SerpData::default();
+        let mut row = columns(&"é".repeat(10_000), "", 0);
+        row.images = serde_json::json!([]);
+        let max_bytes = result_size(&serp, &row) - 25_000;
+
+        let truncated = enforce_result_cap(max_bytes, &mut serp, &mut row);
+        assert_eq!(truncated, vec!["extracted_markdown", "extracted_text"]);
+        assert!(result_size(&serp, &row) <= max_bytes);
+        assert!(row.extracted_markdown.as_deref().unwrap().is_empty());
+        // Cut on a char boundary, not dropped
+        assert!(!row.extracted_text.is_empty() && row.extracted_text.chars().all(|c| c == 'é'));
+
+        let mut small = columns("short", "<p>", 0);
+        assert!(enforce_result_cap(1_000_000, &mut crawler::SerpData::default(), &mut small).is_empty());
+    }
 }